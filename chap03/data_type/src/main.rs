@@ -1,5 +1,8 @@
 use std::io;
+use std::num::{Saturating, Wrapping};
 
+// the bindings only show the syntax, so most of them are never read
+#[allow(unused_variables)]
 fn main() {
     
     /*
@@ -24,6 +27,9 @@ fn main() {
     let truncated = -5 / 3;      // -1
     let remainder = 43 % 5;
 
+    // integer overflow: panics in debug builds, wraps in release; say which one you mean
+    saturating_demo();
+
     // boolean
     let t = true;
     let f: bool = false;
@@ -81,3 +87,75 @@ fn main() {
     println!("The value of the element at index {index} is: {element}");
 
 }
+
+
+fn saturating_demo() {
+    // saturating: stops at the type's MIN / MAX
+    assert_eq!(u32::MAX.saturating_add(1), u32::MAX);
+    assert_eq!(0u32.saturating_sub(1), 0);
+    let level = Saturating(250u8) + Saturating(10);
+    println!("Saturating(250u8) + 10 = {level}");
+
+    // wrapping: modulo 2^N, like the hardware does
+    assert_eq!(Wrapping(u32::MAX) + Wrapping(1), Wrapping(0));
+    println!("Wrapping(0u8) - 1 = {}", Wrapping(0u8) - Wrapping(1));
+
+    // checked: None instead of a wrong answer
+    assert_eq!(u32::MAX.checked_add(1), None);
+    assert_eq!(5u32.checked_sub(3), Some(2));
+    match 200u8.checked_mul(2) {
+        Some(n) => println!("200u8 * 2 = {n}"),
+        None => println!("200u8 * 2 overflows a u8"),
+    }
+
+    println!("distance between 3 and 7 = {}", distance_no_overflow(3, 7));
+    println!("u32::MAX / 2 scaled by 3 = {}", scale_clamped(u32::MAX / 2, 3));
+}
+
+// |a - b| without ever going below zero: one of the two subtractions saturates to 0
+fn distance_no_overflow(a: u32, b: u32) -> u32 {
+    a.saturating_sub(b).max(b.saturating_sub(a))
+}
+
+// value * scale, or u32::MAX if that doesn't fit
+fn scale_clamped(value: u32, scale: u32) -> u32 {
+    value.saturating_mul(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saturating_wrapping_and_checked_at_the_edges() {
+        assert_eq!(u32::MAX.saturating_add(1), u32::MAX);
+        assert_eq!(u32::MIN.saturating_sub(1), u32::MIN);
+        assert_eq!(Saturating(u32::MAX) + Saturating(1), Saturating(u32::MAX));
+        assert_eq!(Saturating(i32::MIN) - Saturating(1), Saturating(i32::MIN));
+
+        assert_eq!(Wrapping(u32::MAX) + Wrapping(1), Wrapping(0));
+        assert_eq!(Wrapping(0u32) - Wrapping(1), Wrapping(u32::MAX));
+
+        assert_eq!(u32::MAX.checked_add(1), None);
+        assert_eq!(u32::MAX.checked_add(0), Some(u32::MAX));
+        assert_eq!(0u32.checked_sub(1), None);
+    }
+
+    #[test]
+    fn distance_either_way_round() {
+        assert_eq!(distance_no_overflow(3, 7), 4);
+        assert_eq!(distance_no_overflow(7, 3), 4);
+        assert_eq!(distance_no_overflow(5, 5), 0);
+        assert_eq!(distance_no_overflow(0, u32::MAX), u32::MAX);
+        assert_eq!(distance_no_overflow(u32::MAX, 0), u32::MAX);
+    }
+
+    #[test]
+    fn scaling_clamps_at_max() {
+        assert_eq!(scale_clamped(10, 3), 30);
+        assert_eq!(scale_clamped(u32::MAX / 2, 2), u32::MAX - 1);
+        assert_eq!(scale_clamped(u32::MAX / 2, 3), u32::MAX);
+        assert_eq!(scale_clamped(u32::MAX, u32::MAX), u32::MAX);
+        assert_eq!(scale_clamped(u32::MAX, 0), 0);
+    }
+}
@@ -0,0 +1,100 @@
+// Counts heap allocations, to see which of the values in main.rs actually own heap memory.
+//
+// Every allocation in the program goes through the #[global_allocator] below, which hands
+//    the real work to the system allocator and only bumps a few counters on the way.
+//    The counters are per thread, so a count measured on one thread (a test, say)
+//    isn't disturbed by what other threads allocate at the same time.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+pub struct CountingAllocator;
+
+thread_local! {
+    // const-initialized: a lazily initialized thread_local could itself allocate,
+    //    which would recurse straight back into the allocator
+    static ALLOC_COUNT: Cell<usize> = const { Cell::new(0) };
+    static DEALLOC_COUNT: Cell<usize> = const { Cell::new(0) };
+    static ALLOC_BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+// try_with: while a thread is shutting down its thread_locals may already be gone,
+//    but it can still allocate; those allocations just aren't counted
+fn bump(counter: &'static std::thread::LocalKey<Cell<usize>>, by: usize) {
+    let _ = counter.try_with(|c| c.set(c.get() + by));
+}
+
+// realloc isn't overridden: the default implementation is alloc + copy + dealloc,
+//    so growing a Vec shows up as one more allocation and one more deallocation
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        bump(&ALLOC_COUNT, 1);
+        bump(&ALLOC_BYTES, layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        bump(&DEALLOC_COUNT, 1);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator;
+
+// Allocations made by this thread since the last reset_count
+pub fn allocation_count() -> usize {
+    ALLOC_COUNT.with(Cell::get)
+}
+
+pub fn deallocation_count() -> usize {
+    DEALLOC_COUNT.with(Cell::get)
+}
+
+// Total bytes requested by this thread's allocations (frees don't subtract)
+pub fn allocated_bytes() -> usize {
+    ALLOC_BYTES.with(Cell::get)
+}
+
+pub fn reset_count() {
+    ALLOC_COUNT.with(|c| c.set(0));
+    DEALLOC_COUNT.with(|c| c.set(0));
+    ALLOC_BYTES.with(|c| c.set(0));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_zeroes_the_counters() {
+        let s = String::from("something on the heap");
+        drop(s);
+        assert!(allocation_count() > 0);
+
+        reset_count();
+        assert_eq!(allocation_count(), 0);
+        assert_eq!(deallocation_count(), 0);
+        assert_eq!(allocated_bytes(), 0);
+    }
+
+    #[test]
+    fn vec_allocates_and_frees_once() {
+        reset_count();
+        let v = vec![1u64, 2, 3];
+        assert_eq!(allocation_count(), 1);
+        assert_eq!(allocated_bytes(), 3 * 8);
+        drop(v);
+        assert_eq!(deallocation_count(), 1);
+    }
+
+    #[test]
+    fn stack_values_do_not_allocate() {
+        reset_count();
+        let x = 5;
+        let array = [x; 16];
+        let empty: Vec<i32> = Vec::new();
+        assert_eq!(array.len() + empty.len(), 16);
+        assert_eq!(allocation_count(), 0);
+    }
+}
@@ -0,0 +1,108 @@
+// Building an array one element at a time, for element types that are neither Copy
+//    (so `[value; N]` doesn't work) nor Default (so there's nothing to overwrite).
+//
+// MaybeUninit<T> is memory big enough for a T that may not hold one yet; it never drops
+//    what's inside. So ownership has to be tracked by hand: exactly the elements written
+//    so far are owned, and if `f` panics halfway those, and only those, must be dropped.
+
+use std::mem::MaybeUninit;
+use std::ptr;
+
+// Owns the first `initialized` elements while the array is being filled
+struct PartialArray<'a, T, const N: usize> {
+    array: &'a mut [MaybeUninit<T>; N],
+    initialized: usize,
+}
+
+impl<T, const N: usize> Drop for PartialArray<'_, T, N> {
+    // only runs if f panicked: on success the guard is forgotten
+    fn drop(&mut self) {
+        for slot in &mut self.array[..self.initialized] {
+            // SAFETY: slots below `initialized` were written and not yet moved out
+            unsafe { slot.assume_init_drop() };
+        }
+    }
+}
+
+pub fn init_array<T, F: Fn(usize) -> T, const N: usize>(f: F) -> [T; N] {
+    // `[MaybeUninit::<T>::uninit(); N]` needs MaybeUninit<T>: Copy, i.e. T: Copy;
+    //    a const block is repeated without copying
+    let mut array = [const { MaybeUninit::<T>::uninit() }; N];
+    let mut guard = PartialArray { array: &mut array, initialized: 0 };
+
+    for i in 0..N {
+        let value = f(i);
+        // SAFETY: the slot is in bounds and uninitialized, so nothing is overwritten
+        unsafe { ptr::write(guard.array[i].as_mut_ptr(), value) };
+        guard.initialized += 1;
+    }
+    // every element is written; the array owns them from here on
+    std::mem::forget(guard);
+
+    // mem::transmute refuses [MaybeUninit<T>; N] -> [T; N] for a generic N (it can't check
+    //    the sizes match), so read it out through a pointer cast instead. MaybeUninit<T> has
+    //    the same layout as T, and `array` itself never drops anything, so nothing is freed twice.
+    // SAFETY: all N elements were initialized above
+    unsafe { ptr::read(&array as *const [MaybeUninit<T>; N] as *const [T; N]) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    // Counts how many of these have been dropped, to catch leaks and double drops
+    struct DropTracker {
+        index: usize,
+        drops: Rc<Cell<usize>>,
+    }
+
+    impl Drop for DropTracker {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn every_element_comes_from_f() {
+        let squares: [String; 4] = init_array(|i| (i * i).to_string());
+        assert_eq!(squares, ["0", "1", "4", "9"]);
+
+        let empty: [String; 0] = init_array(|_| unreachable!());
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn each_element_is_dropped_exactly_once() {
+        let drops = Rc::new(Cell::new(0));
+        let array: [DropTracker; 5] = init_array(|index| DropTracker { index, drops: Rc::clone(&drops) });
+        assert_eq!(array.iter().map(|t| t.index).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(drops.get(), 0);
+
+        drop(array);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn panic_drops_only_the_elements_already_built() {
+        let drops = Rc::new(Cell::new(0));
+        let created = Cell::new(0);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            init_array::<DropTracker, _, 5>(|index| {
+                if index == 3 {
+                    panic!("element 3 can't be built");
+                }
+                created.set(created.get() + 1);
+                DropTracker { index, drops: Rc::clone(&drops) }
+            })
+        }));
+
+        assert!(result.is_err());
+        // 0, 1 and 2 were built, and each was dropped once: no leak, no double free
+        assert_eq!(created.get(), 3);
+        assert_eq!(drops.get(), 3);
+    }
+}
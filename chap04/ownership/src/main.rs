@@ -1,3 +1,16 @@
+mod alloc_counter;
+mod init_array;
+mod self_ref;
+mod tree;
+
+use std::collections::HashMap;
+
+use alloc_counter::{allocated_bytes, allocation_count, deallocation_count, reset_count};
+use init_array::init_array;
+use rectangles::Rectangle;
+use self_ref::SelfRef;
+use tree::{add_child, root_value, TreeNode};
+
 fn main() {
     let s = String::from("hello");  // s comes into scope
     
@@ -10,6 +23,53 @@ fn main() {
                                     // but i32 is Copy,  so it's okay to still
                                     // use x afterward
 
+    // which values own heap memory? count the allocations each one makes
+    //    (read the counter before println!, which may allocate a buffer of its own)
+    reset_count();
+    let hello = String::from("hello");
+    let (count, bytes) = (allocation_count(), allocated_bytes());
+    println!("String::from(\"hello\"): {count} allocation(s), {bytes} bytes");
+
+    reset_count();
+    let v = vec![1, 2, 3];
+    let (count, bytes) = (allocation_count(), allocated_bytes());
+    println!("vec![1, 2, 3]: {count} allocation(s), {bytes} bytes");
+
+    let mut scores = HashMap::new();
+    reset_count();
+    scores.insert(String::from("Blue"), 10);
+    let (count, bytes) = (allocation_count(), allocated_bytes());
+    println!("first HashMap insert: {count} allocation(s), {bytes} bytes");
+
+    reset_count();
+    let y = x;
+    println!("copying an i32: {} allocation(s)", allocation_count());
+    println!("{hello} {v:?} {scores:?} {y}");
+
+    // dropping an owner gives its memory back
+    reset_count();
+    drop(hello);
+    println!("dropping the String: {} deallocation(s)", deallocation_count());
+
+    // an array built element by element from the index; init_array works the same for
+    //    types that are neither Copy nor Default, which `[value; N]` can't handle
+    let squares = init_array::<Rectangle, _, 5>(|i| Rectangle::square((i + 1) as u32));
+    for square in &squares {
+        println!("{square}");
+    }
+
+    // a value that points into itself has to stay put: pinned in a Box, it can't be moved out
+    let mut greeting = SelfRef::new(String::from("hello"));
+    greeting.as_mut().append(", pinned world");
+    println!("{}", SelfRef::get_ref(greeting.as_ref()));
+
+    // parents own their children; children point back with a Weak, which doesn't own
+    let root = TreeNode::root("root");
+    let leaf = add_child(&add_child(&root, "branch"), "leaf");
+    println!("leaf's root = {:?}", root_value(&leaf));
+    drop(root);
+    println!("after dropping the root, leaf's root = {:?}", root_value(&leaf));
+
 }  // Here, x goes out of scope, then s. But because s's value was moved, nothing 
    // special happens.
 
@@ -0,0 +1,101 @@
+// A struct that points into itself, which ordinary ownership can't express:
+//    moving a value copies its bytes somewhere else, so a pointer to one of its own
+//    fields would keep pointing at the old place.
+//
+// Pin<Box<SelfRef>> is the promise that the SelfRef will never move again, so a pointer
+//    set up after pinning stays valid for as long as the value lives.
+//
+// Without the pin, this is what goes wrong:
+//
+//    let mut a = SelfRef { value: String::from("hello"), ptr: ptr::null(), _pin: PhantomPinned };
+//    a.ptr = &a.value;
+//    let b = a;              // the bytes of `a` move into `b`...
+//    unsafe { &*b.ptr }      // ...but b.ptr still points at `a.value`, which is gone
+//
+// and with it, safe code can't move the value out any more; both of these fail to compile,
+//    because SelfRef isn't Unpin:
+//
+//    let pinned = SelfRef::new(String::from("hello"));
+//    let moved: SelfRef = *Pin::into_inner(pinned);
+//    std::mem::swap(&mut *pinned, &mut *other);
+
+use std::marker::PhantomPinned;
+use std::pin::Pin;
+use std::ptr;
+
+pub struct SelfRef {
+    value: String,
+    // always points at `value` (once constructed)
+    ptr: *const String,
+    // opts out of Unpin, so Pin really does stop it from moving
+    _pin: PhantomPinned,
+}
+
+impl SelfRef {
+    pub fn new(value: String) -> Pin<Box<SelfRef>> {
+        let mut boxed = Box::pin(SelfRef {
+            value,
+            ptr: ptr::null(),
+            _pin: PhantomPinned,
+        });
+        let ptr: *const String = &boxed.value;
+        // SAFETY: only a field is assigned; the SelfRef itself doesn't move
+        unsafe { boxed.as_mut().get_unchecked_mut().ptr = ptr };
+        boxed
+    }
+
+    // Pin has a get_ref of its own, so `pinned.as_ref().get_ref()` is ambiguous;
+    //    call it as SelfRef::get_ref(pinned.as_ref())
+    #[allow(clippy::needless_lifetimes)] // spelled out: the &str borrows from the pinned value
+    pub fn get_ref<'a>(self: Pin<&'a Self>) -> &'a str {
+        // SAFETY: ptr was set to &self.value after pinning, and a pinned value never moves,
+        //    so it still points at a live String for as long as `self` is borrowed
+        unsafe { &*self.ptr }
+    }
+
+    // Growing the String may move its heap buffer, but not the String itself (it's a field
+    //    of the pinned struct), so ptr stays valid
+    pub fn append(self: Pin<&mut Self>, text: &str) {
+        // SAFETY: the String is modified in place; nothing is moved out of self
+        let this = unsafe { self.get_unchecked_mut() };
+        this.value.push_str(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points_at_own_value(pinned: Pin<&SelfRef>) -> bool {
+        ptr::eq(pinned.ptr, &pinned.value)
+    }
+
+    #[test]
+    fn get_ref_reads_through_the_pointer() {
+        let pinned = SelfRef::new(String::from("hello"));
+        assert_eq!(SelfRef::get_ref(pinned.as_ref()), "hello");
+        assert!(points_at_own_value(pinned.as_ref()));
+    }
+
+    #[test]
+    fn still_valid_after_a_pinned_mutation() {
+        let mut pinned = SelfRef::new(String::from("hello"));
+        // long enough to make the String reallocate its buffer
+        pinned.as_mut().append(&", world".repeat(100));
+
+        let text = SelfRef::get_ref(pinned.as_ref());
+        assert!(text.starts_with("hello, world, world"));
+        assert_eq!(text.len(), 5 + 7 * 100);
+        assert!(points_at_own_value(pinned.as_ref()));
+    }
+
+    #[test]
+    fn moving_the_box_does_not_move_the_value() {
+        let pinned = SelfRef::new(String::from("boxed"));
+        // the Box (a pointer) moves; the SelfRef it points to stays where it is
+        let moved = pinned;
+        let in_an_array = [moved];
+        assert_eq!(SelfRef::get_ref(in_an_array[0].as_ref()), "boxed");
+        assert!(points_at_own_value(in_an_array[0].as_ref()));
+    }
+}
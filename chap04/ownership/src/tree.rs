@@ -0,0 +1,93 @@
+// Ownership in a tree: parents own their children, children only know their parent.
+//
+// If the parent link were an Rc too, parent and child would keep each other's counts
+//    above zero and neither would ever be freed (see chap15/ref-cylce). A Weak doesn't
+//    count as an owner: dropping the root frees the whole tree, and a child left holding
+//    a Weak to it finds out with upgrade() returning None.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+pub type NodeRef<T> = Rc<RefCell<TreeNode<T>>>;
+
+#[derive(Debug)]
+pub struct TreeNode<T> {
+    pub value: T,
+    pub children: Vec<Rc<RefCell<TreeNode<T>>>>,
+    pub parent: Option<Weak<RefCell<TreeNode<T>>>>,
+}
+
+impl<T> TreeNode<T> {
+    pub fn root(value: T) -> NodeRef<T> {
+        Rc::new(RefCell::new(TreeNode {
+            value,
+            children: Vec::new(),
+            parent: None,
+        }))
+    }
+}
+
+// The new child is owned by `parent`; the returned Rc is a second owner, for adding
+//    grandchildren (drop it and the parent is the only owner again)
+pub fn add_child<T>(parent: &Rc<RefCell<TreeNode<T>>>, child_value: T) -> NodeRef<T> {
+    let child = Rc::new(RefCell::new(TreeNode {
+        value: child_value,
+        children: Vec::new(),
+        parent: Some(Rc::downgrade(parent)),
+    }));
+    parent.borrow_mut().children.push(Rc::clone(&child));
+    child
+}
+
+// The value at the top of node's tree, or None if some ancestor has already been dropped
+pub fn root_value<T: Clone>(node: &Rc<RefCell<TreeNode<T>>>) -> Option<T> {
+    let mut current = Rc::clone(node);
+    loop {
+        let parent = match &current.borrow().parent {
+            None => return Some(current.borrow().value.clone()),
+            Some(parent) => parent.upgrade()?,
+        };
+        current = parent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn three_levels_share_one_root() {
+        let root = TreeNode::root("root");
+        let branch = add_child(&root, "branch");
+        let leaf = add_child(&branch, "leaf");
+        add_child(&root, "other branch");
+
+        assert_eq!(root_value(&leaf), Some("root"));
+        assert_eq!(root_value(&branch), Some("root"));
+        assert_eq!(root_value(&root), Some("root"));
+        assert_eq!(root.borrow().children.len(), 2);
+
+        // only the Weak links point up, so the root has one owner (and two Weak children)
+        assert_eq!(Rc::strong_count(&root), 1);
+        assert_eq!(Rc::weak_count(&root), 2);
+        // the branch is owned by the root and by the `branch` variable
+        assert_eq!(Rc::strong_count(&branch), 2);
+    }
+
+    #[test]
+    fn dropping_the_root_frees_it_despite_the_children() {
+        let root = TreeNode::root(1);
+        let branch = add_child(&root, 2);
+        let leaf = add_child(&branch, 3);
+        let weak_root = Rc::downgrade(&root);
+
+        drop(root);
+        assert!(weak_root.upgrade().is_none());
+        let parent_of_branch = branch.borrow().parent.as_ref().unwrap().upgrade();
+        assert!(parent_of_branch.is_none());
+
+        // the branch is still alive (we hold it), but its tree has lost its top
+        assert_eq!(root_value(&leaf), None);
+        assert_eq!(root_value(&branch), None);
+    }
+}
@@ -1,3 +1,7 @@
+mod words;
+
+use words::words;
+
 fn main() {
     let mut s = String::from("hello world");
 
@@ -15,42 +19,35 @@ fn main() {
     main2()
 }
 
-fn first_word(s: &String) -> usize {
-    let bytes = s.as_bytes();
+// The first word: leading whitespace is skipped, and the word ends at the next whitespace
+//    of any kind (tabs and non-breaking spaces count, not only b' '). None when there is
+//    no word at all, so "" can't be mistaken for an empty word.
+fn first_word_opt(s: &str) -> Option<&str> {
+    words(s).next()
+}
 
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            return i;
-        }
+// the byte index just past the first word, or s.len() if there's none.
+//    This one and first_word_2 take &String on purpose, to compare with first_word_3's &str
+#[allow(clippy::ptr_arg)]
+fn first_word(s: &String) -> usize {
+    let leading = s.len() - s.trim_start().len();
+    match first_word_opt(s) {
+        Some(word) => leading + word.len(),
+        None => s.len(),
     }
-
-    s.len()
 }
 
+#[allow(clippy::ptr_arg)]
 fn first_word_2(s: &String) -> &str {
-    let bytes = s.as_bytes();
-    
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            return &s[0..i];
-        }
-    }
-
-    &s[..]
+    first_word_opt(s).unwrap_or("")
 }
 
 fn first_word_3(s: &str) -> &str {
-    let bytes = s.as_bytes();
-    
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            return &s[0..i];
-        }
-    }
-
-    &s[..]
+    first_word_opt(s).unwrap_or("")
 }
 
+// every way of slicing a string is spelled out, [..] included
+#[allow(clippy::redundant_slicing)]
 fn main2() {
 
     let my_string = String::from("hello world");
@@ -66,4 +63,66 @@ fn main2() {
 
     let _word = first_word_3(my_string_literal);
 
+    // leading spaces used to give an empty first word
+    if let Some(word) = first_word_opt("  hello  world") {
+        println!("the first word is {word:?}");
+    }
+    assert_eq!(first_word_opt(" \t "), None);
+
+    // every word, without looking for the first one over and over
+    let text = "the quick  brown\tfox";
+    println!("{} words, the last is {:?}", words(text).count(), words(text).last());
+    let backwards: Vec<&str> = words(text).rev().collect();
+    println!("backwards: {}", backwards.join(" "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leading_and_repeated_spaces() {
+        assert_eq!(first_word_opt("  hello  world"), Some("hello"));
+        assert_eq!(first_word_3("  hello  world"), "hello");
+        assert_eq!(first_word_opt("hello   world  "), Some("hello"));
+    }
+
+    #[test]
+    fn any_whitespace_ends_a_word() {
+        assert_eq!(first_word_opt("\thello\tworld"), Some("hello"));
+        assert_eq!(first_word_opt("hello\nworld"), Some("hello"));
+        // U+00A0, a non-breaking space
+        assert_eq!(first_word_opt("hello\u{a0}world"), Some("hello"));
+    }
+
+    #[test]
+    fn no_word_at_all() {
+        assert_eq!(first_word_opt(""), None);
+        assert_eq!(first_word_opt("   \t\n"), None);
+        assert_eq!(first_word_3(""), "");
+        assert_eq!(first_word(&String::from("   ")), 3);
+    }
+
+    #[test]
+    fn single_word() {
+        assert_eq!(first_word_opt("hello"), Some("hello"));
+        assert_eq!(first_word_2(&String::from("hello")), "hello");
+        assert_eq!(first_word(&String::from("hello")), 5);
+    }
+
+    #[test]
+    fn multibyte_first_word() {
+        assert_eq!(first_word_opt("안녕하세요 세계"), Some("안녕하세요"));
+        assert_eq!(first_word_opt(" héllo wörld"), Some("héllo"));
+        // a byte index: past the space and the 15 bytes of the word
+        assert_eq!(first_word(&String::from(" 안녕하세요 세계")), 16);
+    }
+
+    #[test]
+    fn index_ends_the_word() {
+        let s = String::from("  hello world");
+        let end = first_word(&s);
+        assert_eq!(end, 7);
+        assert_eq!(s[..end].trim_start(), "hello");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,140 @@
+// The words of a string, one slice at a time: each one borrows from the string, so
+//    nothing is copied or allocated, and a word can't outlive the text it came from.
+//
+// A word is a run of non-whitespace characters; any amount of whitespace of any kind
+//    (char::is_whitespace) separates two words, so there are never empty words.
+//    Words can be taken from either end.
+
+use std::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+pub struct Words<'a> {
+    // what hasn't been handed out yet, from either end
+    rest: &'a str,
+}
+
+pub fn words(s: &str) -> Words<'_> {
+    Words { rest: s }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let rest = self.rest.trim_start();
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(end);
+        self.rest = after;
+        Some(word)
+    }
+
+    // no more words than half the bytes, rounded up: every word but the last needs a separator
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.rest.len().div_ceil(2)))
+    }
+
+    fn last(mut self) -> Option<&'a str> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for Words<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let rest = self.rest.trim_end();
+        if rest.is_empty() {
+            self.rest = rest;
+            return None;
+        }
+        // just past the last whitespace character, which may be more than one byte long
+        let start = rest
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let (before, word) = rest.split_at(start);
+        self.rest = before;
+        Some(word)
+    }
+}
+
+impl FusedIterator for Words<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUTS: [&str; 14] = [
+        "",
+        " ",
+        "hello",
+        "hello world",
+        "  hello  world  ",
+        "\thello\tworld\n",
+        "a b c d e",
+        "one\r\ntwo\r\n",
+        // U+00A0 no-break space, U+3000 ideographic space, U+2028 line separator
+        "no\u{a0}break",
+        "안녕하세요\u{3000}세계",
+        "\u{2028}first\u{2028}\u{2028}second\u{2028}",
+        "héllo wörld 🦀",
+        "    \t\n   ",
+        "x",
+    ];
+
+    #[test]
+    fn same_words_as_split_whitespace() {
+        for input in INPUTS {
+            let ours: Vec<&str> = words(input).collect();
+            let std: Vec<&str> = input.split_whitespace().collect();
+            assert_eq!(ours, std, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn same_words_from_the_back() {
+        for input in INPUTS {
+            let ours: Vec<&str> = words(input).rev().collect();
+            let std: Vec<&str> = input.split_whitespace().rev().collect();
+            assert_eq!(ours, std, "{input:?}");
+            assert_eq!(words(input).last(), input.split_whitespace().last(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn both_ends_meet_in_the_middle() {
+        let mut iter = words("  one two  three four five ");
+        assert_eq!(iter.next(), Some("one"));
+        assert_eq!(iter.next_back(), Some("five"));
+        assert_eq!(iter.next_back(), Some("four"));
+        assert_eq!(iter.next(), Some("two"));
+        assert_eq!(iter.next(), Some("three"));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        let mut iter = words("only");
+        assert_eq!(iter.next_back(), Some("only"));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn words_borrow_from_the_input() {
+        let text = String::from("borrowed, not copied");
+        let first = words(&text).next().unwrap();
+        assert!(std::ptr::eq(first.as_ptr(), text.as_ptr()));
+        let last = words(&text).next_back().unwrap();
+        assert_eq!(last.as_ptr() as usize - text.as_ptr() as usize, text.len() - "copied".len());
+    }
+
+    #[test]
+    fn size_hint_holds() {
+        for input in INPUTS {
+            let (low, high) = words(input).size_hint();
+            let count = words(input).count();
+            assert!(low <= count && count <= high.unwrap(), "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// The Rectangle from the walk-through in main.rs, as a library so other code can share it.
+//
+// With the `wasm` feature it can also be used from JavaScript:
+//    wasm-pack build --features wasm
+// This file uses only core arithmetic, nothing from std::io, so it builds for wasm32-unknown-unknown.
+
+use std::fmt;
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+pub mod random;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Rectangle {
+    pub fn new(width: u32, height: u32) -> Rectangle {
+        Rectangle { width, height }
+    }
+
+    // `new Rectangle(width, height)` in JavaScript
+    #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+    pub fn from_js(width: u32, height: u32) -> Rectangle {
+        Rectangle::new(width, height)
+    }
+
+    pub fn area(&self) -> u32 {
+        self.width * self.height
+    }
+
+    pub fn perimeter(&self) -> u32 {
+        2 * (self.width + self.height)
+    }
+
+    pub fn can_hold(&self, other: &Rectangle) -> bool {
+        self.width > other.width && self.height > other.height
+    }
+
+    pub fn square(size: u32) -> Rectangle {
+        Rectangle {
+            width: size,
+            height: size,
+        }
+    }
+
+    // From the top-left corner (x1, y1) to the bottom-right one (x2, y2). None unless the
+    //    second point is strictly right of and below the first, so the result is never empty.
+    pub fn from_diagonal_points(x1: u32, y1: u32, x2: u32, y2: u32) -> Option<Rectangle> {
+        if x1 < x2 && y1 < y2 {
+            Some(Rectangle::new(x2 - x1, y2 - y1))
+        } else {
+            None
+        }
+    }
+
+    // width / height: above 1 for landscape, below 1 for portrait. Infinite for a
+    //    rectangle with no height, NaN for one with no width either.
+    pub fn aspect_ratio(&self) -> f64 {
+        f64::from(self.width) / f64::from(self.height)
+    }
+}
+
+pub const GOLDEN_RATIO: f64 = 1.618_033_988_749_895;
+
+// The candidate whose aspect_ratio is nearest GOLDEN_RATIO (the first on a tie).
+//    None for an empty slice, or one holding only 0x0 rectangles, which have no ratio.
+pub fn closest_to_golden_ratio(candidates: &[Rectangle]) -> Option<&Rectangle> {
+    candidates
+        .iter()
+        .map(|rect| ((rect.aspect_ratio() - GOLDEN_RATIO).abs(), rect))
+        .filter(|(distance, _)| !distance.is_nan())
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, rect)| rect)
+}
+
+// Not exported to JavaScript: there `width` is already the field's getter
+impl Rectangle {
+    pub fn width(&self) -> bool {
+        self.width > 0
+    }
+
+    // n strips of the full width, stacked top to bottom, each height / n high; the last
+    //    one also takes the remainder, so together they cover the whole rectangle.
+    //    No strips for n == 0.
+    pub fn subdivide_horizontal(&self, n: usize) -> Vec<Rectangle> {
+        split_length(self.height, n)
+            .map(|height| Rectangle::new(self.width, height))
+            .collect()
+    }
+
+    // n columns of the full height, left to right, the last taking the remainder
+    pub fn subdivide_vertical(&self, n: usize) -> Vec<Rectangle> {
+        split_length(self.width, n)
+            .map(|width| Rectangle::new(width, self.height))
+            .collect()
+    }
+
+    // A grid of w_tiles across by h_tiles down, row by row from the top left
+    pub fn tile(&self, w_tiles: usize, h_tiles: usize) -> Vec<Rectangle> {
+        self.subdivide_horizontal(h_tiles)
+            .iter()
+            .flat_map(|row| row.subdivide_vertical(w_tiles))
+            .collect()
+    }
+}
+
+// `length` cut into n parts of length / n, the last one also taking the remainder.
+//    With more parts than length (or than u32 can count) most of them are 0 long.
+fn split_length(length: u32, n: usize) -> impl Iterator<Item = u32> {
+    let part = u32::try_from(n).map_or(0, |n| length.checked_div(n).unwrap_or(0));
+    // part * (n - 1) <= length, so this can't underflow, and only the u64 product can be large
+    let last = u64::from(length) - u64::from(part) * n.saturating_sub(1) as u64;
+    (0..n).map(move |i| if i + 1 == n { last as u32 } else { part })
+}
+
+// `30x50 rectangle, area 1500, perimeter 160`; tests/snapshots.rs pins the exact text
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // in u64, so printing a huge rectangle can't overflow the way area() would
+        let (width, height) = (u64::from(self.width), u64::from(self.height));
+        let kind = if width == height { "square" } else { "rectangle" };
+        write!(
+            f,
+            "{width}x{height} {kind}, area {}, perimeter {}",
+            width * height,
+            2 * (width + height)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn area_and_perimeter() {
+        let rect = Rectangle::new(30, 50);
+        assert_eq!(rect.area(), 1500);
+        assert_eq!(rect.perimeter(), 160);
+        assert_eq!(Rectangle::square(3).perimeter(), 12);
+        assert_eq!(Rectangle::from_js(2, 5), Rectangle::new(2, 5));
+    }
+
+    #[test]
+    fn can_hold_needs_both_sides_strictly_larger() {
+        let rect = Rectangle::new(30, 50);
+        assert!(rect.can_hold(&Rectangle::new(10, 40)));
+        assert!(!rect.can_hold(&Rectangle::new(60, 45)));
+        assert!(!rect.can_hold(&Rectangle::new(30, 10)));
+        assert!(!rect.can_hold(&rect));
+    }
+
+    #[test]
+    fn width_is_nonzero() {
+        assert!(Rectangle::new(1, 0).width());
+        assert!(!Rectangle::new(0, 1).width());
+    }
+
+    #[test]
+    fn from_diagonal_points_needs_the_second_corner_below_right() {
+        assert_eq!(Rectangle::from_diagonal_points(10, 20, 40, 70), Some(Rectangle::new(30, 50)));
+        assert_eq!(Rectangle::from_diagonal_points(0, 0, 1, 1), Some(Rectangle::square(1)));
+        // the wrong way round, or flat
+        assert_eq!(Rectangle::from_diagonal_points(40, 70, 10, 20), None);
+        assert_eq!(Rectangle::from_diagonal_points(10, 70, 40, 20), None);
+        assert_eq!(Rectangle::from_diagonal_points(10, 20, 10, 70), None);
+        assert_eq!(Rectangle::from_diagonal_points(10, 20, 40, 20), None);
+        assert_eq!(Rectangle::from_diagonal_points(0, 0, u32::MAX, u32::MAX), Some(Rectangle::square(u32::MAX)));
+    }
+
+    #[test]
+    fn aspect_ratios() {
+        assert_eq!(Rectangle::square(7).aspect_ratio(), 1.0);
+        assert_eq!(Rectangle::new(1920, 1080).aspect_ratio(), 16.0 / 9.0);
+        assert_eq!(Rectangle::new(30, 60).aspect_ratio(), 0.5);
+        assert_eq!(Rectangle::new(3, 0).aspect_ratio(), f64::INFINITY);
+        assert!(Rectangle::new(0, 0).aspect_ratio().is_nan());
+    }
+
+    #[test]
+    fn golden_ratio_search() {
+        let candidates = [
+            Rectangle::square(10),
+            Rectangle::new(16, 9),
+            Rectangle::new(89, 55),
+            Rectangle::new(3, 2),
+            Rectangle::new(10, 0),
+        ];
+        // consecutive Fibonacci numbers approach the golden ratio
+        assert_eq!(closest_to_golden_ratio(&candidates), Some(&Rectangle::new(89, 55)));
+        assert_eq!(closest_to_golden_ratio(&candidates[..2]), Some(&Rectangle::new(16, 9)));
+        // 3:2 (1.5) is nearer than 16:9 (1.78)
+        assert_eq!(
+            closest_to_golden_ratio(&[Rectangle::new(2, 1), Rectangle::new(16, 9), Rectangle::new(3, 2)]),
+            Some(&Rectangle::new(3, 2))
+        );
+        // a tie goes to the first
+        let tie = [Rectangle::new(8, 5), Rectangle::new(16, 10)];
+        assert_eq!(closest_to_golden_ratio(&tie), Some(&tie[0]));
+        assert_eq!(closest_to_golden_ratio(&[]), None);
+        assert_eq!(closest_to_golden_ratio(&[Rectangle::new(0, 0)]), None);
+        let flat = [Rectangle::new(0, 0), Rectangle::new(5, 0)];
+        assert_eq!(closest_to_golden_ratio(&flat), Some(&flat[1]));
+    }
+
+    fn total_area(parts: &[Rectangle]) -> u64 {
+        parts.iter().map(|part| u64::from(part.area())).sum()
+    }
+
+    #[test]
+    fn subdivide_into_strips() {
+        let rect = Rectangle::new(30, 50);
+        let rows = rect.subdivide_horizontal(3);
+        assert_eq!(rows, vec![Rectangle::new(30, 16), Rectangle::new(30, 16), Rectangle::new(30, 18)]);
+        let columns = rect.subdivide_vertical(4);
+        assert_eq!(
+            columns,
+            vec![Rectangle::new(7, 50), Rectangle::new(7, 50), Rectangle::new(7, 50), Rectangle::new(9, 50)]
+        );
+        for n in 0..=60 {
+            for parts in [rect.subdivide_horizontal(n), rect.subdivide_vertical(n)] {
+                assert_eq!(parts.len(), n);
+                assert!(total_area(&parts) <= u64::from(rect.area()), "{n}");
+            }
+        }
+        assert_eq!(rect.subdivide_horizontal(1), vec![rect]);
+        assert!(rect.subdivide_vertical(0).is_empty());
+        // more strips than rows of pixels: all but the last are empty
+        assert_eq!(Rectangle::new(4, 2).subdivide_horizontal(3), vec![
+            Rectangle::new(4, 0),
+            Rectangle::new(4, 0),
+            Rectangle::new(4, 2),
+        ]);
+    }
+
+    #[test]
+    fn tile_is_a_grid() {
+        let rect = Rectangle::new(100, 60);
+        let tiles = rect.tile(2, 3);
+        assert_eq!(tiles.len(), 6);
+        assert!(tiles.iter().all(|tile| *tile == Rectangle::new(50, 20)));
+        assert_eq!(total_area(&tiles), u64::from(rect.area()));
+
+        let rect = Rectangle::new(31, 17);
+        for (w_tiles, h_tiles) in [(1, 1), (3, 4), (5, 2), (0, 3), (31, 17)] {
+            let tiles = rect.tile(w_tiles, h_tiles);
+            assert_eq!(tiles.len(), w_tiles * h_tiles);
+            assert!(total_area(&tiles) <= u64::from(rect.area()));
+        }
+        // row by row: the last column of each row is wider, the last row taller
+        let tiles = rect.tile(3, 4);
+        assert_eq!(tiles[2], Rectangle::new(11, 4));
+        assert_eq!(tiles[11], Rectangle::new(11, 5));
+    }
+}
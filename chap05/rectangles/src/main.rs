@@ -1,3 +1,5 @@
+use rectangles::Rectangle;
+
 fn main() {
     
     // step 1
@@ -29,6 +31,7 @@ fn main() {
     );
     println!("rect2 is {rect2:?}");
     println!("rect2 is {rect2:#?}");
+    println!("rect2 is a {rect2}");
 
     // step 4
     let scale = 2;
@@ -74,35 +77,6 @@ fn area_of_dimensions(dimensions: (u32, u32)) -> u32 {
     dimensions.0 * dimensions.1
 }
 
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
-
-impl Rectangle {
-    fn area(&self) -> u32 {
-        self.width * self.height
-    }
-
-    fn width(&self) -> bool {
-        self.width > 0
-    }
-
-    fn can_hold(&self, other: &Rectangle) -> bool {
-        self.width > other.width && self.height > other.height
-    }
-}
-
-impl Rectangle {
-    fn square(size: u32) -> Self {
-        Self {
-            width: size,
-            height: size,
-        }
-    }
-}
-
 fn area_of_rectangle(rectangle: &Rectangle) -> u32 {
     rectangle.width * rectangle.height
 }
@@ -0,0 +1,97 @@
+// Random rectangles for tests, without pulling in the rand crate.
+//
+// A linear congruential generator with Knuth's MMIX constants: not random enough for
+//    anything that matters, but fast, and the same seed always gives the same sequence,
+//    so a failing test fails the same way every time it runs.
+
+use crate::Rectangle;
+
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+const INCREMENT: u64 = 1_442_695_040_888_963_407;
+
+#[derive(Debug, Clone)]
+pub struct LcgRng {
+    state: u64,
+}
+
+impl LcgRng {
+    pub fn new(seed: u64) -> Self {
+        LcgRng { state: seed }
+    }
+
+    // The high half of the state: an LCG's low bits repeat with a short period
+    //    (the lowest one just alternates), the high ones much less so
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+        (self.state >> 32) as u32
+    }
+
+    // In 1..=max; slightly biased towards the low end unless max is a power of two,
+    //    which doesn't matter for test data
+    fn next_in(&mut self, max: u32) -> u32 {
+        1 + self.next_u32() % max
+    }
+}
+
+impl Rectangle {
+    // Width and height each in 1..=max_dim. Panics if max_dim is 0, since then there is
+    //    no such rectangle.
+    pub fn random(rng: &mut LcgRng, max_dim: u32) -> Rectangle {
+        assert!(max_dim > 0, "max_dim must be at least 1");
+        let width = rng.next_in(max_dim);
+        Rectangle::new(width, rng.next_in(max_dim))
+    }
+}
+
+pub fn random_rectangles(rng: &mut LcgRng, n: usize, max_dim: u32) -> Vec<Rectangle> {
+    (0..n).map(|_| Rectangle::random(rng, max_dim)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_rectangles() {
+        let first = random_rectangles(&mut LcgRng::new(42), 100, 500);
+        let second = random_rectangles(&mut LcgRng::new(42), 100, 500);
+        assert_eq!(first, second);
+        assert_ne!(first, random_rectangles(&mut LcgRng::new(43), 100, 500));
+
+        // one at a time draws the same sequence as the batch
+        let mut rng = LcgRng::new(42);
+        assert_eq!(Rectangle::random(&mut rng, 500), first[0]);
+        assert_eq!(Rectangle::random(&mut rng, 500), first[1]);
+    }
+
+    #[test]
+    fn dimensions_stay_in_range() {
+        let mut rng = LcgRng::new(7);
+        for max_dim in [1, 2, 3, 10, 1000, u32::MAX] {
+            for rect in random_rectangles(&mut rng, 200, max_dim) {
+                assert!((1..=max_dim).contains(&rect.width), "{rect:?} for {max_dim}");
+                assert!((1..=max_dim).contains(&rect.height), "{rect:?} for {max_dim}");
+            }
+        }
+        assert_eq!(random_rectangles(&mut rng, 3, 1), vec![Rectangle::square(1); 3]);
+        assert!(random_rectangles(&mut rng, 0, 10).is_empty());
+    }
+
+    #[test]
+    fn values_spread_over_the_range() {
+        // a generator that got stuck, or only used a few values, would fail this
+        let mut rng = LcgRng::new(0);
+        let mut seen = [false; 10];
+        for rect in random_rectangles(&mut rng, 200, 10) {
+            seen[rect.width as usize - 1] = true;
+            seen[rect.height as usize - 1] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    #[should_panic(expected = "max_dim must be at least 1")]
+    fn zero_max_dim_panics() {
+        Rectangle::random(&mut LcgRng::new(1), 0);
+    }
+}
@@ -0,0 +1,19 @@
+// The expected output lives in tests/snapshots; chap11/snapshot explains how to update it
+
+use rectangles::Rectangle;
+use snapshot::assert_snapshot;
+
+#[test]
+fn rectangle_display() {
+    let cases = [
+        ("rectangle_30x50", Rectangle::new(30, 50)),
+        ("rectangle_50x30", Rectangle::new(50, 30)),
+        ("rectangle_square_4", Rectangle::square(4)),
+        ("rectangle_1x1", Rectangle::new(1, 1)),
+        ("rectangle_0x10", Rectangle::new(0, 10)),
+        ("rectangle_max", Rectangle::new(u32::MAX, u32::MAX)),
+    ];
+    for (name, rect) in cases {
+        assert_snapshot!(name, &format!("{rect}"));
+    }
+}
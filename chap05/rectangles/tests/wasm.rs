@@ -0,0 +1,12 @@
+// Runs in a JavaScript engine: wasm-pack test --node --features wasm
+#![cfg(target_arch = "wasm32")]
+
+use rectangles::Rectangle;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn area_in_wasm() {
+    assert_eq!(Rectangle::from_js(30, 50).area(), 1500);
+    assert_eq!(Rectangle::square(4).area(), 16);
+    assert!(Rectangle::new(30, 50).can_hold(&Rectangle::new(10, 40)));
+}
@@ -1,11 +1,239 @@
+mod matches_demo;
 
 // note: add `#[derive(Debug)]` to `UsState` or manually `impl Debug for UsState
-#[derive(Debug)] // so we can inspect the state in a minute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] // so we can inspect the state in a minute
 enum UsState {
     Alabama,
     Alaska,
+    Arizona,
+    Arkansas,
+    California,
+    Colorado,
+    Connecticut,
+    Delaware,
+    Florida,
+    Georgia,
+    Hawaii,
+    Idaho,
+    Illinois,
+    Indiana,
+    Iowa,
+    Kansas,
+    Kentucky,
+    Louisiana,
+    Maine,
+    Maryland,
+    Massachusetts,
+    Michigan,
+    Minnesota,
+    Mississippi,
+    Missouri,
+    Montana,
+    Nebraska,
+    Nevada,
+    NewHampshire,
+    NewJersey,
+    NewMexico,
+    NewYork,
+    NorthCarolina,
+    NorthDakota,
+    Ohio,
+    Oklahoma,
+    Oregon,
+    Pennsylvania,
+    RhodeIsland,
+    SouthCarolina,
+    SouthDakota,
+    Tennessee,
+    Texas,
+    Utah,
+    Vermont,
+    Virginia,
+    Washington,
+    WestVirginia,
+    Wisconsin,
+    Wyoming,
 }
 
+impl UsState {
+    const ALL: [UsState; 50] = [
+        UsState::Alabama,
+        UsState::Alaska,
+        UsState::Arizona,
+        UsState::Arkansas,
+        UsState::California,
+        UsState::Colorado,
+        UsState::Connecticut,
+        UsState::Delaware,
+        UsState::Florida,
+        UsState::Georgia,
+        UsState::Hawaii,
+        UsState::Idaho,
+        UsState::Illinois,
+        UsState::Indiana,
+        UsState::Iowa,
+        UsState::Kansas,
+        UsState::Kentucky,
+        UsState::Louisiana,
+        UsState::Maine,
+        UsState::Maryland,
+        UsState::Massachusetts,
+        UsState::Michigan,
+        UsState::Minnesota,
+        UsState::Mississippi,
+        UsState::Missouri,
+        UsState::Montana,
+        UsState::Nebraska,
+        UsState::Nevada,
+        UsState::NewHampshire,
+        UsState::NewJersey,
+        UsState::NewMexico,
+        UsState::NewYork,
+        UsState::NorthCarolina,
+        UsState::NorthDakota,
+        UsState::Ohio,
+        UsState::Oklahoma,
+        UsState::Oregon,
+        UsState::Pennsylvania,
+        UsState::RhodeIsland,
+        UsState::SouthCarolina,
+        UsState::SouthDakota,
+        UsState::Tennessee,
+        UsState::Texas,
+        UsState::Utah,
+        UsState::Vermont,
+        UsState::Virginia,
+        UsState::Washington,
+        UsState::WestVirginia,
+        UsState::Wisconsin,
+        UsState::Wyoming,
+    ];
+
+    fn all() -> [UsState; 50] {
+        UsState::ALL
+    }
+
+    // approximate, from the 2020 census
+    fn population(&self) -> u32 {
+        match self {
+            UsState::Alabama       => 5_024_279,
+            UsState::Alaska        => 733_391,
+            UsState::Arizona       => 7_151_502,
+            UsState::Arkansas      => 3_011_524,
+            UsState::California    => 39_538_223,
+            UsState::Colorado      => 5_773_714,
+            UsState::Connecticut   => 3_605_944,
+            UsState::Delaware      => 989_948,
+            UsState::Florida       => 21_538_187,
+            UsState::Georgia       => 10_711_908,
+            UsState::Hawaii        => 1_455_271,
+            UsState::Idaho         => 1_839_106,
+            UsState::Illinois      => 12_812_508,
+            UsState::Indiana       => 6_785_528,
+            UsState::Iowa          => 3_190_369,
+            UsState::Kansas        => 2_937_880,
+            UsState::Kentucky      => 4_505_836,
+            UsState::Louisiana     => 4_657_757,
+            UsState::Maine         => 1_362_359,
+            UsState::Maryland      => 6_177_224,
+            UsState::Massachusetts => 7_029_917,
+            UsState::Michigan      => 10_077_331,
+            UsState::Minnesota     => 5_706_494,
+            UsState::Mississippi   => 2_961_279,
+            UsState::Missouri      => 6_154_913,
+            UsState::Montana       => 1_084_225,
+            UsState::Nebraska      => 1_961_504,
+            UsState::Nevada        => 3_104_614,
+            UsState::NewHampshire  => 1_377_529,
+            UsState::NewJersey     => 9_288_994,
+            UsState::NewMexico     => 2_117_522,
+            UsState::NewYork       => 20_201_249,
+            UsState::NorthCarolina => 10_439_388,
+            UsState::NorthDakota   => 779_094,
+            UsState::Ohio          => 11_799_448,
+            UsState::Oklahoma      => 3_959_353,
+            UsState::Oregon        => 4_237_256,
+            UsState::Pennsylvania  => 13_002_700,
+            UsState::RhodeIsland   => 1_097_379,
+            UsState::SouthCarolina => 5_118_425,
+            UsState::SouthDakota   => 886_667,
+            UsState::Tennessee     => 6_910_840,
+            UsState::Texas         => 29_145_505,
+            UsState::Utah          => 3_271_616,
+            UsState::Vermont       => 643_077,
+            UsState::Virginia      => 8_631_393,
+            UsState::Washington    => 7_705_281,
+            UsState::WestVirginia  => 1_793_716,
+            UsState::Wisconsin     => 5_893_718,
+            UsState::Wyoming       => 576_851,
+        }
+    }
+
+    // two-letter postal abbreviation
+    fn abbreviation(&self) -> &'static str {
+        match self {
+            UsState::Alabama       => "AL",
+            UsState::Alaska        => "AK",
+            UsState::Arizona       => "AZ",
+            UsState::Arkansas      => "AR",
+            UsState::California    => "CA",
+            UsState::Colorado      => "CO",
+            UsState::Connecticut   => "CT",
+            UsState::Delaware      => "DE",
+            UsState::Florida       => "FL",
+            UsState::Georgia       => "GA",
+            UsState::Hawaii        => "HI",
+            UsState::Idaho         => "ID",
+            UsState::Illinois      => "IL",
+            UsState::Indiana       => "IN",
+            UsState::Iowa          => "IA",
+            UsState::Kansas        => "KS",
+            UsState::Kentucky      => "KY",
+            UsState::Louisiana     => "LA",
+            UsState::Maine         => "ME",
+            UsState::Maryland      => "MD",
+            UsState::Massachusetts => "MA",
+            UsState::Michigan      => "MI",
+            UsState::Minnesota     => "MN",
+            UsState::Mississippi   => "MS",
+            UsState::Missouri      => "MO",
+            UsState::Montana       => "MT",
+            UsState::Nebraska      => "NE",
+            UsState::Nevada        => "NV",
+            UsState::NewHampshire  => "NH",
+            UsState::NewJersey     => "NJ",
+            UsState::NewMexico     => "NM",
+            UsState::NewYork       => "NY",
+            UsState::NorthCarolina => "NC",
+            UsState::NorthDakota   => "ND",
+            UsState::Ohio          => "OH",
+            UsState::Oklahoma      => "OK",
+            UsState::Oregon        => "OR",
+            UsState::Pennsylvania  => "PA",
+            UsState::RhodeIsland   => "RI",
+            UsState::SouthCarolina => "SC",
+            UsState::SouthDakota   => "SD",
+            UsState::Tennessee     => "TN",
+            UsState::Texas         => "TX",
+            UsState::Utah          => "UT",
+            UsState::Vermont       => "VT",
+            UsState::Virginia      => "VA",
+            UsState::Washington    => "WA",
+            UsState::WestVirginia  => "WV",
+            UsState::Wisconsin     => "WI",
+            UsState::Wyoming       => "WY",
+        }
+    }
+
+    // "ak" works as well as "AK"
+    fn from_abbreviation(s: &str) -> Option<UsState> {
+        UsState::ALL
+            .into_iter()
+            .find(|state| state.abbreviation().eq_ignore_ascii_case(s))
+    }
+}
+
+#[derive(Debug, PartialEq)]
 enum Coin {
     Penny,
     Nickel,
@@ -13,6 +241,33 @@ enum Coin {
     Quarter(UsState),
 }
 
+impl Coin {
+    // the inverse of value_in_cents; quarters we make up come from Alabama
+    fn from_cents(cents: u8) -> Option<Coin> {
+        match cents {
+            1  => Some(Coin::Penny),
+            5  => Some(Coin::Nickel),
+            10 => Some(Coin::Dime),
+            25 => Some(Coin::Quarter(UsState::Alabama)),
+            _  => None,
+        }
+    }
+}
+
+// Fewest coins adding up to `amount` cents, biggest first.
+//    Taking the biggest coin that fits is optimal for 25/10/5/1 (not for every set of coins!).
+fn make_change(amount: u8) -> Vec<Coin> {
+    let mut coins = Vec::new();
+    let mut left = amount;
+    for cents in [25, 10, 5, 1] {
+        while left >= cents {
+            coins.extend(Coin::from_cents(cents));
+            left -= cents;
+        }
+    }
+    coins
+}
+
 fn value_in_cents(coin: Coin) -> u8 {
     match coin {
         Coin::Penny   => {
@@ -29,6 +284,8 @@ fn value_in_cents(coin: Coin) -> u8 {
     }
 }
 
+// spelled out to show matching on Option; clippy would have it be x.map(|i| i + 1)
+#[allow(clippy::manual_map)]
 fn plus_one(x: Option<i32>) -> Option<i32> {
     match x {
         None => None,
@@ -39,12 +296,22 @@ fn plus_one(x: Option<i32>) -> Option<i32> {
 fn add_fancy_hat() {}
 fn remove_fancy_hat() {}
 // fn move_player(num_spaces: u8) {}
+// for the commented-out arm below
+#[allow(dead_code)]
 fn reroll() {}
 
 fn main() {
     let five = Some(5);
     let six  = plus_one(five);
     let none = plus_one(None);
+    println!("{five:?} plus one is {six:?}, None plus one is {none:?}");
+
+    let coin = Coin::Quarter(UsState::from_abbreviation("AK").unwrap());
+    println!("{} cents", value_in_cents(coin));
+    println!("change for 41 cents: {:?}", make_change(41));
+    if let Some(state) = UsState::all().iter().max_by_key(|s| s.population()) {
+        println!("{state:?} has the most people, about {}", state.population());
+    }
 
     let dice_roll = 9;
     match dice_roll {
@@ -54,4 +321,125 @@ fn main() {
         // _ => reroll(),
         _ => (),
     }
+
+    // match guards: an extra `if` condition an arm must also satisfy
+    for t in [-5, 10, 25, 35] {
+        println!("{t}°C is {}", guards_demo(t));
+    }
+    println!("(3, 7): {}", compare(3, 7));
+    println!("{}, {}", describe_month(4), describe_month(13));
+
+    matches_demo::main();
+}
+
+// arms are tried top to bottom, so each guard only needs the upper bound
+fn guards_demo(temperature: i32) -> &'static str {
+    match temperature {
+        t if t < 0  => "freezing",
+        t if t < 20 => "cold",
+        t if t < 30 => "comfortable",
+        _           => "hot",
+    }
+}
+
+// guards can compare parts of a tuple with each other, which a pattern alone can't
+fn compare(x: i32, y: i32) -> &'static str {
+    match (x, y) {
+        (a, b) if a == b => "equal",
+        (a, b) if a > b  => "first is larger",
+        _                => "second is larger",
+    }
+}
+
+// `@` binds the value while also testing it against a range
+fn describe_month(n: u32) -> String {
+    match n {
+        n @ 1..=12 => format!("month {n}"),
+        _          => String::from("invalid"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_guard_arm_is_reachable() {
+        assert_eq!(guards_demo(-1), "freezing");
+        assert_eq!(guards_demo(0), "cold");
+        assert_eq!(guards_demo(19), "cold");
+        assert_eq!(guards_demo(20), "comfortable");
+        assert_eq!(guards_demo(29), "comfortable");
+        assert_eq!(guards_demo(30), "hot");
+
+        assert_eq!(compare(2, 2), "equal");
+        assert_eq!(compare(5, 2), "first is larger");
+        assert_eq!(compare(-5, 2), "second is larger");
+
+        assert_eq!(describe_month(1), "month 1");
+        assert_eq!(describe_month(12), "month 12");
+        assert_eq!(describe_month(0), "invalid");
+        assert_eq!(describe_month(13), "invalid");
+    }
+
+    #[test]
+    fn from_cents_valid_and_invalid() {
+        assert_eq!(Coin::from_cents(1), Some(Coin::Penny));
+        assert_eq!(Coin::from_cents(5), Some(Coin::Nickel));
+        assert_eq!(Coin::from_cents(10), Some(Coin::Dime));
+        assert_eq!(Coin::from_cents(25), Some(Coin::Quarter(UsState::Alabama)));
+        for cents in [0, 2, 4, 15, 24, 50, 100, 255] {
+            assert_eq!(Coin::from_cents(cents), None, "{cents}");
+        }
+        for cents in [1, 5, 10, 25] {
+            assert_eq!(value_in_cents(Coin::from_cents(cents).unwrap()), cents);
+        }
+    }
+
+    #[test]
+    fn make_change_41() {
+        let quarter = Coin::Quarter(UsState::Alabama);
+        assert_eq!(make_change(41), vec![quarter, Coin::Dime, Coin::Nickel, Coin::Penny]);
+        assert_eq!(make_change(0), vec![]);
+    }
+
+    #[test]
+    fn make_change_adds_up() {
+        for amount in 0..=99 {
+            let coins = make_change(amount);
+            let total: u32 = coins.into_iter().map(|c| u32::from(value_in_cents(c))).sum();
+            assert_eq!(total, u32::from(amount));
+        }
+        assert_eq!(make_change(99).len(), 3 + 2 + 4);
+    }
+
+    #[test]
+    fn all_returns_every_state_once() {
+        let all = UsState::all();
+        assert_eq!(all.len(), 50);
+        let distinct: HashSet<&str> = all.iter().map(|s| s.abbreviation()).collect();
+        assert_eq!(distinct.len(), 50);
+    }
+
+    #[test]
+    fn abbreviations_round_trip() {
+        for state in UsState::all() {
+            let abbreviation = state.abbreviation();
+            assert_eq!(abbreviation.len(), 2);
+            assert_eq!(UsState::from_abbreviation(abbreviation), Some(state));
+        }
+        assert_eq!(UsState::from_abbreviation("ny"), Some(UsState::NewYork));
+        assert_eq!(UsState::from_abbreviation("DC"), None);
+        assert_eq!(UsState::from_abbreviation(""), None);
+    }
+
+    #[test]
+    fn populations_are_positive() {
+        assert!(UsState::all().iter().all(|s| s.population() > 0));
+        assert_eq!(UsState::all().iter().max_by_key(|s| s.population()), Some(&UsState::California));
+        assert_eq!(UsState::all().iter().min_by_key(|s| s.population()), Some(&UsState::Wyoming));
+        let total: u64 = UsState::all().iter().map(|s| u64::from(s.population())).sum();
+        assert!((330_000_000..340_000_000).contains(&total));
+    }
 }
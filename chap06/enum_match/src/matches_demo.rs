@@ -0,0 +1,89 @@
+// matches!(value, pattern) is a `match` that only answers "does it fit?" with a bool,
+//    handy wherever a condition is expected: filter closures, asserts, && chains...
+use super::{Coin, UsState};
+
+pub fn count_quarters(coins: &[Coin]) -> usize {
+    coins.iter().filter(|c| matches!(c, Coin::Quarter(_))).count()
+}
+
+// Same check spelled out with match: four lines for one bool
+//    (clippy suggests matches! here too)
+#[allow(clippy::match_like_matches_macro)]
+pub fn is_quarter_verbose(coin: &Coin) -> bool {
+    match coin {
+        Coin::Quarter(_) => true,
+        _ => false,
+    }
+}
+
+pub fn is_quarter(coin: &Coin) -> bool {
+    matches!(coin, Coin::Quarter(_))
+}
+
+// `|` works like in a match arm
+pub fn is_alphabetically_first(state: &UsState) -> bool {
+    matches!(state, UsState::Alaska | UsState::Alabama)
+}
+
+// and so do guards
+pub fn is_big(opt: Option<i32>) -> bool {
+    matches!(opt, Some(n) if n > 5)
+}
+
+pub fn main() {
+    let coins = [Coin::Penny, Coin::Quarter(UsState::Alaska), Coin::Dime, Coin::Quarter(UsState::Ohio)];
+    println!("{} quarters", count_quarters(&coins));
+    println!("first one a quarter? {} (or {})", is_quarter(&coins[0]), is_quarter_verbose(&coins[0]));
+    println!("Alabama first? {}", is_alphabetically_first(&UsState::Alabama));
+    println!("Some(7) big? {}", is_big(Some(7)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_quarters_on_known_inputs() {
+        assert_eq!(count_quarters(&[]), 0);
+        assert_eq!(count_quarters(&[Coin::Penny, Coin::Nickel, Coin::Dime]), 0);
+        let coins = [
+            Coin::Quarter(UsState::Texas),
+            Coin::Penny,
+            Coin::Quarter(UsState::Maine),
+            Coin::Quarter(UsState::Texas),
+        ];
+        assert_eq!(count_quarters(&coins), 3);
+    }
+
+    #[test]
+    fn matches_agrees_with_match() {
+        for coin in [Coin::Penny, Coin::Nickel, Coin::Dime, Coin::Quarter(UsState::Iowa)] {
+            assert_eq!(is_quarter(&coin), is_quarter_verbose(&coin));
+        }
+        assert!(is_alphabetically_first(&UsState::Alaska));
+        assert!(!is_alphabetically_first(&UsState::Arizona));
+        assert!(is_big(Some(6)));
+        assert!(!is_big(Some(5)));
+        assert!(!is_big(None));
+    }
+
+    // Three checks where matches! is cleaner than if let
+    #[test]
+    #[allow(clippy::match_like_matches_macro)]
+    fn cleaner_than_if_let() {
+        let coin = Coin::Quarter(UsState::Utah);
+
+        // 1. a bool from a pattern: no `if let ... { true } else { false }`
+        let with_if_let = if let Coin::Quarter(_) = coin { true } else { false };
+        assert_eq!(matches!(coin, Coin::Quarter(_)), with_if_let);
+
+        // 2. as a condition combined with others, where if let can't be chained with &&
+        //    (outside of edition 2024's let chains)
+        let limit = Some(10);
+        assert!(matches!(limit, Some(n) if n > 5) && matches!(coin, Coin::Quarter(UsState::Utah)));
+
+        // 3. right inside an assertion or closure
+        let values = [Some(1), None, Some(9), Some(12)];
+        assert_eq!(values.iter().filter(|v| matches!(v, Some(n) if *n > 5)).count(), 2);
+    }
+}
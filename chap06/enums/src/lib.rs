@@ -0,0 +1,6 @@
+// The examples that have grown past a walk-through in main.rs. message has to be here:
+//    a #[non_exhaustive] enum only behaves differently outside the crate that defines it,
+//    and its doc tests act as that other crate.
+
+pub mod message;
+pub mod vending;
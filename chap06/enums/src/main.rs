@@ -1,3 +1,9 @@
+use std::fmt;
+use std::str::FromStr;
+
+use enums::message::{Message, MessageV2};
+use enums::vending::VendingMachine;
+
 fn main() {
     
     // structs give you a way of grouping together related fields and data,
@@ -17,15 +23,41 @@ fn main() {
     // };
 
     // let  home = IpAddr::V4(String::from("127.0.0.1"));
-    // let home = IpAddr::V4(127, 0, 0, 1);
     // let loopback = IpAddr::V6(String::from("::1"));
+    let home = IpAddr::V4(127, 0, 0, 1);
+    let loopback = IpAddr::V6([0, 0, 0, 0, 0, 0, 0, 1]);
+    println!("home is {home} (loopback: {}), loopback is {loopback}", home.is_loopback());
+
+    match "192.168.0.300".parse::<IpAddr>() {
+        Ok(ip) => println!("parsed {ip}"),
+        Err(e) => println!("could not parse: {e}"),
+    }
+    let lan = IpAddr::V4(192, 168, 0, 255);
+    println!("{lan} is private: {}, broadcast: {}", lan.is_private(), lan.is_broadcast());
+
+    let m = Message::Write(String::from("hello"));
+    m.call();
+    let broadcast = MessageV2::Broadcast {
+        audience: vec![String::from("everyone")],
+        content: String::from("hello"),
+    };
+    println!("{:?} and {broadcast:?}", MessageV2::from(m));
 
-    // let m = Message::Write(String::from("hello"));
-    // m.call();
+    let mut machine = VendingMachine::new([(String::from("cola"), 150)].into_iter().collect());
+    machine.insert_coin(100);
+    machine.insert_coin(100);
+    machine.select_item("cola");
+    if let Some((item, change)) = machine.dispense() {
+        println!("got a {item} and {change} cents change");
+    }
 
+    // only there to show the types Option<T> infers
+    #[allow(unused_variables)]
     let some_number = Some(5);
+    #[allow(unused_variables)]
     let some_char = Some('e');
     
+    #[allow(unused_variables)]
     let absent_number: Option<i32> = None;
     
 
@@ -45,22 +77,240 @@ fn main() {
 //     address: String,
 // }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum IpAddr {
     // V4(String),
     V4(u8, u8, u8, u8),
-    V6(String)
+    // V6(String)
+    // eight 16-bit groups, so the address can be printed in its shortest form
+    V6([u16; 8]),
+}
+
+impl IpAddr {
+    // 127.0.0.0/8 or ::1
+    fn is_loopback(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, ..) => a == 127,
+            IpAddr::V6(groups) => groups == [0, 0, 0, 0, 0, 0, 0, 1],
+        }
+    }
+
+    // the RFC 1918 ranges 10.0.0.0/8, 172.16.0.0/12 and 192.168.0.0/16;
+    //    for V6 the unique local fc00::/7 range plays the same role
+    fn is_private(&self) -> bool {
+        match *self {
+            IpAddr::V4(10, ..) => true,
+            IpAddr::V4(172, b, ..) => (16..=31).contains(&b),
+            IpAddr::V4(192, 168, ..) => true,
+            IpAddr::V4(..) => false,
+            IpAddr::V6(groups) => groups[0] & 0xfe00 == 0xfc00,
+        }
+    }
+
+    // IPv6 has no broadcast address
+    fn is_broadcast(&self) -> bool {
+        matches!(self, IpAddr::V4(255, 255, 255, 255))
+    }
+}
+
+impl fmt::Display for IpAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpAddr::V4(a, b, c, d) => write!(f, "{a}.{b}.{c}.{d}"),
+            IpAddr::V6(groups) => {
+                // RFC 5952: the longest run (the first one on a tie) of two or more zero groups becomes `::`
+                let (start, len) = longest_zero_run(groups);
+                if len < 2 {
+                    return write_groups(f, groups);
+                }
+                write_groups(f, &groups[..start])?;
+                write!(f, "::")?;
+                write_groups(f, &groups[start + len..])
+            }
+        }
+    }
+}
+
+fn write_groups(f: &mut fmt::Formatter, groups: &[u16]) -> fmt::Result {
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            write!(f, ":")?;
+        }
+        write!(f, "{group:x}")?;
+    }
+    Ok(())
+}
+
+// (start, length) of the longest run of zero groups
+fn longest_zero_run(groups: &[u16; 8]) -> (usize, usize) {
+    let mut best = (0, 0);
+    let mut start = 0;
+    for (i, &group) in groups.iter().enumerate() {
+        if group != 0 {
+            start = i + 1;
+        } else if i + 1 - start > best.1 {
+            best = (start, i + 1 - start);
+        }
+    }
+    best
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseIpError {
+    // a V4 part that isn't a number from 0 to 255
+    InvalidOctet(String),
+    // a V6 part that isn't 1 to 4 hex digits
+    InvalidGroup(String),
+    // too many or too few parts
+    WrongLength,
 }
 
-enum Message {
-    Quit,
-    Move { x: i32, y: i32 },
-    Write(String),
-    ChangeColor(i32, i32, i32),
+impl fmt::Display for ParseIpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseIpError::InvalidOctet(part) => write!(f, "`{part}` is not an octet (0-255)"),
+            ParseIpError::InvalidGroup(part) => write!(f, "`{part}` is not a 16-bit hex group"),
+            ParseIpError::WrongLength => write!(f, "wrong number of parts for an IP address"),
+        }
+    }
+}
+
+impl FromStr for IpAddr {
+    type Err = ParseIpError;
+
+    // "127.0.0.1", or IPv6 like "2001:db8::1" (without an embedded IPv4 tail)
+    fn from_str(s: &str) -> Result<IpAddr, ParseIpError> {
+        if s.contains(':') {
+            return parse_v6(s).map(IpAddr::V6);
+        }
+
+        let mut octets = [0u8; 4];
+        let mut parts = s.split('.');
+        for octet in octets.iter_mut() {
+            let part = parts.next().ok_or(ParseIpError::WrongLength)?;
+            // u8::from_str would accept "+1", so check for plain digits first
+            if part.is_empty() || part.len() > 3 || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ParseIpError::InvalidOctet(part.to_string()));
+            }
+            *octet = part.parse().map_err(|_| ParseIpError::InvalidOctet(part.to_string()))?;
+        }
+        if parts.next().is_some() {
+            return Err(ParseIpError::WrongLength);
+        }
+
+        let [a, b, c, d] = octets;
+        Ok(IpAddr::V4(a, b, c, d))
+    }
+}
+
+fn parse_v6(s: &str) -> Result<[u16; 8], ParseIpError> {
+    fn groups(part: &str) -> Result<Vec<u16>, ParseIpError> {
+        if part.is_empty() {
+            return Ok(Vec::new());
+        }
+        part.split(':')
+            .map(|group| {
+                if group.is_empty() || group.len() > 4 || !group.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(ParseIpError::InvalidGroup(group.to_string()));
+                }
+                u16::from_str_radix(group, 16).map_err(|_| ParseIpError::InvalidGroup(group.to_string()))
+            })
+            .collect()
+    }
+
+    let mut result = [0u16; 8];
+    match s.split_once("::") {
+        Some((head, tail)) => {
+            let head = groups(head)?;
+            let tail = groups(tail)?;
+            // `::` stands for at least one zero group
+            if head.len() + tail.len() > 7 {
+                return Err(ParseIpError::WrongLength);
+            }
+            result[..head.len()].copy_from_slice(&head);
+            result[8 - tail.len()..].copy_from_slice(&tail);
+        }
+        None => {
+            let all = groups(s)?;
+            if all.len() != 8 {
+                return Err(ParseIpError::WrongLength);
+            }
+            result.copy_from_slice(&all);
+        }
+    }
+    Ok(result)
 }
 
-impl Message {
-    fn call(&self) {
-        // do something
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_known_addresses() {
+        assert_eq!(IpAddr::V4(127, 0, 0, 1).to_string(), "127.0.0.1");
+        assert_eq!(IpAddr::V4(255, 255, 255, 255).to_string(), "255.255.255.255");
+        assert_eq!(IpAddr::V6([0, 0, 0, 0, 0, 0, 0, 1]).to_string(), "::1");
+        assert_eq!(IpAddr::V6([0; 8]).to_string(), "::");
+    }
+
+    #[test]
+    fn v6_display_collapses_longest_zero_run() {
+        let ip = IpAddr::V6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(ip.to_string(), "2001:db8::1");
+        // the longer of two runs wins, the first on a tie
+        let ip = IpAddr::V6([0x2001, 0, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(ip.to_string(), "2001:0:0:1::1");
+        let ip = IpAddr::V6([1, 0, 0, 2, 0, 0, 3, 4]);
+        assert_eq!(ip.to_string(), "1::2:0:0:3:4");
+        // a single zero group stays
+        let ip = IpAddr::V6([0x2001, 0xdb8, 0, 1, 1, 1, 1, 1]);
+        assert_eq!(ip.to_string(), "2001:db8:0:1:1:1:1:1");
+        let ip = IpAddr::V6([0xfe80, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(ip.to_string(), "fe80::");
+    }
+
+    #[test]
+    fn private_ranges() {
+        for ip in ["10.0.0.1", "10.255.255.255", "172.16.0.1", "172.31.255.255", "192.168.0.1", "192.168.255.255"] {
+            assert!(ip.parse::<IpAddr>().unwrap().is_private(), "{ip} is private");
+        }
+        for ip in ["9.255.255.255", "11.0.0.0", "172.15.255.255", "172.32.0.0", "192.167.0.1", "192.169.0.1", "8.8.8.8"] {
+            assert!(!ip.parse::<IpAddr>().unwrap().is_private(), "{ip} is public");
+        }
+        assert!(IpAddr::V6([0xfd00, 0, 0, 0, 0, 0, 0, 1]).is_private());
+        assert!(!IpAddr::V6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]).is_private());
+    }
+
+    #[test]
+    fn loopback_and_broadcast() {
+        assert!(IpAddr::V4(127, 0, 0, 1).is_loopback());
+        assert!(IpAddr::V4(127, 1, 2, 3).is_loopback());
+        assert!(!IpAddr::V4(128, 0, 0, 1).is_loopback());
+        assert!(IpAddr::V6([0, 0, 0, 0, 0, 0, 0, 1]).is_loopback());
+        assert!(!IpAddr::V6([0; 8]).is_loopback());
+
+        assert!(IpAddr::V4(255, 255, 255, 255).is_broadcast());
+        assert!(!IpAddr::V4(255, 255, 255, 0).is_broadcast());
+        assert!(!IpAddr::V6([0xffff; 8]).is_broadcast());
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        for ip in ["127.0.0.1", "0.0.0.0", "::1", "::", "2001:db8::1", "fe80::", "1:2:3:4:5:6:7:8"] {
+            assert_eq!(ip.parse::<IpAddr>().unwrap().to_string(), ip);
+        }
+        assert_eq!("2001:DB8:0:0:0:0:0:1".parse(), Ok(IpAddr::V6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1])));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn parse_validates_octets_and_groups() {
+        assert_eq!("192.168.0.256".parse::<IpAddr>(), Err(ParseIpError::InvalidOctet(String::from("256"))));
+        assert_eq!("1.2.3.+4".parse::<IpAddr>(), Err(ParseIpError::InvalidOctet(String::from("+4"))));
+        assert_eq!("1..3.4".parse::<IpAddr>(), Err(ParseIpError::InvalidOctet(String::new())));
+        assert_eq!("1.2.3".parse::<IpAddr>(), Err(ParseIpError::WrongLength));
+        assert_eq!("1.2.3.4.5".parse::<IpAddr>(), Err(ParseIpError::WrongLength));
+        assert_eq!("2001:db8::g".parse::<IpAddr>(), Err(ParseIpError::InvalidGroup(String::from("g"))));
+        assert_eq!("1:2:3:4:5:6:7".parse::<IpAddr>(), Err(ParseIpError::WrongLength));
+        assert_eq!("1:2:3:4::5:6:7:8".parse::<IpAddr>(), Err(ParseIpError::WrongLength));
+    }
+}
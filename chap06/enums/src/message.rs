@@ -0,0 +1,95 @@
+// Adding a variant to a public enum breaks every `match` on it in other crates: they
+//    were exhaustive, and suddenly they aren't. #[non_exhaustive] makes that promise up
+//    front: outside this crate a match must always have a `_` arm, so new variants can
+//    be added later without breaking anyone.
+//
+// MessageV2 shows the other way out, for an enum that wasn't marked in time: leave the
+//    old one alone and add a new enum with the extra variant, plus a From conversion.
+
+/// ```compile_fail,E0004
+/// use enums::message::Message;
+///
+/// // listing every variant isn't enough outside the crate: more may be added
+/// fn name(m: &Message) -> &'static str {
+///     match m {
+///         Message::Quit => "quit",
+///         Message::Move { .. } => "move",
+///         Message::Write(_) => "write",
+///         Message::ChangeColor(..) => "change color",
+///     }
+/// }
+/// ```
+///
+/// ```
+/// use enums::message::Message;
+///
+/// fn name(m: &Message) -> &'static str {
+///     match m {
+///         Message::Quit => "quit",
+///         Message::Move { .. } => "move",
+///         Message::Write(_) => "write",
+///         Message::ChangeColor(..) => "change color",
+///         _ => "something newer",
+///     }
+/// }
+/// assert_eq!(name(&Message::Quit), "quit");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Message {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(String),
+    ChangeColor(i32, i32, i32),
+}
+
+impl Message {
+    pub fn call(&self) {
+        // a `_` arm here too, so adding a variant needs no change in call
+        match self {
+            Message::Move { x, y } => println!("moving to ({x}, {y})"),
+            Message::Write(text) => println!("writing {text:?}"),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MessageV2 {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(String),
+    ChangeColor(i32, i32, i32),
+    Broadcast { audience: Vec<String>, content: String },
+}
+
+// Every old message means the same thing in the new version. Inside this crate the match
+//    can (and should) be exhaustive: a new Message variant then fails to compile right here.
+impl From<Message> for MessageV2 {
+    fn from(message: Message) -> MessageV2 {
+        match message {
+            Message::Quit => MessageV2::Quit,
+            Message::Move { x, y } => MessageV2::Move { x, y },
+            Message::Write(text) => MessageV2::Write(text),
+            Message::ChangeColor(r, g, b) => MessageV2::ChangeColor(r, g, b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_message_converts() {
+        assert_eq!(MessageV2::from(Message::Quit), MessageV2::Quit);
+        assert_eq!(MessageV2::from(Message::Move { x: 1, y: -2 }), MessageV2::Move { x: 1, y: -2 });
+        assert_eq!(
+            MessageV2::from(Message::Write(String::from("hi"))),
+            MessageV2::Write(String::from("hi"))
+        );
+        let v2: MessageV2 = Message::ChangeColor(255, 0, 0).into();
+        assert_eq!(v2, MessageV2::ChangeColor(255, 0, 0));
+    }
+}
@@ -0,0 +1,170 @@
+// A state machine is a good fit for an enum: the machine is in exactly one state at a
+//    time, and each state carries only the data that makes sense in it (there's no
+//    balance while Idle, no item before one is chosen).
+//
+//    Idle --coin--> Accepting --select--> Dispensing --dispense--> Idle
+//
+// cancel() goes back to Idle from anywhere and hands back whatever money is still in.
+
+use std::collections::HashMap;
+use std::mem;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VendingState {
+    Idle,
+    Accepting { balance: u32 },
+    Dispensing { item: String, change: u32 },
+    // something was asked of the machine that it can't do from where it was; the next
+    //    coin or cancel clears it
+    Error(String),
+}
+
+pub struct VendingMachine {
+    state: VendingState,
+    // in cents
+    prices: HashMap<String, u32>,
+}
+
+impl VendingMachine {
+    pub fn new(prices: HashMap<String, u32>) -> VendingMachine {
+        VendingMachine { state: VendingState::Idle, prices }
+    }
+
+    pub fn state(&self) -> &VendingState {
+        &self.state
+    }
+
+    // What comes back out in the return tray: 0 when the coin was taken. While an item
+    //    is being dispensed the coin slot is shut, so the coin is handed straight back and
+    //    nothing changes; so is one the balance has no room for.
+    pub fn insert_coin(&mut self, cents: u32) -> u32 {
+        let (state, rejected) = match mem::replace(&mut self.state, VendingState::Idle) {
+            VendingState::Idle | VendingState::Error(_) => (VendingState::Accepting { balance: cents }, 0),
+            VendingState::Accepting { balance } => match balance.checked_add(cents) {
+                Some(balance) => (VendingState::Accepting { balance }, 0),
+                None => (VendingState::Accepting { balance }, cents),
+            },
+            dispensing @ VendingState::Dispensing { .. } => (dispensing, cents),
+        };
+        self.state = state;
+        rejected
+    }
+
+    // An unknown item, or one that costs more than the balance, leaves the machine
+    //    Accepting so more coins can go in, or another item be chosen
+    pub fn select_item(&mut self, name: &str) {
+        match &self.state {
+            VendingState::Accepting { balance } => {
+                if let Some(&price) = self.prices.get(name) {
+                    if price <= *balance {
+                        self.state = VendingState::Dispensing {
+                            item: name.to_string(),
+                            change: balance - price,
+                        };
+                    }
+                }
+            }
+            VendingState::Idle | VendingState::Error(_) => {
+                self.state = VendingState::Error(String::from("insert coins first"));
+            }
+            // already busy with an item
+            VendingState::Dispensing { .. } => {}
+        }
+    }
+
+    pub fn dispense(&mut self) -> Option<(String, u32)> {
+        match mem::replace(&mut self.state, VendingState::Idle) {
+            VendingState::Dispensing { item, change } => Some((item, change)),
+            other => {
+                self.state = other;
+                None
+            }
+        }
+    }
+
+    // Until the item has come out, all the money inserted can still be had back
+    pub fn cancel(&mut self) -> u32 {
+        let refund = match &self.state {
+            VendingState::Accepting { balance } => *balance,
+            VendingState::Dispensing { item, change } => self.prices[item] + change,
+            VendingState::Idle | VendingState::Error(_) => 0,
+        };
+        self.state = VendingState::Idle;
+        refund
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn machine() -> VendingMachine {
+        let prices = [("cola", 150), ("chips", 100)];
+        VendingMachine::new(prices.iter().map(|&(item, price)| (item.to_string(), price)).collect())
+    }
+
+    #[test]
+    fn complete_purchase() {
+        let mut machine = machine();
+        assert_eq!(machine.state(), &VendingState::Idle);
+        assert_eq!(machine.insert_coin(100), 0);
+        assert_eq!(machine.insert_coin(25), 0);
+        assert_eq!(machine.state(), &VendingState::Accepting { balance: 125 });
+
+        // not enough yet, and no such item: still accepting
+        machine.select_item("cola");
+        machine.select_item("candy");
+        assert_eq!(machine.state(), &VendingState::Accepting { balance: 125 });
+        assert_eq!(machine.dispense(), None);
+
+        machine.insert_coin(50);
+        machine.select_item("cola");
+        assert_eq!(machine.state(), &VendingState::Dispensing { item: String::from("cola"), change: 25 });
+        // the slot is shut while dispensing: the coin comes straight back
+        assert_eq!(machine.insert_coin(100), 100);
+        machine.select_item("chips");
+        assert_eq!(machine.dispense(), Some((String::from("cola"), 25)));
+        assert_eq!(machine.state(), &VendingState::Idle);
+        assert_eq!(machine.dispense(), None);
+    }
+
+    #[test]
+    fn coin_that_would_overflow_the_balance_comes_back() {
+        let mut machine = machine();
+        assert_eq!(machine.insert_coin(u32::MAX - 10), 0);
+        assert_eq!(machine.insert_coin(25), 25);
+        assert_eq!(machine.state(), &VendingState::Accepting { balance: u32::MAX - 10 });
+        // one that still fits is taken
+        assert_eq!(machine.insert_coin(10), 0);
+        assert_eq!(machine.cancel(), u32::MAX);
+    }
+
+    #[test]
+    fn cancel_mid_cycle_refunds_everything() {
+        let mut machine = machine();
+        machine.insert_coin(50);
+        machine.insert_coin(50);
+        assert_eq!(machine.cancel(), 100);
+        assert_eq!(machine.state(), &VendingState::Idle);
+        assert_eq!(machine.cancel(), 0);
+
+        // chosen but not yet dispensed: the price comes back too
+        machine.insert_coin(200);
+        machine.select_item("chips");
+        assert_eq!(machine.cancel(), 200);
+        assert_eq!(machine.dispense(), None);
+    }
+
+    #[test]
+    fn selecting_without_money_is_an_error() {
+        let mut machine = machine();
+        machine.select_item("chips");
+        assert_eq!(machine.state(), &VendingState::Error(String::from("insert coins first")));
+        assert_eq!(machine.dispense(), None);
+
+        // a coin clears it
+        machine.insert_coin(100);
+        machine.select_item("chips");
+        assert_eq!(machine.dispense(), Some((String::from("chips"), 0)));
+    }
+}
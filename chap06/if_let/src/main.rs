@@ -1,10 +1,12 @@
-
+// the demo only makes an Alaska quarter; the other variants are there for the patterns
+#[allow(dead_code)]
 #[derive(Debug)] // so we can inspect the state in a minute
 enum UsState {
     Alabama,
     Alaska,
 }
 
+#[allow(dead_code)]
 enum Coin {
     Penny,
     Nickel,
@@ -38,4 +40,190 @@ fn main() {
     } else {
         count += 1;
     }
+    println!("{count} coin(s) that weren't quarters");
+
+    // let-else: bind with a refutable pattern, or leave (return/break/continue/panic) right away
+    println!("{}", let_else_demo(Some(String::from("hello"))));
+    println!("{}", let_else_demo(None));
+    println!("{:?} {:?}", parse_point("3,-4"), parse_point("3;4"));
+
+    let user = User { name: String::from("ferris"), email: Some(String::from("ferris@rust-lang.org")), age: 8 };
+    println!("{:?}", contact_line(&user));
+
+    // while let: keep looping as long as the pattern matches
+    let mut stack = vec![1, 2, 3];
+    println!("{:?}, {stack:?} left", drain_stack(&mut stack));
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    tx.send(String::from("hi")).unwrap();
+    tx.send(String::from("there")).unwrap();
+    println!("{:?}", process_messages(rx));
+
+    println!("{:?}", countdown_values(3));
+}
+
+// pops until the stack is empty, so the result is the stack reversed
+fn drain_stack(stack: &mut Vec<i32>) -> Vec<i32> {
+    let mut reversed = Vec::with_capacity(stack.len());
+    while let Some(top) = stack.pop() {
+        reversed.push(top);
+    }
+    reversed
+}
+
+// everything that has already arrived; try_recv fails (ending the loop) as soon as the
+//    channel is empty, without waiting for more
+fn process_messages(receiver: std::sync::mpsc::Receiver<String>) -> Vec<String> {
+    let mut messages = Vec::new();
+    while let Ok(msg) = receiver.try_recv() {
+        messages.push(msg);
+    }
+    messages
+}
+
+struct Countdown {
+    remaining: u32,
+}
+
+impl Iterator for Countdown {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.remaining + 1)
+    }
+}
+
+// `for n in countdown` is the usual way to write this; it is what this loop desugars to
+#[allow(clippy::while_let_on_iterator)]
+fn countdown_values(from: u32) -> Vec<u32> {
+    let mut countdown = Countdown { remaining: from };
+    let mut values = Vec::new();
+    while let Some(n) = countdown.next() {
+        values.push(n);
+    }
+    values
+}
+
+fn let_else_demo(input: Option<String>) -> String {
+    let Some(text) = input else {
+        return String::from("nothing");
+    };
+    // `text` is in scope for the rest of the function, without the extra nesting of if let
+    format!("got {text}")
+}
+
+// "x,y" -> (x, y); each step bails out on failure
+//    (the first step could be `s.split_once(',')?`; let-else is spelled out here to compare)
+#[allow(clippy::question_mark)]
+fn parse_point(s: &str) -> Option<(i32, i32)> {
+    let Some((x, y)) = s.split_once(',') else {
+        return None;
+    };
+    let Ok(x) = x.trim().parse::<i32>() else {
+        return None;
+    };
+    let Ok(y) = y.trim().parse::<i32>() else {
+        return None;
+    };
+    Some((x, y))
+}
+
+struct User {
+    name: String,
+    email: Option<String>,
+    age: u32,
+}
+
+// destructure a struct and check its fields in one pattern
+fn contact_line(user: &User) -> Option<String> {
+    let User { name, email: Some(email), age: 1.. } = user else {
+        return None;
+    };
+    Some(format!("{name} <{email}>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the same as parse_point, written with match
+    fn parse_point_match(s: &str) -> Option<(i32, i32)> {
+        match s.split_once(',') {
+            Some((x, y)) => match (x.trim().parse(), y.trim().parse()) {
+                (Ok(x), Ok(y)) => Some((x, y)),
+                _ => None,
+            },
+            None => None,
+        }
+    }
+
+    fn user(email: Option<&str>, age: u32) -> User {
+        User { name: String::from("ferris"), email: email.map(String::from), age }
+    }
+
+    #[test]
+    fn drain_stack_reverses_and_empties() {
+        let mut stack = vec![1, 2, 3, 4];
+        assert_eq!(drain_stack(&mut stack), vec![4, 3, 2, 1]);
+        assert!(stack.is_empty());
+
+        let mut empty = Vec::new();
+        assert_eq!(drain_stack(&mut empty), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn process_messages_takes_what_is_there() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        for word in ["a", "b", "c"] {
+            tx.send(String::from(word)).unwrap();
+        }
+        // the sender is still alive, so only emptiness can have stopped the loop
+        assert_eq!(process_messages(rx), vec!["a", "b", "c"]);
+
+        let (tx, rx) = std::sync::mpsc::channel::<String>();
+        drop(tx);
+        assert!(process_messages(rx).is_empty());
+    }
+
+    #[test]
+    fn while_let_over_custom_iterator() {
+        assert_eq!(countdown_values(3), vec![3, 2, 1]);
+        assert_eq!(countdown_values(0), Vec::<u32>::new());
+        assert_eq!(countdown_values(5), Countdown { remaining: 5 }.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn let_else_early_return() {
+        assert_eq!(let_else_demo(None), "nothing");
+        assert_eq!(let_else_demo(Some(String::from("hi"))), "got hi");
+        assert_eq!(let_else_demo(Some(String::new())), "got ");
+    }
+
+    #[test]
+    fn parse_point_success_and_failures() {
+        assert_eq!(parse_point("3,4"), Some((3, 4)));
+        assert_eq!(parse_point(" -10 , 20 "), Some((-10, 20)));
+        assert_eq!(parse_point("3 4"), None);
+        assert_eq!(parse_point("x,4"), None);
+        assert_eq!(parse_point("3,y"), None);
+        assert_eq!(parse_point("3,4,5"), None);
+    }
+
+    #[test]
+    fn let_else_matches_match() {
+        for input in ["3,4", "0,0", "-1, 2", "", ",", "1,", "a,b", "99999999999,1", "1,2,3"] {
+            assert_eq!(parse_point(input), parse_point_match(input), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn struct_destructuring_with_let_else() {
+        assert_eq!(contact_line(&user(Some("f@rust-lang.org"), 8)), Some(String::from("ferris <f@rust-lang.org>")));
+        assert_eq!(contact_line(&user(None, 8)), None);
+        assert_eq!(contact_line(&user(Some("f@rust-lang.org"), 0)), None);
+    }
 }
@@ -0,0 +1,26 @@
+// A tiny key-value shell on top of a HashMap; all the work is in collections::repl::execute.
+use std::io::{self, BufRead, Write};
+
+use collections::repl::{self, ReplOutput, ReplState};
+
+fn main() -> io::Result<()> {
+    let mut state = ReplState::new();
+    println!("{}", repl::USAGE);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        // end of input (Ctrl-D) works like `quit`
+        let Some(line) = lines.next() else { break };
+        match repl::execute(&mut state, &line?) {
+            ReplOutput::Text(text) if text.is_empty() => {}
+            ReplOutput::Text(text) => println!("{text}"),
+            ReplOutput::Error(message) => println!("error: {message}"),
+            ReplOutput::Quit => break,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+// Reuses Vec<u8> buffers instead of allocating a fresh one for every response.
+//    A cleared Vec keeps its allocation, so a buffer that grew to 8 KiB once doesn't
+//    have to grow again the next time around.
+//
+// At most `capacity` idle buffers are kept; extra ones returned while the pool is
+//    full are simply freed, so a burst of traffic doesn't pin that memory forever.
+//    The Mutex makes it shareable between threads (in an Arc, or a static).
+pub struct BufferPool {
+    pool: Mutex<Vec<Vec<u8>>>,
+    capacity: usize,
+    acquired: AtomicUsize,
+}
+
+impl BufferPool {
+    pub fn new(capacity: usize) -> BufferPool {
+        BufferPool {
+            pool: Mutex::new(Vec::with_capacity(capacity)),
+            capacity,
+            acquired: AtomicUsize::new(0),
+        }
+    }
+
+    // An empty buffer, reused if there's an idle one. It goes back when the guard is dropped.
+    pub fn acquire(&self) -> PoolGuard<'_> {
+        self.acquired.fetch_add(1, Ordering::Relaxed);
+        let buf = self.pool.lock().unwrap().pop().unwrap_or_default();
+        PoolGuard { buf: Some(buf), pool: self }
+    }
+
+    // (idle buffers in the pool, acquire calls so far)
+    pub fn stats(&self) -> (usize, usize) {
+        (self.pool.lock().unwrap().len(), self.acquired.load(Ordering::Relaxed))
+    }
+
+    fn give_back(&self, mut buf: Vec<u8>) {
+        buf.clear();
+        let mut pool = self.pool.lock().unwrap();
+        if pool.len() < self.capacity {
+            pool.push(buf);
+        }
+    }
+}
+
+// A borrowed buffer; use it as a Vec<u8>
+pub struct PoolGuard<'a> {
+    // only None while being dropped
+    buf: Option<Vec<u8>>,
+    pool: &'a BufferPool,
+}
+
+impl Deref for PoolGuard<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        self.buf.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PoolGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        self.buf.as_mut().unwrap()
+    }
+}
+
+impl Drop for PoolGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(buf) = self.buf.take() {
+            self.pool.give_back(buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn dropped_guard_returns_its_buffer() {
+        let pool = BufferPool::new(4);
+        assert_eq!(pool.stats(), (0, 0));
+
+        let mut buf = pool.acquire();
+        buf.extend_from_slice(b"HTTP/1.1 200 OK\r\n");
+        let ptr = buf.as_ptr();
+        assert_eq!(pool.stats(), (0, 1));
+        drop(buf);
+        assert_eq!(pool.stats(), (1, 1));
+
+        // the same allocation comes back out
+        let again = pool.acquire();
+        assert_eq!(again.as_ptr(), ptr);
+        assert_eq!(pool.stats(), (0, 2));
+    }
+
+    #[test]
+    fn returned_buffers_are_cleared_but_keep_their_capacity() {
+        let pool = BufferPool::new(1);
+        {
+            let mut buf = pool.acquire();
+            write!(buf, "{}", "x".repeat(1000)).unwrap();
+        }
+        let buf = pool.acquire();
+        assert!(buf.is_empty());
+        assert!(buf.capacity() >= 1000);
+    }
+
+    #[test]
+    fn idle_list_never_exceeds_capacity() {
+        let pool = BufferPool::new(2);
+        let guards: Vec<PoolGuard> = (0..5).map(|_| pool.acquire()).collect();
+        assert_eq!(pool.stats(), (0, 5));
+        drop(guards);
+        assert_eq!(pool.stats(), (2, 5));
+
+        let zero = BufferPool::new(0);
+        drop(zero.acquire());
+        assert_eq!(zero.stats(), (0, 1));
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let pool = BufferPool::new(8);
+        std::thread::scope(|s| {
+            for n in 0..4 {
+                let pool = &pool;
+                s.spawn(move || {
+                    for _ in 0..100 {
+                        let mut buf = pool.acquire();
+                        buf.push(n);
+                        assert_eq!(buf.len(), 1);
+                    }
+                });
+            }
+        });
+        let (available, acquired) = pool.stats();
+        assert_eq!(acquired, 400);
+        assert!((1..=4).contains(&available));
+    }
+}
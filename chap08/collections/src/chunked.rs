@@ -0,0 +1,175 @@
+use std::sync::Mutex;
+use std::thread;
+
+// Runs a transformation over a Vec one chunk at a time instead of all at once.
+pub struct ChunkedProcessor<T> {
+    data: Vec<T>,
+    chunk_size: usize,
+}
+
+impl<T> ChunkedProcessor<T> {
+    // Panics if chunk_size is zero (same as slice::chunks).
+    pub fn new(data: Vec<T>, chunk_size: usize) -> ChunkedProcessor<T> {
+        assert!(chunk_size > 0, "chunk size must be non-zero");
+        ChunkedProcessor { data, chunk_size }
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.data.len().div_ceil(self.chunk_size)
+    }
+
+    // Applies f to each chunk and concatenates the outputs in chunk order.
+    pub fn process<F>(&self, f: F) -> Vec<T>
+    where
+        F: Fn(&[T]) -> Vec<T>,
+    {
+        self.progress(f, |_, _| {})
+    }
+
+    // Same result as process(), with the chunks spread over at most
+    //    thread::available_parallelism() scoped threads: each worker takes the next chunk
+    //    from a shared queue until none are left, so a slow chunk doesn't hold up the rest.
+    //    thread::scope lets the threads borrow self.data and f without 'static bounds, and
+    //    every output is put back at its chunk's index, so the order is kept.
+    pub fn process_parallel<F>(&self, f: F) -> Vec<T>
+    where
+        F: Fn(&[T]) -> Vec<T> + Sync,
+        T: Send + Sync,
+    {
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        self.process_with_workers(f, workers)
+    }
+
+    fn process_with_workers<F>(&self, f: F, workers: usize) -> Vec<T>
+    where
+        F: Fn(&[T]) -> Vec<T> + Sync,
+        T: Send + Sync,
+    {
+        let f = &f;
+        let queue = Mutex::new(self.data.chunks(self.chunk_size).enumerate());
+        let queue = &queue;
+        let workers = workers.clamp(1, self.chunk_count().max(1));
+
+        let mut outputs: Vec<Vec<T>> = Vec::with_capacity(self.chunk_count());
+        outputs.resize_with(self.chunk_count(), Vec::new);
+        thread::scope(|s| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    s.spawn(move || {
+                        let mut done = Vec::new();
+                        loop {
+                            // its own statement, so the lock is let go before f runs
+                            let next = queue.lock().unwrap().next();
+                            let Some((i, chunk)) = next else {
+                                return done;
+                            };
+                            done.push((i, f(chunk)));
+                        }
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                for (i, output) in handle.join().unwrap() {
+                    outputs[i] = output;
+                }
+            }
+        });
+        outputs.into_iter().flatten().collect()
+    }
+
+    // Like process(), calling on_chunk(completed, total) after every chunk.
+    pub fn progress<F, G>(&self, f: F, mut on_chunk: G) -> Vec<T>
+    where
+        F: Fn(&[T]) -> Vec<T>,
+        G: FnMut(usize, usize),
+    {
+        let total = self.chunk_count();
+        let mut out = Vec::with_capacity(self.data.len());
+        for (i, chunk) in self.data.chunks(self.chunk_size).enumerate() {
+            out.extend(f(chunk));
+            on_chunk(i + 1, total);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn doubled(chunk: &[i32]) -> Vec<i32> {
+        chunk.iter().map(|n| n * 2).collect()
+    }
+
+    #[test]
+    fn sequential_matches_plain_map() {
+        let data: Vec<i32> = (0..1000).collect();
+        let expected: Vec<i32> = data.iter().map(|n| n * 2).collect();
+
+        let processor = ChunkedProcessor::new(data, 64);
+        assert_eq!(processor.process(doubled), expected);
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let data: Vec<i32> = (0..1000).collect();
+        let processor = ChunkedProcessor::new(data, 64);
+        assert_eq!(processor.process_parallel(doubled), processor.process(doubled));
+    }
+
+    #[test]
+    fn workers_are_capped_and_share_the_chunks() {
+        let processor = ChunkedProcessor::new((0..1000).collect(), 10);
+        let threads = Mutex::new(HashSet::new());
+        let out = processor.process_with_workers(
+            |chunk: &[i32]| {
+                threads.lock().unwrap().insert(thread::current().id());
+                doubled(chunk)
+            },
+            3,
+        );
+        assert_eq!(out, processor.process(doubled));
+        // 100 chunks, but no more than 3 threads ran them
+        let count = threads.lock().unwrap().len();
+        assert!((1..=3).contains(&count), "{count} threads");
+
+        // more workers than chunks, or none at all, still gives every chunk once
+        let small = ChunkedProcessor::new((0..25).collect(), 10);
+        assert_eq!(small.process_with_workers(doubled, 64), small.process(doubled));
+        assert_eq!(small.process_with_workers(doubled, 0), small.process(doubled));
+    }
+
+    #[test]
+    fn chunk_outputs_may_change_length() {
+        // keep only the first element of every chunk
+        let processor = ChunkedProcessor::new((0..1000).collect(), 100);
+        let firsts = processor.process_parallel(|chunk: &[i32]| vec![chunk[0]]);
+        assert_eq!(firsts, (0..1000).step_by(100).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn progress_reports_every_chunk() {
+        let processor = ChunkedProcessor::new((0..1000).collect(), 300);
+        let mut calls = Vec::new();
+        let out = processor.progress(doubled, |done, total| calls.push((done, total)));
+
+        assert_eq!(out.len(), 1000);
+        assert_eq!(calls, vec![(1, 4), (2, 4), (3, 4), (4, 4)]);
+    }
+
+    #[test]
+    fn empty_input() {
+        let processor = ChunkedProcessor::new(Vec::<i32>::new(), 10);
+        assert_eq!(processor.chunk_count(), 0);
+        assert!(processor.process(doubled).is_empty());
+        assert!(processor.process_parallel(doubled).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn zero_chunk_size_panics() {
+        ChunkedProcessor::new(vec![1], 0);
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Buckets items by the key computed from each one; items keep their original order inside a bucket.
+pub fn group_by<K, V, F>(items: Vec<V>, key_fn: F) -> HashMap<K, Vec<V>>
+where
+    K: Eq + Hash,
+    F: Fn(&V) -> K,
+{
+    let mut groups: HashMap<K, Vec<V>> = HashMap::new();
+    for item in items {
+        groups.entry(key_fn(&item)).or_default().push(item);
+    }
+    groups
+}
+
+// Splits items in two: what `f` maps to Ok goes left, what it maps to Err goes right.
+pub fn partition_map<L, R, T, F>(items: Vec<T>, f: F) -> (Vec<L>, Vec<R>)
+where
+    F: Fn(T) -> Result<L, R>,
+{
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    for item in items {
+        match f(item) {
+            Ok(l) => left.push(l),
+            Err(r) => right.push(r),
+        }
+    }
+    (left, right)
+}
+
+// Maps every item to a Vec and concatenates the results in order.
+pub fn flat_map<T, U, F>(items: Vec<T>, f: F) -> Vec<U>
+where
+    F: Fn(T) -> Vec<U>,
+{
+    let mut out = Vec::new();
+    for item in items {
+        out.extend(f(item));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_by_word_length() {
+        let words = vec!["a", "bb", "cc", "d", "eee", "ff"];
+        let groups = group_by(words, |w| w.len());
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&1], vec!["a", "d"]);
+        assert_eq!(groups[&2], vec!["bb", "cc", "ff"]);
+        assert_eq!(groups[&3], vec!["eee"]);
+    }
+
+    #[test]
+    fn group_by_empty_input() {
+        let groups = group_by(Vec::<i32>::new(), |n| n % 2);
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn partition_map_evens_and_odds() {
+        let (evens, odds): (Vec<i32>, Vec<i32>) =
+            partition_map((1..=9).collect(), |n| if n % 2 == 0 { Ok(n) } else { Err(n) });
+
+        assert_eq!(evens, vec![2, 4, 6, 8]);
+        assert_eq!(odds, vec![1, 3, 5, 7, 9]);
+    }
+
+    #[test]
+    fn partition_map_can_change_types() {
+        let (numbers, bad): (Vec<i32>, Vec<String>) =
+            partition_map(vec!["1", "x", "3"], |s| s.parse().map_err(|_| s.to_string()));
+
+        assert_eq!(numbers, vec![1, 3]);
+        assert_eq!(bad, vec![String::from("x")]);
+    }
+
+    #[test]
+    fn flat_map_flattens_in_order() {
+        let flat = flat_map(vec![1, 2, 3], |n| vec![n; n]);
+        assert_eq!(flat, vec![1, 2, 2, 3, 3, 3]);
+
+        let chars = flat_map(vec!["ab", "", "c"], |s| s.chars().collect());
+        assert_eq!(chars, vec!['a', 'b', 'c']);
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+// HashMap uses SipHash by default: resistant to HashDoS attacks, but slowish for short keys.
+//    FNV-1a is a tiny non-cryptographic hash that is fast on short keys (no DoS resistance!).
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+pub struct FnvHasher {
+    state: u64,
+}
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher { state: FNV_OFFSET_BASIS }
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            // FNV-1a: xor first, then multiply (FNV-1 does it the other way around)
+            self.state ^= u64::from(byte);
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+// Hands a fresh FnvHasher to the map for every key it hashes
+#[derive(Clone, Copy, Default)]
+pub struct FnvBuildHasher;
+
+impl BuildHasher for FnvBuildHasher {
+    type Hasher = FnvHasher;
+
+    fn build_hasher(&self) -> FnvHasher {
+        FnvHasher::default()
+    }
+}
+
+pub type FnvHashMap<K, V> = HashMap<K, V, FnvBuildHasher>;
+
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+#[derive(Debug)]
+pub struct BenchResult {
+    pub siphash: Duration,
+    pub fnv: Duration,
+}
+
+// Inserts and then looks up every key in a map built with the given hasher
+fn insert_and_lookup<S: BuildHasher + Default>(keys: &[String]) -> (Duration, HashMap<&str, usize, S>) {
+    let start = Instant::now();
+    let mut map: HashMap<&str, usize, S> = HashMap::default();
+    for (i, key) in keys.iter().enumerate() {
+        map.insert(key, i);
+    }
+    let mut found = 0;
+    for key in keys {
+        if map.contains_key(key.as_str()) {
+            found += 1;
+        }
+    }
+    // keep the optimizer from dropping the lookups
+    std::hint::black_box(found);
+    (start.elapsed(), map)
+}
+
+pub fn bench_keys(n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("key{i}")).collect()
+}
+
+// Rough micro-benchmark (run in release mode for meaningful numbers):
+//    cargo test --release --lib hashing -- --nocapture
+pub fn benchmark(n: usize) -> BenchResult {
+    let keys = bench_keys(n);
+    let (siphash, _) = insert_and_lookup::<std::collections::hash_map::RandomState>(&keys);
+    let (fnv, _) = insert_and_lookup::<FnvBuildHasher>(&keys);
+
+    println!("{n} keys: SipHash {siphash:?}, FNV-1a {fnv:?}");
+    BenchResult { siphash, fnv }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_fnv1a_values() {
+        // reference values from the FNV specification
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn same_lookups_as_default_map() {
+        let keys = bench_keys(10_000);
+        let mut default_map = HashMap::new();
+        let mut fnv_map = FnvHashMap::default();
+        for (i, key) in keys.iter().enumerate() {
+            default_map.insert(key.clone(), i * 3);
+            fnv_map.insert(key.clone(), i * 3);
+        }
+
+        assert_eq!(default_map.len(), fnv_map.len());
+        for key in keys.iter().map(String::as_str).chain(["missing", "key-1", ""]) {
+            assert_eq!(default_map.get(key), fnv_map.get(key));
+        }
+    }
+
+    #[test]
+    fn benchmark_runs() {
+        let result = benchmark(100_000);
+        assert!(result.siphash > Duration::ZERO);
+        assert!(result.fnv > Duration::ZERO);
+    }
+}
@@ -1,9 +1,177 @@
 
 
-pub fn main() {
-    // The type HashMap<K, V> stores a mapping of keys of type K to values of type V using a hashing function
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use collections::hashing::FnvHashMap;
+use collections::stats::RunningStats;
+
+// Where each word appears: word -> sorted line numbers (1-based, each line at most once)
+#[derive(Debug, PartialEq)]
+pub struct WordIndex {
+    map: HashMap<String, Vec<usize>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    EmptyWord { row: usize },
+    // a quoted word without its closing quote, or with something other than `,` after it
+    BadQuote { row: usize },
+    InvalidLineNumber { row: usize, value: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::EmptyWord { row } => write!(f, "row {row}: empty word"),
+            ParseError::BadQuote { row } => write!(f, "row {row}: badly quoted word"),
+            ParseError::InvalidLineNumber { row, value } => {
+                write!(f, "row {row}: invalid line number `{value}`")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl WordIndex {
+    pub fn build_index(text: &str) -> WordIndex {
+        let mut map: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            for word in line.split_whitespace() {
+                let lines = map.entry(word.to_string()).or_default();
+                // lines are visited in order, so checking the last one is enough to stay sorted and unique
+                if lines.last() != Some(&line_no) {
+                    lines.push(line_no);
+                }
+            }
+        }
+        WordIndex { map }
+    }
+
+    // line numbers where the word appears, empty for unknown words
+    pub fn search(&self, word: &str) -> &[usize] {
+        self.map.get(word).map(|lines| &lines[..]).unwrap_or(&[])
+    }
+
+    // one `word,line1,line2,...` row per word, sorted by word so the output is stable.
+    //    Words keep their punctuation, so one with a `,` or `"` in it is quoted as in
+    //    any CSV file: `"hello,",1` and `"""hi""",2`
+    pub fn serialize_to_csv(&self) -> String {
+        let mut words: Vec<&String> = self.map.keys().collect();
+        words.sort();
+
+        let mut csv = String::new();
+        for word in words {
+            if word.contains([',', '"']) {
+                csv.push_str(&format!("\"{}\"", word.replace('"', "\"\"")));
+            } else {
+                csv.push_str(word);
+            }
+            for line in &self.map[word] {
+                csv.push_str(&format!(",{line}"));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn deserialize_from_csv(csv: &str) -> Result<WordIndex, ParseError> {
+        let mut map = HashMap::new();
+        for (i, row) in csv.lines().enumerate().filter(|(_, row)| !row.is_empty()) {
+            let row_no = i + 1;
+            let (word, rest) = split_word(row).ok_or(ParseError::BadQuote { row: row_no })?;
+            if word.is_empty() {
+                return Err(ParseError::EmptyWord { row: row_no });
+            }
+
+            let mut lines = Vec::new();
+            for field in rest.split(',').skip(1) {
+                let line = field.parse().map_err(|_| ParseError::InvalidLineNumber {
+                    row: row_no,
+                    value: field.to_string(),
+                })?;
+                lines.push(line);
+            }
+            lines.sort();
+            lines.dedup();
+            map.insert(word, lines);
+        }
+        Ok(WordIndex { map })
+    }
+}
+
+// The word at the start of a row, unquoted, and the rest of the row from the `,` after it
+//    (empty when the row is only a word); None for a quote that isn't closed properly
+fn split_word(row: &str) -> Option<(String, &str)> {
+    let Some(quoted) = row.strip_prefix('"') else {
+        let end = row.find(',').unwrap_or(row.len());
+        return Some((row[..end].to_string(), &row[end..]));
+    };
+
+    let mut word = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '"' {
+            word.push(c);
+            continue;
+        }
+        // `""` is a quote inside the word; a single one ends it
+        let rest = &quoted[i + 1..];
+        if rest.starts_with('"') {
+            word.push('"');
+            chars.next();
+        } else if rest.is_empty() || rest.starts_with(',') {
+            return Some((word, rest));
+        } else {
+            return None;
+        }
+    }
+    None
+}
+
+// How words are normalized before counting them
+#[derive(Debug, Clone, Copy)]
+pub struct NormalizeOpts {
+    // "World" and "world" count as the same word
+    pub case_fold: bool,
+    // "world," and "(world)" count as "world"; inner punctuation like "don't" is kept
+    pub strip_punctuation: bool,
+    // words shorter than this many chars (after normalizing) are skipped
+    pub min_len: usize,
+}
 
-    use std::collections::HashMap;
+impl Default for NormalizeOpts {
+    fn default() -> Self {
+        NormalizeOpts { case_fold: true, strip_punctuation: true, min_len: 1 }
+    }
+}
+
+pub fn word_frequencies_normalized(text: &str, opts: NormalizeOpts) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for word in text.split_whitespace() {
+        let word = if opts.strip_punctuation {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+        } else {
+            word
+        };
+        // str::to_lowercase (not char by char) so a char lowering to several chars,
+        //    like 'İ' -> "i̇", comes out the same way in every word that contains it
+        let word = if opts.case_fold { word.to_lowercase() } else { word.to_string() };
+        if word.is_empty() || word.chars().count() < opts.min_len {
+            continue;
+        }
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+// the bindings only show the API, so not all of them are read
+#[allow(unused_variables)]
+pub fn main() -> Result<(), Box<dyn Error>> {
+    // The type HashMap<K, V> stores a mapping of keys of type K to values of type V using a hashing function
 
     let mut scores = HashMap::new();
     scores.insert(String::from("Blue"), 10);
@@ -53,5 +221,168 @@ pub fn main() {
     }
 
     println!("{map:?}");
-    
+
+    // the same words, summarised on the fly without keeping their lengths around
+    let mut lengths = RunningStats::new();
+    for word in text.split_whitespace() {
+        lengths.update(word.chars().count() as f64);
+    }
+    println!(
+        "{} words, mean length {:.2}, std dev {:.2}",
+        lengths.count(),
+        lengths.mean(),
+        lengths.std_dev()
+    );
+
+    // "World" and "world," above would be two different keys; normalize words first to merge them
+    let counts = word_frequencies_normalized("Hello world! Wonderful World, hello.", NormalizeOpts::default());
+    println!("{counts:?}");
+
+    // By default, HashMap uses a hashing function called SipHash (resistant to DoS attacks).
+    //    Another hasher can be plugged in through the third type parameter, a BuildHasher.
+    let mut fnv_map: FnvHashMap<&str, i32> = FnvHashMap::default();
+    for word in text.split_whitespace() {
+        *fnv_map.entry(word).or_insert(0) += 1;
+    }
+    println!("same counts with FNV-1a: {}", fnv_map.len() == map.len() && fnv_map.iter().all(|(k, v)| map[k] == *v));
+
+    // Going one step further than counting: remember the lines each word appears on
+    let text = "hello world\nwonderful world\nhello again";
+    let index = WordIndex::build_index(text);
+    println!("'world' appears on lines {:?}", index.search("world"));
+
+    let csv = index.serialize_to_csv();
+    print!("{csv}");
+    let restored = WordIndex::deserialize_from_csv(&csv)?;
+    println!("round-trip ok: {}", restored == index);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "the quick brown fox
+jumps over the lazy dog
+a fox is quick
+the end is near
+dogs and foxes
+nothing here
+quick quick quick
+over and over
+the dog sleeps
+fox";
+
+    fn counts(text: &str, opts: NormalizeOpts) -> Vec<(String, usize)> {
+        let mut counts: Vec<_> = word_frequencies_normalized(text, opts).into_iter().collect();
+        counts.sort();
+        counts
+    }
+
+    fn pairs(expected: &[(&str, usize)]) -> Vec<(String, usize)> {
+        expected.iter().map(|&(w, n)| (w.to_string(), n)).collect()
+    }
+
+    #[test]
+    fn mixed_case_is_merged() {
+        let opts = NormalizeOpts::default();
+        assert_eq!(counts("World world WORLD wOrLd", opts), pairs(&[("world", 4)]));
+
+        let keep_case = NormalizeOpts { case_fold: false, ..opts };
+        assert_eq!(counts("World world", keep_case), pairs(&[("World", 1), ("world", 1)]));
+    }
+
+    #[test]
+    fn punctuation_is_stripped_at_word_edges_only() {
+        let text = "\"Hello,\" she said -- (hello!) don't... hello? don't";
+        assert_eq!(
+            counts(text, NormalizeOpts::default()),
+            pairs(&[("don't", 2), ("hello", 3), ("said", 1), ("she", 1)])
+        );
+
+        let raw = NormalizeOpts { strip_punctuation: false, ..NormalizeOpts::default() };
+        assert_eq!(counts("hello, hello", raw), pairs(&[("hello", 1), ("hello,", 1)]));
+    }
+
+    #[test]
+    fn short_words_are_skipped() {
+        let opts = NormalizeOpts { min_len: 3, ..NormalizeOpts::default() };
+        assert_eq!(counts("a an the a cat", opts), pairs(&[("cat", 1), ("the", 1)]));
+    }
+
+    #[test]
+    fn turkish_dotted_capital_i() {
+        // 'İ' lowercases to two chars, "i̇" (i + combining dot above)
+        let text = "İstanbul İSTANBUL istanbul";
+        let counts = word_frequencies_normalized(text, NormalizeOpts::default());
+        assert_eq!(counts["i\u{307}stanbul"], 2);
+        assert_eq!(counts["istanbul"], 1);
+        assert_eq!(counts.len(), 2);
+
+        // min_len counts the chars after lowering: "i̇" is 2 chars
+        let opts = NormalizeOpts { min_len: 2, ..NormalizeOpts::default() };
+        assert_eq!(word_frequencies_normalized("İ İ i", opts).len(), 1);
+    }
+
+    #[test]
+    fn german_sharp_s() {
+        let counts = word_frequencies_normalized("Straße STRAẞE straße STRASSE", NormalizeOpts::default());
+        // capital ẞ lowers to ß; "SS" stays "ss" since to_lowercase is not full case folding
+        assert_eq!(counts["straße"], 3);
+        assert_eq!(counts["strasse"], 1);
+    }
+
+    #[test]
+    fn search_finds_known_lines() {
+        let index = WordIndex::build_index(TEXT);
+        assert_eq!(index.search("the"), &[1, 2, 4, 9]);
+        assert_eq!(index.search("fox"), &[1, 3, 10]);
+        assert_eq!(index.search("quick"), &[1, 3, 7]);
+        assert_eq!(index.search("over"), &[2, 8]);
+        assert_eq!(index.search("dog"), &[2, 9]);
+        assert!(index.search("cat").is_empty());
+    }
+
+    #[test]
+    fn csv_rows_are_sorted_by_word() {
+        let index = WordIndex::build_index("b a\na c");
+        assert_eq!(index.serialize_to_csv(), "a,1,2\nb,1\nc,2\n");
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let index = WordIndex::build_index(TEXT);
+        let restored = WordIndex::deserialize_from_csv(&index.serialize_to_csv()).unwrap();
+        assert_eq!(restored, index);
+        assert_eq!(restored.search("fox"), &[1, 3, 10]);
+    }
+
+    #[test]
+    fn csv_round_trip_keeps_punctuation() {
+        let index = WordIndex::build_index("hello, world\nsay \"hi\"\nhello, again");
+        let csv = index.serialize_to_csv();
+        assert_eq!(csv, "\"\"\"hi\"\"\",2\nagain,3\n\"hello,\",1,3\nsay,2\nworld,1\n");
+
+        let restored = WordIndex::deserialize_from_csv(&csv).unwrap();
+        assert_eq!(restored, index);
+        assert_eq!(restored.search("hello,"), &[1, 3]);
+        assert_eq!(restored.search("\"hi\""), &[2]);
+        assert!(restored.search("hello").is_empty());
+    }
+
+    #[test]
+    fn csv_parse_errors() {
+        assert_eq!(
+            WordIndex::deserialize_from_csv("ok,1\n,2"),
+            Err(ParseError::EmptyWord { row: 2 })
+        );
+        assert_eq!(
+            WordIndex::deserialize_from_csv("word,1,x"),
+            Err(ParseError::InvalidLineNumber { row: 1, value: String::from("x") })
+        );
+        assert_eq!(WordIndex::deserialize_from_csv("\"open,1"), Err(ParseError::BadQuote { row: 1 }));
+        assert_eq!(WordIndex::deserialize_from_csv("\"a\"b,1"), Err(ParseError::BadQuote { row: 1 }));
+        assert_eq!(WordIndex::deserialize_from_csv("\"\",1"), Err(ParseError::EmptyWord { row: 1 }));
+    }
 }
@@ -0,0 +1,107 @@
+// Splits [min, max] of data into `bins` equal-width bins and counts how many values fall in each.
+//    Every bin is half-open [start, end) except the last one, which also includes max.
+//    When all values are equal the range is widened by 0.5 on both sides so the bins have a width.
+pub fn histogram(data: &[f64], bins: usize) -> Vec<(f64, f64, usize)> {
+    if data.is_empty() || bins == 0 {
+        return Vec::new();
+    }
+
+    let mut min = data.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min == max {
+        min -= 0.5;
+        max += 0.5;
+    }
+    let width = (max - min) / bins as f64;
+
+    let mut counts = vec![0; bins];
+    for &value in data {
+        let index = (((value - min) / width) as usize).min(bins - 1);
+        counts[index] += 1;
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let start = min + width * i as f64;
+            // use max itself for the last edge so rounding can't leave it out
+            let end = if i + 1 == bins { max } else { min + width * (i + 1) as f64 };
+            (start, end, count)
+        })
+        .collect()
+}
+
+// One line per bin: `start - end | ### count`, where the largest bin gets `bar_width` '#'s
+//    and the others are scaled (rounded) to it.
+pub fn render_histogram(hist: &[(f64, f64, usize)], bar_width: usize) -> String {
+    let max_count = hist.iter().map(|&(_, _, count)| count).max().unwrap_or(0);
+
+    let mut chart = String::new();
+    for &(start, end, count) in hist {
+        let len = (count * bar_width + max_count / 2)
+            .checked_div(max_count)
+            .unwrap_or(0);
+        chart.push_str(&format!("{start:.2} - {end:.2} | {} {count}\n", "#".repeat(len)));
+    }
+    chart
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bins_and_counts_for_sorted_input() {
+        let data = [1.0, 2.0, 2.0, 3.0, 4.0, 5.0, 5.0, 5.0, 9.0];
+        let hist = histogram(&data, 4);
+
+        assert_eq!(
+            hist,
+            vec![
+                (1.0, 3.0, 3),
+                (3.0, 5.0, 2),
+                (5.0, 7.0, 3),
+                (7.0, 9.0, 1),
+            ]
+        );
+        let total: usize = hist.iter().map(|&(_, _, count)| count).sum();
+        assert_eq!(total, data.len());
+    }
+
+    #[test]
+    fn max_value_lands_in_last_bin() {
+        let hist = histogram(&[0.0, 10.0], 5);
+        assert_eq!(hist[0].2, 1);
+        assert_eq!(hist[4], (8.0, 10.0, 1));
+    }
+
+    #[test]
+    fn empty_input_or_zero_bins() {
+        assert!(histogram(&[], 3).is_empty());
+        assert!(histogram(&[1.0, 2.0], 0).is_empty());
+    }
+
+    #[test]
+    fn identical_values_get_a_widened_range() {
+        let hist = histogram(&[2.0, 2.0, 2.0], 2);
+        assert_eq!(hist, vec![(1.5, 2.0, 0), (2.0, 2.5, 3)]);
+    }
+
+    #[test]
+    fn renders_three_bins() {
+        let hist = histogram(&[0.0, 1.0, 1.5, 2.0, 2.5, 3.0], 3);
+        let expected = "\
+0.00 - 1.00 | ### 1
+1.00 - 2.00 | ####### 2
+2.00 - 3.00 | ########## 3
+";
+        assert_eq!(render_histogram(&hist, 10), expected);
+    }
+
+    #[test]
+    fn renders_empty_bins_without_bars() {
+        let hist = [(0.0, 1.0, 0), (1.0, 2.0, 0)];
+        assert_eq!(render_histogram(&hist, 5), "0.00 - 1.00 |  0\n1.00 - 2.00 |  0\n");
+    }
+}
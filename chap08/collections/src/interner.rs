@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+// Keeps one copy of each distinct string and hands out references to it.
+//    Interned strings can be compared with ptr::eq (one pointer comparison) instead of
+//    byte by byte, and a thousand copies of the same word cost one allocation.
+//
+// The leak trade-off: every interned string is Box::leak'ed, so it lives until the
+//    program exits, even after the Interner is dropped. That's what makes the &'static str
+//    safe to hand out (nothing can free it under you), and it's fine for a bounded set of
+//    names such as identifiers or column headers. For an unbounded stream of distinct
+//    strings (user input, say) the memory only ever grows; there you'd want handles
+//    (indices into a Vec<String>) owned by the interner instead.
+#[derive(Debug, Default)]
+pub struct Interner {
+    map: HashMap<String, &'static str>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    pub fn intern(&mut self, s: &str) -> &'static str {
+        if let Some(&interned) = self.map.get(s) {
+            return interned;
+        }
+        let interned: &'static str = Box::leak(s.to_string().into_boxed_str());
+        self.map.insert(s.to_string(), interned);
+        interned
+    }
+
+    // Distinct strings interned so far
+    pub fn interned_count(&self) -> usize {
+        self.map.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn repeated_interns_share_one_copy() {
+        let mut interner = Interner::new();
+        let first = interner.intern("hello");
+        let owned = String::from("hel") + "lo";
+        let second = interner.intern(&owned);
+
+        assert!(ptr::eq(first, second));
+        // equal text, but a different allocation
+        assert!(!ptr::eq(first, owned.as_str()));
+        assert!(!ptr::eq(first, interner.intern("world")));
+    }
+
+    #[test]
+    fn count_grows_only_for_new_strings() {
+        let mut interner = Interner::new();
+        assert_eq!(interner.interned_count(), 0);
+        for word in "the cat and the hat and the bat".split(' ') {
+            interner.intern(word);
+        }
+        assert_eq!(interner.interned_count(), 5);
+        interner.intern("");
+        interner.intern("");
+        assert_eq!(interner.interned_count(), 6);
+    }
+
+    #[test]
+    fn references_outlive_the_interner() {
+        let kept = {
+            let mut interner = Interner::new();
+            interner.intern("still here")
+        };
+        assert_eq!(kept, "still here");
+    }
+}
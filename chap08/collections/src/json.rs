@@ -0,0 +1,411 @@
+use std::fmt;
+
+// Just enough JSON to save and load small documents like a Spreadsheet.
+//    Objects keep their keys in order, and every number is an f64 (like in JavaScript).
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    // The value of `key` if this is an object that has it
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+// Compact output, no spaces
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(b) => write!(f, "{b}"),
+            // f64's Display is the shortest text that parses back to the same number;
+            //    JSON has no NaN or infinity, so those become null
+            JsonValue::Number(x) if x.is_finite() => write!(f, "{x}"),
+            JsonValue::Number(_) => write!(f, "null"),
+            JsonValue::String(s) => write!(f, "{}", escape(s)),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{value}", escape(key))?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// `s` as a quoted JSON string literal
+pub fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonError {
+    UnexpectedEnd,
+    // `pos` is a byte offset into the input
+    UnexpectedChar { ch: char, pos: usize },
+    InvalidNumber { pos: usize },
+    InvalidEscape { pos: usize },
+    TrailingCharacters { pos: usize },
+    // the array or object starting here is nested more than MAX_DEPTH deep
+    TooDeep { pos: usize },
+    // well-formed JSON that doesn't have the expected shape
+    Schema(String),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::UnexpectedEnd => write!(f, "unexpected end of JSON input"),
+            JsonError::UnexpectedChar { ch, pos } => write!(f, "unexpected `{ch}` at byte {pos}"),
+            JsonError::InvalidNumber { pos } => write!(f, "invalid number at byte {pos}"),
+            JsonError::InvalidEscape { pos } => write!(f, "invalid escape sequence at byte {pos}"),
+            JsonError::TrailingCharacters { pos } => write!(f, "unexpected data after the JSON value at byte {pos}"),
+            JsonError::TooDeep { pos } => write!(f, "nested more than {MAX_DEPTH} deep at byte {pos}"),
+            JsonError::Schema(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+// Arrays and objects inside each other; every level is a call deeper in the parser, so
+//    without a limit "[[[[..." runs out of stack instead of returning an error
+pub const MAX_DEPTH: usize = 128;
+
+pub fn parse(input: &str) -> Result<JsonValue, JsonError> {
+    let mut parser = Parser { input, pos: 0, depth: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(JsonError::TrailingCharacters { pos: parser.pos });
+    }
+    Ok(value)
+}
+
+// Recursive descent: one method per kind of value, each leaving `pos` just after what it read
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    // arrays and objects open around pos
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Result<char, JsonError> {
+        let c = self.peek().ok_or(JsonError::UnexpectedEnd)?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        let pos = self.pos;
+        match self.next()? {
+            c if c == expected => Ok(()),
+            ch => Err(JsonError::UnexpectedChar { ch, pos }),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonValue, JsonError> {
+        self.skip_whitespace();
+        match self.peek().ok_or(JsonError::UnexpectedEnd)? {
+            '{' => self.nested(Self::object),
+            '[' => self.nested(Self::array),
+            '"' => self.string().map(JsonValue::String),
+            '-' | '0'..='9' => self.number(),
+            't' => self.literal("true", JsonValue::Bool(true)),
+            'f' => self.literal("false", JsonValue::Bool(false)),
+            'n' => self.literal("null", JsonValue::Null),
+            ch => Err(JsonError::UnexpectedChar { ch, pos: self.pos }),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<JsonValue, JsonError>) -> Result<JsonValue, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(JsonError::TooDeep { pos: self.pos });
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn literal(&mut self, word: &str, value: JsonValue) -> Result<JsonValue, JsonError> {
+        for expected in word.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    fn object(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            let pos = self.pos;
+            match self.next()? {
+                ',' => continue,
+                '}' => return Ok(JsonValue::Object(fields)),
+                ch => return Err(JsonError::UnexpectedChar { ch, pos }),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue, JsonError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            let pos = self.pos;
+            match self.next()? {
+                ',' => continue,
+                ']' => return Ok(JsonValue::Array(items)),
+                ch => return Err(JsonError::UnexpectedChar { ch, pos }),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            let pos = self.pos;
+            match self.next()? {
+                '"' => return Ok(out),
+                '\\' => match self.next()? {
+                    '"' => out.push('"'),
+                    '\\' => out.push('\\'),
+                    '/' => out.push('/'),
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => out.push(self.unicode_escape(pos)?),
+                    _ => return Err(JsonError::InvalidEscape { pos }),
+                },
+                ch if (ch as u32) < 0x20 => return Err(JsonError::UnexpectedChar { ch, pos }),
+                ch => out.push(ch),
+            }
+        }
+    }
+
+    // After `\u`; characters outside the BMP come as a surrogate pair like `\ud83d\ude00`
+    fn unicode_escape(&mut self, pos: usize) -> Result<char, JsonError> {
+        let first = self.hex4(pos)?;
+        let code = if (0xd800..0xdc00).contains(&first) {
+            if self.next()? != '\\' || self.next()? != 'u' {
+                return Err(JsonError::InvalidEscape { pos });
+            }
+            let second = self.hex4(pos)?;
+            if !(0xdc00..0xe000).contains(&second) {
+                return Err(JsonError::InvalidEscape { pos });
+            }
+            0x10000 + ((first - 0xd800) << 10) + (second - 0xdc00)
+        } else {
+            first
+        };
+        char::from_u32(code).ok_or(JsonError::InvalidEscape { pos })
+    }
+
+    fn hex4(&mut self, pos: usize) -> Result<u32, JsonError> {
+        let digits = self.input.get(self.pos..self.pos + 4).ok_or(JsonError::UnexpectedEnd)?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(JsonError::InvalidEscape { pos });
+        }
+        self.pos += 4;
+        u32::from_str_radix(digits, 16).map_err(|_| JsonError::InvalidEscape { pos })
+    }
+
+    // JSON's number grammar is stricter than f64::from_str ("1.", ".5", "inf" aren't allowed),
+    //    so find where the number ends by the grammar, then let Rust convert it
+    fn number(&mut self) -> Result<JsonValue, JsonError> {
+        let start = self.pos;
+        let bytes = self.input.as_bytes();
+        let digits = |pos: &mut usize| {
+            let from = *pos;
+            while *pos < bytes.len() && bytes[*pos].is_ascii_digit() {
+                *pos += 1;
+            }
+            *pos - from
+        };
+
+        let mut pos = start;
+        if bytes.get(pos) == Some(&b'-') {
+            pos += 1;
+        }
+        let int_digits = digits(&mut pos);
+        let leading_zero = int_digits > 1 && bytes[pos - int_digits] == b'0';
+        if int_digits == 0 || leading_zero {
+            return Err(JsonError::InvalidNumber { pos: start });
+        }
+        if bytes.get(pos) == Some(&b'.') {
+            pos += 1;
+            if digits(&mut pos) == 0 {
+                return Err(JsonError::InvalidNumber { pos: start });
+            }
+        }
+        if let Some(b'e' | b'E') = bytes.get(pos) {
+            pos += 1;
+            if let Some(b'+' | b'-') = bytes.get(pos) {
+                pos += 1;
+            }
+            if digits(&mut pos) == 0 {
+                return Err(JsonError::InvalidNumber { pos: start });
+            }
+        }
+
+        self.pos = pos;
+        self.input[start..pos]
+            .parse()
+            .map(JsonValue::Number)
+            .map_err(|_| JsonError::InvalidNumber { pos: start })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nested_values() {
+        let value = parse(r#" {"a": [1, -2.5e1, true, null], "b": {"c": "d"}, "e": []} "#).unwrap();
+        assert_eq!(
+            value,
+            JsonValue::Object(vec![
+                (
+                    String::from("a"),
+                    JsonValue::Array(vec![
+                        JsonValue::Number(1.0),
+                        JsonValue::Number(-25.0),
+                        JsonValue::Bool(true),
+                        JsonValue::Null,
+                    ])
+                ),
+                (
+                    String::from("b"),
+                    JsonValue::Object(vec![(String::from("c"), JsonValue::String(String::from("d")))])
+                ),
+                (String::from("e"), JsonValue::Array(vec![])),
+            ])
+        );
+        assert_eq!(value.get("b").and_then(|b| b.get("c")), Some(&JsonValue::String(String::from("d"))));
+        assert_eq!(value.get("z"), None);
+    }
+
+    #[test]
+    fn strings_escape_and_unescape() {
+        let tricky = "say \"hi\"\\ \n\t\u{1}\u{1f} 안녕 👋";
+        let escaped = escape(tricky);
+        assert_eq!(escaped, r#""say \"hi\"\\ \n\t\u0001\u001f 안녕 👋""#);
+        assert_eq!(parse(&escaped), Ok(JsonValue::String(String::from(tricky))));
+
+        assert_eq!(parse(r#""é\/😀""#), Ok(JsonValue::String(String::from("é/😀"))));
+    }
+
+    #[test]
+    fn display_is_compact_json() {
+        let value = JsonValue::Object(vec![
+            (String::from("n"), JsonValue::Number(0.1)),
+            (String::from("list"), JsonValue::Array(vec![JsonValue::Null, JsonValue::Bool(false)])),
+        ]);
+        assert_eq!(value.to_string(), r#"{"n":0.1,"list":[null,false]}"#);
+        assert_eq!(parse(&value.to_string()), Ok(value));
+    }
+
+    #[test]
+    fn malformed_input() {
+        assert_eq!(parse(""), Err(JsonError::UnexpectedEnd));
+        assert_eq!(parse("[1, 2"), Err(JsonError::UnexpectedEnd));
+        assert_eq!(parse("[1; 2]"), Err(JsonError::UnexpectedChar { ch: ';', pos: 2 }));
+        assert_eq!(parse("{1: 2}"), Err(JsonError::UnexpectedChar { ch: '1', pos: 1 }));
+        assert_eq!(parse("01"), Err(JsonError::InvalidNumber { pos: 0 }));
+        assert_eq!(parse("[1.]"), Err(JsonError::InvalidNumber { pos: 1 }));
+        assert_eq!(parse(r#""\x""#), Err(JsonError::InvalidEscape { pos: 1 }));
+        // a lone high surrogate
+        assert_eq!(parse(r#""\ud83d""#), Err(JsonError::InvalidEscape { pos: 1 }));
+        assert_eq!(parse("\"a\nb\""), Err(JsonError::UnexpectedChar { ch: '\n', pos: 2 }));
+        assert_eq!(parse("true false"), Err(JsonError::TrailingCharacters { pos: 5 }));
+        assert_eq!(parse("nul"), Err(JsonError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error_not_a_stack_overflow() {
+        assert_eq!(parse(&"[".repeat(100_000)), Err(JsonError::TooDeep { pos: MAX_DEPTH }));
+        let objects = r#"{"a":"#.repeat(MAX_DEPTH + 1);
+        assert_eq!(parse(&objects), Err(JsonError::TooDeep { pos: 5 * MAX_DEPTH }));
+
+        // right at the limit is fine, and siblings don't add up
+        let deepest = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(parse(&deepest).is_ok());
+        let too_deep = format!("[{deepest}]");
+        assert_eq!(parse(&too_deep), Err(JsonError::TooDeep { pos: MAX_DEPTH }));
+        let wide = format!("[{}]", [&deepest[1..deepest.len() - 1]; 3].join(","));
+        assert!(parse(&wide).is_ok());
+    }
+}
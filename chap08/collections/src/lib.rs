@@ -0,0 +1,15 @@
+pub mod vecutil;
+pub mod sorted_vec;
+pub mod histogram;
+pub mod functional;
+pub mod stats;
+pub mod chunked;
+pub mod hashing;
+pub mod maputil;
+pub mod lru;
+pub mod multimap;
+pub mod spreadsheet;
+pub mod json;
+pub mod repl;
+pub mod interner;
+pub mod buffer_pool;
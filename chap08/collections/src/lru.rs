@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+// Least-recently-used cache with a fixed capacity.
+//    Instead of a linked list, every access stamps the entry with an increasing counter;
+//    a BTreeMap from stamp to key gives us the oldest entry in O(log n) when we need to evict.
+pub struct LruCache<K: Eq + Hash + Clone, V> {
+    capacity: usize,
+    entries: HashMap<K, (V, u64)>,
+    recency: BTreeMap<u64, K>,
+    clock: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    // Panics if n is zero: a cache that can hold nothing isn't useful.
+    pub fn with_capacity(n: usize) -> LruCache<K, V> {
+        assert!(n > 0, "capacity must be non-zero");
+        LruCache {
+            capacity: n,
+            entries: HashMap::with_capacity(n),
+            recency: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    fn next_stamp(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    // Looks up key and marks it as the most recently used entry.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let stamp = self.next_stamp();
+        let (value, old_stamp) = self.entries.get_mut(key)?;
+        let key = self.recency.remove(old_stamp).expect("recency index out of sync");
+        self.recency.insert(stamp, key);
+        *old_stamp = stamp;
+        Some(value)
+    }
+
+    // Inserts or overwrites key; when a new key doesn't fit, the least recently used
+    //    entry is evicted and returned.
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        let stamp = self.next_stamp();
+
+        if let Some((old_value, old_stamp)) = self.entries.get_mut(&key) {
+            *old_value = value;
+            self.recency.remove(old_stamp);
+            *old_stamp = stamp;
+            self.recency.insert(stamp, key);
+            return None;
+        }
+
+        let evicted = if self.entries.len() == self.capacity {
+            self.recency.pop_first().map(|(_, oldest)| {
+                let (value, _) = self.entries.remove(&oldest).expect("recency index out of sync");
+                (oldest, value)
+            })
+        } else {
+            None
+        };
+
+        self.recency.insert(stamp, key.clone());
+        self.entries.insert(key, (value, stamp));
+        evicted
+    }
+
+    // Does not count as a use.
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_after_gets() {
+        let mut cache = LruCache::with_capacity(3);
+        assert_eq!(cache.put("a", 1), None);
+        assert_eq!(cache.put("b", 2), None);
+        assert_eq!(cache.put("c", 3), None);
+
+        // a becomes the most recent, so b is now the oldest
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.put("d", 4), Some(("b", 2)));
+
+        assert_eq!(cache.get(&"c"), Some(&3));
+        assert_eq!(cache.put("e", 5), Some(("a", 1)));
+
+        assert!(cache.contains(&"c"));
+        assert!(cache.contains(&"d"));
+        assert!(cache.contains(&"e"));
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn overwriting_does_not_grow_or_evict() {
+        let mut cache = LruCache::with_capacity(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(cache.put("a", 10), None);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&"a"), Some(&10));
+
+        // the overwrite refreshed a, so b goes first
+        assert_eq!(cache.put("c", 3), Some(("b", 2)));
+    }
+
+    #[test]
+    fn capacity_one() {
+        let mut cache = LruCache::with_capacity(1);
+        assert_eq!(cache.put(1, "one"), None);
+        assert_eq!(cache.put(2, "two"), Some((1, "one")));
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), Some(&"two"));
+        assert_eq!(cache.put(2, "deux"), None);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn missing_key_and_contains_do_not_refresh() {
+        let mut cache = LruCache::with_capacity(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(cache.get(&"z"), None);
+        assert!(cache.contains(&"a"));
+        assert_eq!(cache.put("c", 3), Some(("a", 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be non-zero")]
+    fn zero_capacity_panics() {
+        LruCache::<i32, i32>::with_capacity(0);
+    }
+}
@@ -3,15 +3,163 @@ mod vector;
 mod string;
 mod hashmap;
 
-fn main() {
-    section("VECTOR", vector::main);
-    section("STRING", string::main);
-    section("HASHMAP", hashmap::main);
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+type SectionFn = fn() -> Result<(), Box<dyn Error>>;
+
+const SECTIONS: [(&str, SectionFn); 3] = [
+    ("VECTOR", vector::main),
+    ("STRING", string::main),
+    ("HASHMAP", hashmap::main),
+];
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let reports = run_all();
+    print!("{}", summary(&reports));
+    overall_result(&reports)
+}
+
+// What a single section run produced: how long it took and whether it succeeded
+#[derive(Debug)]
+struct SectionReport {
+    tag: String,
+    duration: Duration,
+    result: Result<(), Box<dyn Error>>,
+}
+
+fn run_all() -> Vec<SectionReport> {
+    run_sections(&SECTIONS)
 }
 
-fn section<F>(tag: &str, f: F) 
-where F: FnOnce() -> () {
+// Runs every section even if an earlier one failed
+fn run_sections(sections: &[(&str, SectionFn)]) -> Vec<SectionReport> {
+    sections.iter().map(|&(tag, f)| section(tag, f)).collect()
+}
+
+fn section<F>(tag: &str, f: F) -> SectionReport
+where F: FnOnce() -> Result<(), Box<dyn Error>> {
     println!("=================== Section {tag} Start ======================");
-    f();
+    let start = Instant::now();
+    let result = f();
+    let duration = start.elapsed();
+    if let Err(e) = &result {
+        println!("Section {tag} failed: {e}");
+    }
     println!("=================== Section {tag} End   ======================");
+
+    SectionReport {
+        tag: tag.to_string(),
+        duration,
+        result,
+    }
+}
+
+// Summary table of all sections, slowest first
+fn summary(reports: &[SectionReport]) -> String {
+    let mut sorted: Vec<&SectionReport> = reports.iter().collect();
+    sorted.sort_by_key(|report| std::cmp::Reverse(report.duration));
+
+    let mut table = String::from("=================== Summary ======================\n");
+    for report in sorted {
+        let status = match &report.result {
+            Err(e) => format!("FAILED ({e})"),
+            Ok(()) => String::from("ok"),
+        };
+        table.push_str(&format!(
+            "{:<10} {:>12.3?}  {status}\n",
+            report.tag, report.duration
+        ));
+    }
+    table
+}
+
+// Err if any section failed, so main exits with a nonzero status
+fn overall_result(reports: &[SectionReport]) -> Result<(), Box<dyn Error>> {
+    let failed: Vec<&str> = reports
+        .iter()
+        .filter(|report| report.result.is_err())
+        .map(|report| report.tag.as_str())
+        .collect();
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} of {} sections failed: {}", failed.len(), reports.len(), failed.join(", ")).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn broken() -> Result<(), Box<dyn Error>> {
+        Err("hidden section failed".into())
+    }
+
+    #[test]
+    fn section_measures_duration() {
+        let report = section("SLEEP", || {
+            thread::sleep(Duration::from_millis(30));
+            Ok(())
+        });
+        assert_eq!(report.tag, "SLEEP");
+        assert!(report.duration >= Duration::from_millis(30));
+        assert!(report.result.is_ok());
+    }
+
+    #[test]
+    fn section_forwards_error() {
+        let report = section("BROKEN", || Err("something went wrong".into()));
+        assert_eq!(report.result.unwrap_err().to_string(), "something went wrong");
+    }
+
+    #[test]
+    fn summary_is_sorted_by_duration_and_marks_failures() {
+        let reports = vec![
+            section("FAST", || Ok(())),
+            section("SLOW", || {
+                thread::sleep(Duration::from_millis(40));
+                Ok(())
+            }),
+            section("BROKEN", || {
+                thread::sleep(Duration::from_millis(10));
+                Err("boom".into())
+            }),
+        ];
+
+        let table = summary(&reports);
+        let rows: Vec<&str> = table.lines().skip(1).collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("SLOW") && rows[0].ends_with("ok"));
+        assert!(rows[1].starts_with("BROKEN") && rows[1].ends_with("FAILED (boom)"));
+        assert!(rows[2].starts_with("FAST"));
+    }
+
+    #[test]
+    fn all_demo_sections_succeed() {
+        let reports = run_all();
+        assert_eq!(reports.len(), SECTIONS.len());
+        assert!(overall_result(&reports).is_ok());
+    }
+
+    #[test]
+    fn failing_section_does_not_stop_the_others() {
+        let sections: [(&str, SectionFn); 3] = [
+            ("VECTOR", vector::main),
+            ("HIDDEN", broken),
+            ("HASHMAP", hashmap::main),
+        ];
+        let reports = run_sections(&sections);
+
+        let tags: Vec<&str> = reports.iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, vec!["VECTOR", "HIDDEN", "HASHMAP"]);
+        assert!(reports[0].result.is_ok());
+        assert!(reports[1].result.is_err());
+        assert!(reports[2].result.is_ok());
+
+        let err = overall_result(&reports).unwrap_err();
+        assert_eq!(err.to_string(), "1 of 3 sections failed: HIDDEN");
+    }
 }
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Inverts a map into value -> keys having that value ("which teams scored 10?").
+//    The keys inside a bucket come out in the map's iteration order, which is arbitrary;
+//    use invert_sorted when the order matters.
+pub fn invert<K: Clone, V: Eq + Hash + Clone>(map: &HashMap<K, V>) -> HashMap<V, Vec<K>> {
+    let mut inverted: HashMap<V, Vec<K>> = HashMap::new();
+    for (key, value) in map {
+        inverted.entry(value.clone()).or_default().push(key.clone());
+    }
+    inverted
+}
+
+// Same as invert, with the keys of every bucket sorted so the result is deterministic.
+pub fn invert_sorted<K: Clone + Ord, V: Eq + Hash + Clone>(map: &HashMap<K, V>) -> HashMap<V, Vec<K>> {
+    let mut inverted = invert(map);
+    for keys in inverted.values_mut() {
+        keys.sort();
+    }
+    inverted
+}
+
+// Consumes the map so keys and values are moved instead of cloned (bucket order is arbitrary).
+pub fn invert_owned<K, V: Eq + Hash>(map: HashMap<K, V>) -> HashMap<V, Vec<K>> {
+    let mut inverted: HashMap<V, Vec<K>> = HashMap::new();
+    for (key, value) in map {
+        inverted.entry(value).or_default().push(key);
+    }
+    inverted
+}
+
+// Keys present in both maps, each with its value from a and from b; a key in only one of
+//    them is left out. Iterates over the smaller map, so it costs O(min(a, b)) lookups.
+pub fn zip_maps<'a, K: Eq + Hash, V1, V2>(
+    a: &'a HashMap<K, V1>,
+    b: &'a HashMap<K, V2>,
+) -> HashMap<&'a K, (&'a V1, &'a V2)> {
+    if a.len() <= b.len() {
+        a.iter().filter_map(|(key, va)| Some((key, (va, b.get(key)?)))).collect()
+    } else {
+        b.iter().filter_map(|(key, vb)| Some((key, (a.get(key)?, vb)))).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapDiff<K, V> {
+    // only in new
+    pub added: HashMap<K, V>,
+    // only in old
+    pub removed: HashMap<K, V>,
+    // in both, with different values: (old value, new value)
+    pub changed: HashMap<K, (V, V)>,
+}
+
+impl<K, V> MapDiff<K, V> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+// What happened between old and new. Keys whose value stayed the same appear nowhere.
+pub fn diff_maps<K: Eq + Hash + Clone, V: PartialEq + Clone>(
+    old: &HashMap<K, V>,
+    new: &HashMap<K, V>,
+) -> MapDiff<K, V> {
+    let mut diff = MapDiff {
+        added: HashMap::new(),
+        removed: HashMap::new(),
+        changed: HashMap::new(),
+    };
+    for (key, old_value) in old {
+        match new.get(key) {
+            None => {
+                diff.removed.insert(key.clone(), old_value.clone());
+            }
+            Some(new_value) if new_value != old_value => {
+                diff.changed.insert(key.clone(), (old_value.clone(), new_value.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+    for (key, new_value) in new {
+        if !old.contains_key(key) {
+            diff.added.insert(key.clone(), new_value.clone());
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores() -> HashMap<String, i32> {
+        HashMap::from([
+            (String::from("Blue"), 10),
+            (String::from("Yellow"), 50),
+            (String::from("Red"), 10),
+            (String::from("Green"), 10),
+        ])
+    }
+
+    #[test]
+    fn groups_keys_with_duplicate_values() {
+        let inverted = invert_sorted(&scores());
+        assert_eq!(inverted.len(), 2);
+        assert_eq!(inverted[&10], vec!["Blue", "Green", "Red"]);
+        assert_eq!(inverted[&50], vec!["Yellow"]);
+    }
+
+    #[test]
+    fn unordered_invert_has_same_buckets() {
+        let mut inverted = invert(&scores());
+        let mut tens = inverted.remove(&10).unwrap();
+        tens.sort();
+        assert_eq!(tens, vec!["Blue", "Green", "Red"]);
+        assert_eq!(inverted.remove(&50).unwrap(), vec!["Yellow"]);
+        assert!(inverted.is_empty());
+    }
+
+    #[test]
+    fn unique_values_give_single_key_buckets() {
+        let map = HashMap::from([("a", 1), ("b", 2), ("c", 3)]);
+        let inverted = invert(&map);
+        assert_eq!(inverted.len(), 3);
+        assert!(inverted.values().all(|keys| keys.len() == 1));
+        assert_eq!(inverted[&2], vec!["b"]);
+    }
+
+    #[test]
+    fn owned_invert_moves_keys() {
+        let mut inverted = invert_owned(scores());
+        let mut tens = inverted.remove(&10).unwrap();
+        tens.sort();
+        assert_eq!(tens, vec!["Blue", "Green", "Red"]);
+        assert_eq!(inverted[&50], vec![String::from("Yellow")]);
+    }
+
+    #[test]
+    fn empty_map() {
+        let map: HashMap<String, i32> = HashMap::new();
+        assert!(invert(&map).is_empty());
+        assert!(invert_sorted(&map).is_empty());
+        assert!(invert_owned(map).is_empty());
+    }
+
+    #[test]
+    fn zip_keeps_only_shared_keys() {
+        let names = HashMap::from([(1, "one"), (2, "two"), (3, "three")]);
+        let squares = HashMap::from([(2, 4), (3, 9), (4, 16), (5, 25)]);
+        let zipped = zip_maps(&names, &squares);
+        assert_eq!(zipped.len(), 2);
+        assert_eq!(zipped[&2], (&"two", &4));
+        assert_eq!(zipped[&3], (&"three", &9));
+        assert!(!zipped.contains_key(&1) && !zipped.contains_key(&4));
+
+        // the same whichever map is the smaller one
+        let flipped = zip_maps(&squares, &names);
+        assert_eq!(flipped[&2], (&4, &"two"));
+        assert_eq!(flipped.len(), 2);
+        assert!(zip_maps(&names, &HashMap::<i32, i32>::new()).is_empty());
+    }
+
+    #[test]
+    fn diff_sorts_keys_into_added_removed_changed() {
+        let old = scores();
+        let mut new = scores();
+        new.remove("Red");
+        new.insert(String::from("Yellow"), 55);
+        new.insert(String::from("Purple"), 5);
+
+        let diff = diff_maps(&old, &new);
+        assert_eq!(diff.added, HashMap::from([(String::from("Purple"), 5)]));
+        assert_eq!(diff.removed, HashMap::from([(String::from("Red"), 10)]));
+        assert_eq!(diff.changed, HashMap::from([(String::from("Yellow"), (50, 55))]));
+        // Blue and Green didn't change, so they're in none of the three
+        assert!(!diff.changed.contains_key("Blue"));
+
+        // the other way round, added and removed swap places
+        let back = diff_maps(&new, &old);
+        assert_eq!(back.added, diff.removed);
+        assert_eq!(back.removed, diff.added);
+        assert_eq!(back.changed["Yellow"], (55, 50));
+    }
+
+    #[test]
+    fn identical_maps_have_an_empty_diff() {
+        assert!(diff_maps(&scores(), &scores()).is_empty());
+        let empty: HashMap<String, i32> = HashMap::new();
+        assert!(diff_maps(&empty, &empty).is_empty());
+        assert_eq!(diff_maps(&empty, &scores()).added, scores());
+        assert_eq!(diff_maps(&scores(), &empty).removed, scores());
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// One key, many values: a HashMap<K, Vec<V>> that never keeps empty Vecs around,
+//    so every key returned by keys() has at least one value.
+#[derive(Debug)]
+pub struct MultiMap<K: Eq + Hash, V> {
+    map: HashMap<K, Vec<V>>,
+}
+
+impl<K: Eq + Hash, V> MultiMap<K, V> {
+    pub fn new() -> Self {
+        MultiMap { map: HashMap::new() }
+    }
+
+    // Appends value to the values of key.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().push(value);
+    }
+
+    // Values of key in insertion order, an empty slice for a missing key.
+    pub fn get(&self, key: &K) -> &[V] {
+        self.map.get(key).map(|values| &values[..]).unwrap_or(&[])
+    }
+
+    // Removes the first value of key equal to value; the key goes away with its last value.
+    pub fn remove_value(&mut self, key: &K, value: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        let Some(values) = self.map.get_mut(key) else {
+            return false;
+        };
+        let Some(index) = values.iter().position(|v| v == value) else {
+            return false;
+        };
+        values.remove(index);
+        if values.is_empty() {
+            self.map.remove(key);
+        }
+        true
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.map.keys()
+    }
+
+    // Every (key, value) pair, one per value.
+    pub fn iter_flat(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.map
+            .iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    // Number of keys (not values).
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<K: Eq + Hash, V> Default for MultiMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for MultiMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = MultiMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn colors() -> MultiMap<&'static str, &'static str> {
+        vec![
+            ("Favorite color", "Blue"),
+            ("Favorite color", "Green"),
+            ("Least favorite color", "Brown"),
+            ("Favorite color", "Blue"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn insert_and_get_keep_order() {
+        let map = colors();
+        assert_eq!(map.get(&"Favorite color"), &["Blue", "Green", "Blue"]);
+        assert_eq!(map.get(&"Least favorite color"), &["Brown"]);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn missing_key_is_empty_slice() {
+        let map = colors();
+        assert!(map.get(&"Shoe size").is_empty());
+        assert!(!map.contains_key(&"Shoe size"));
+    }
+
+    #[test]
+    fn remove_value_removes_one_occurrence() {
+        let mut map = colors();
+        assert!(map.remove_value(&"Favorite color", &"Blue"));
+        assert_eq!(map.get(&"Favorite color"), &["Green", "Blue"]);
+        assert!(!map.remove_value(&"Favorite color", &"Purple"));
+        assert!(!map.remove_value(&"Shoe size", &"Blue"));
+    }
+
+    #[test]
+    fn removing_last_value_removes_key() {
+        let mut map = colors();
+        assert!(map.remove_value(&"Least favorite color", &"Brown"));
+        assert!(!map.contains_key(&"Least favorite color"));
+
+        let keys: Vec<_> = map.keys().collect();
+        assert_eq!(keys, vec![&"Favorite color"]);
+    }
+
+    #[test]
+    fn iter_flat_yields_every_pair() {
+        let map = colors();
+        let mut pairs: Vec<(&str, &str)> = map.iter_flat().map(|(k, v)| (*k, *v)).collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                ("Favorite color", "Blue"),
+                ("Favorite color", "Blue"),
+                ("Favorite color", "Green"),
+                ("Least favorite color", "Brown"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_of_new_map() {
+        let map: MultiMap<String, i32> = MultiMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.keys().count(), 0);
+        assert_eq!(map.iter_flat().count(), 0);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+// What the collections-repl binary works on between commands
+#[derive(Debug, Default)]
+pub struct ReplState {
+    pub values: HashMap<String, String>,
+}
+
+impl ReplState {
+    pub fn new() -> ReplState {
+        ReplState::default()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplOutput {
+    // text to print, possibly empty
+    Text(String),
+    Error(String),
+    Quit,
+}
+
+pub const USAGE: &str = "\
+commands:
+  set <key> <value>       store a value (the rest of the line)
+  get <key>               print a value
+  del <key>               remove a value
+  inc <key>               add 1 to a number, starting from 0
+  list                    print every key and value
+  count-words <sentence>  count how often each word appears
+  quit                    leave";
+
+// Runs one line of input against the state. Never panics: bad input becomes an Error.
+pub fn execute(state: &mut ReplState, line: &str) -> ReplOutput {
+    let line = line.trim();
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let mut args = rest.split_whitespace();
+
+    match (command, args.next()) {
+        ("", _) => ReplOutput::Text(String::new()),
+        ("quit" | "exit", _) => ReplOutput::Quit,
+        ("help", _) => ReplOutput::Text(USAGE.to_string()),
+        ("set", Some(key)) => {
+            let value = rest[key.len()..].trim();
+            if value.is_empty() {
+                return usage_error("set needs a key and a value");
+            }
+            state.values.insert(key.to_string(), value.to_string());
+            ReplOutput::Text(String::from("OK"))
+        }
+        ("get", Some(key)) => match state.values.get(key) {
+            Some(value) => ReplOutput::Text(value.clone()),
+            None => ReplOutput::Error(format!("no value for `{key}`")),
+        },
+        ("del", Some(key)) => match state.values.remove(key) {
+            Some(value) => ReplOutput::Text(format!("deleted {key} ({value})")),
+            None => ReplOutput::Error(format!("no value for `{key}`")),
+        },
+        ("inc", Some(key)) => {
+            let value = state.values.entry(key.to_string()).or_insert_with(|| String::from("0"));
+            match value.parse::<i64>().ok().and_then(|n| n.checked_add(1)) {
+                Some(n) => {
+                    *value = n.to_string();
+                    ReplOutput::Text(value.clone())
+                }
+                None => ReplOutput::Error(format!("`{key}` holds `{value}`, which can't be incremented")),
+            }
+        }
+        ("list", _) => {
+            let mut entries: Vec<(&String, &String)> = state.values.iter().collect();
+            entries.sort();
+            let lines: Vec<String> = entries.iter().map(|(k, v)| format!("{k} = {v}")).collect();
+            ReplOutput::Text(lines.join("\n"))
+        }
+        ("count-words", Some(_)) => {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for word in rest.split_whitespace() {
+                *counts.entry(word).or_insert(0) += 1;
+            }
+            let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+            counts.sort();
+            let lines: Vec<String> = counts.iter().map(|(word, n)| format!("{word}: {n}")).collect();
+            ReplOutput::Text(lines.join("\n"))
+        }
+        ("set" | "get" | "del" | "inc", None) => usage_error(&format!("{command} needs a key")),
+        ("count-words", None) => usage_error("count-words needs a sentence"),
+        _ => usage_error(&format!("unknown command `{command}`")),
+    }
+}
+
+fn usage_error(message: &str) -> ReplOutput {
+    ReplOutput::Error(format!("{message}\n{USAGE}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> ReplOutput {
+        ReplOutput::Text(s.to_string())
+    }
+
+    #[test]
+    fn scripted_session() {
+        let mut state = ReplState::new();
+        let script = [
+            ("set name Ferris the crab", text("OK")),
+            ("get name", text("Ferris the crab")),
+            ("inc visits", text("1")),
+            ("  inc   visits  ", text("2")),
+            ("list", text("name = Ferris the crab\nvisits = 2")),
+            ("del name", text("deleted name (Ferris the crab)")),
+            ("list", text("visits = 2")),
+            ("count-words the cat saw the dog", text("cat: 1\ndog: 1\nsaw: 1\nthe: 2")),
+            ("", text("")),
+            ("quit", ReplOutput::Quit),
+        ];
+        for (line, expected) in script {
+            assert_eq!(execute(&mut state, line), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn errors_do_not_change_state() {
+        let mut state = ReplState::new();
+        assert_eq!(execute(&mut state, "get missing"), ReplOutput::Error(String::from("no value for `missing`")));
+        assert_eq!(execute(&mut state, "del missing"), ReplOutput::Error(String::from("no value for `missing`")));
+
+        execute(&mut state, "set word hello");
+        assert!(matches!(execute(&mut state, "inc word"), ReplOutput::Error(e) if e.contains("can't be incremented")));
+        assert_eq!(execute(&mut state, "get word"), text("hello"));
+
+        execute(&mut state, &format!("set big {}", i64::MAX));
+        assert!(matches!(execute(&mut state, "inc big"), ReplOutput::Error(_)));
+    }
+
+    #[test]
+    fn bad_commands_show_usage() {
+        let mut state = ReplState::new();
+        for line in ["frobnicate", "set", "set key", "get", "inc", "count-words", "SET a b"] {
+            match execute(&mut state, line) {
+                ReplOutput::Error(e) => assert!(e.ends_with(USAGE), "{line}: {e}"),
+                other => panic!("{line}: expected an error, got {other:?}"),
+            }
+        }
+        assert!(state.values.is_empty());
+        assert_eq!(execute(&mut state, "help"), text(USAGE));
+    }
+}
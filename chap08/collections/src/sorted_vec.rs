@@ -0,0 +1,142 @@
+// A Vec that always keeps its elements in ascending order.
+//    binary_search gives us the insert position in O(log n); the insert itself still shifts
+//    the tail like Vec::insert does, so an insert is O(n) overall.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortedVec<T: Ord> {
+    items: Vec<T>,
+}
+
+impl<T: Ord> SortedVec<T> {
+    pub fn new() -> Self {
+        SortedVec { items: Vec::new() }
+    }
+
+    // Inserts value keeping the order (duplicates allowed) and returns its index.
+    pub fn insert(&mut self, value: T) -> usize {
+        let index = match self.items.binary_search(&value) {
+            Ok(index) | Err(index) => index,
+        };
+        self.items.insert(index, value);
+        index
+    }
+
+    // Inserts value only if it isn't present yet.
+    //    Ok(index) where it was inserted, Err(index) of the existing equal element otherwise.
+    pub fn insert_unique(&mut self, value: T) -> Result<usize, usize> {
+        match self.items.binary_search(&value) {
+            Ok(existing) => Err(existing),
+            Err(index) => {
+                self.items.insert(index, value);
+                Ok(index)
+            }
+        }
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.items.binary_search(value).is_ok()
+    }
+
+    // Removes one element equal to value, if there is any.
+    pub fn remove(&mut self, value: &T) -> Option<T> {
+        match self.items.binary_search(value) {
+            Ok(index) => Some(self.items.remove(index)),
+            Err(_) => None,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl<T: Ord> Default for SortedVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Collecting sorts once at the end instead of doing n binary-search inserts.
+impl<T: Ord> FromIterator<T> for SortedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut items: Vec<T> = iter.into_iter().collect();
+        items.sort();
+        SortedVec { items }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // deterministic Fisher-Yates shuffle, good enough for test data
+    fn shuffled(n: u32, mut seed: u64) -> Vec<u32> {
+        let mut v: Vec<u32> = (0..n).map(|i| i % (n / 2 + 1)).collect();
+        for i in (1..v.len()).rev() {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let j = (seed >> 33) as usize % (i + 1);
+            v.swap(i, j);
+        }
+        v
+    }
+
+    #[test]
+    fn insert_keeps_slice_sorted() {
+        for seed in 0..20 {
+            let values = shuffled(200, seed);
+            let mut sorted = SortedVec::new();
+            for &v in &values {
+                sorted.insert(v);
+            }
+
+            let mut expected = values.clone();
+            expected.sort();
+            assert_eq!(sorted.as_slice(), &expected[..]);
+        }
+    }
+
+    #[test]
+    fn from_iter_sorts_once() {
+        let sorted: SortedVec<i32> = vec![5, 3, 9, 3, 1].into_iter().collect();
+        assert_eq!(sorted.as_slice(), &[1, 3, 3, 5, 9]);
+    }
+
+    #[test]
+    fn contains_and_remove() {
+        let mut sorted: SortedVec<i32> = vec![4, 2, 2, 8].into_iter().collect();
+        assert!(sorted.contains(&2));
+        assert!(!sorted.contains(&5));
+
+        assert_eq!(sorted.remove(&2), Some(2));
+        assert!(sorted.contains(&2));
+        assert_eq!(sorted.remove(&2), Some(2));
+        assert!(!sorted.contains(&2));
+        assert_eq!(sorted.remove(&2), None);
+        assert_eq!(sorted.as_slice(), &[4, 8]);
+    }
+
+    #[test]
+    fn insert_unique_rejects_duplicates_with_existing_index() {
+        let mut sorted = SortedVec::new();
+        assert_eq!(sorted.insert_unique(10), Ok(0));
+        assert_eq!(sorted.insert_unique(5), Ok(0));
+        assert_eq!(sorted.insert_unique(20), Ok(2));
+        assert_eq!(sorted.insert_unique(10), Err(1));
+        assert_eq!(sorted.as_slice(), &[5, 10, 20]);
+    }
+
+    #[test]
+    fn insert_allows_duplicates() {
+        let mut sorted = SortedVec::new();
+        sorted.insert(1);
+        sorted.insert(1);
+        assert_eq!(sorted.len(), 2);
+    }
+}
@@ -0,0 +1,496 @@
+use std::borrow::Borrow;
+use std::fmt;
+
+use crate::json::{self, JsonError, JsonValue};
+
+// A vector can only hold one type, but an enum's variants can carry different types,
+//    so a row of cells is a Vec<SpreadsheetCell>.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpreadsheetCell {
+    Int(i32),
+    Float(f64),
+    Text(String),
+    Formula(Formula),
+}
+
+// A column referenced by its header name
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnRef(pub String);
+
+impl ColumnRef {
+    pub fn new(name: &str) -> ColumnRef {
+        ColumnRef(name.to_string())
+    }
+}
+
+// Formulas are stored as-is and only computed when a cell is evaluated
+#[derive(Debug, Clone, PartialEq)]
+pub enum Formula {
+    Sum(ColumnRef),
+    Avg(ColumnRef),
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Formula::Sum(ColumnRef(column)) => write!(f, "=SUM({column})"),
+            Formula::Avg(ColumnRef(column)) => write!(f, "=AVG({column})"),
+        }
+    }
+}
+
+// A formula cell shows its formula text, not its value
+impl fmt::Display for SpreadsheetCell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpreadsheetCell::Int(n) => write!(f, "{n}"),
+            SpreadsheetCell::Float(x) => write!(f, "{x}"),
+            SpreadsheetCell::Text(s) => write!(f, "{s}"),
+            SpreadsheetCell::Formula(formula) => write!(f, "{formula}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    OutOfBounds { row: usize, col: usize },
+    NotNumeric { row: usize, col: usize },
+    MissingColumn(String),
+    // AVG over a column without a single number
+    NoValues(String),
+    // the formula (directly or through other formulas) depends on its own cell
+    Cycle { row: usize, col: usize },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::OutOfBounds { row, col } => write!(f, "no cell at row {row}, column {col}"),
+            EvalError::NotNumeric { row, col } => write!(f, "cell at row {row}, column {col} is not a number"),
+            EvalError::MissingColumn(name) => write!(f, "no column named `{name}`"),
+            EvalError::NoValues(name) => write!(f, "column `{name}` has no numbers"),
+            EvalError::Cycle { row, col } => write!(f, "cell at row {row}, column {col} refers to itself"),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spreadsheet {
+    columns: Vec<String>,
+    rows: Vec<Vec<SpreadsheetCell>>,
+}
+
+impl Spreadsheet {
+    pub fn new(columns: &[&str]) -> Spreadsheet {
+        Spreadsheet {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    // Panics if the row doesn't have one cell per column.
+    pub fn push_row(&mut self, row: Vec<SpreadsheetCell>) {
+        assert_eq!(row.len(), self.columns.len(), "row must have one cell per column");
+        self.rows.push(row);
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn rows(&self) -> &[Vec<SpreadsheetCell>] {
+        &self.rows
+    }
+
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> Option<&SpreadsheetCell> {
+        self.rows.get(row)?.get(col)
+    }
+
+    // Every cell of the named column, top to bottom; nothing if there is no such column
+    pub fn column<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a SpreadsheetCell> + 'a {
+        self.column_index(name)
+            .into_iter()
+            .flat_map(move |col| self.rows.iter().map(move |row| &row[col]))
+    }
+
+    // {"columns":["item",...],"rows":[[{"type":"text","value":"apple"},...],...]}
+    pub fn to_json(&self) -> String {
+        let columns = self.columns.iter().map(|c| JsonValue::String(c.clone())).collect();
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| JsonValue::Array(row.iter().map(cell_to_json).collect()))
+            .collect();
+        JsonValue::Object(vec![
+            (String::from("columns"), JsonValue::Array(columns)),
+            (String::from("rows"), JsonValue::Array(rows)),
+        ])
+        .to_string()
+    }
+
+    pub fn from_json(input: &str) -> Result<Spreadsheet, JsonError> {
+        let value = json::parse(input)?;
+        let columns = match value.get("columns") {
+            Some(JsonValue::Array(columns)) => columns
+                .iter()
+                .map(|c| match c {
+                    JsonValue::String(name) => Ok(name.clone()),
+                    _ => Err(schema("column names must be strings")),
+                })
+                .collect::<Result<Vec<String>, JsonError>>()?,
+            _ => return Err(schema("expected a \"columns\" array")),
+        };
+        let rows = match value.get("rows") {
+            Some(JsonValue::Array(rows)) => rows,
+            _ => return Err(schema("expected a \"rows\" array")),
+        };
+
+        let mut sheet = Spreadsheet { columns, rows: Vec::new() };
+        for (i, row) in rows.iter().enumerate() {
+            let cells = match row {
+                JsonValue::Array(cells) if cells.len() == sheet.columns.len() => cells,
+                _ => return Err(schema(&format!("row {i} must be an array of {} cells", sheet.columns.len()))),
+            };
+            let row = cells.iter().map(cell_from_json).collect::<Result<Vec<_>, _>>()?;
+            sheet.rows.push(row);
+        }
+        Ok(sheet)
+    }
+
+    // Numeric value of a cell, computing formulas on demand.
+    pub fn evaluate(&self, row: usize, col: usize) -> Result<f64, EvalError> {
+        self.evaluate_cell(row, col, &mut Vec::new())
+    }
+
+    // `visiting` holds the formula cells currently being computed; meeting one of them again is a cycle
+    fn evaluate_cell(&self, row: usize, col: usize, visiting: &mut Vec<(usize, usize)>) -> Result<f64, EvalError> {
+        match self.cell(row, col).ok_or(EvalError::OutOfBounds { row, col })? {
+            SpreadsheetCell::Int(n) => Ok(f64::from(*n)),
+            SpreadsheetCell::Float(x) => Ok(*x),
+            SpreadsheetCell::Text(_) => Err(EvalError::NotNumeric { row, col }),
+            SpreadsheetCell::Formula(formula) => {
+                if visiting.contains(&(row, col)) {
+                    return Err(EvalError::Cycle { row, col });
+                }
+                visiting.push((row, col));
+                let value = self.evaluate_formula(formula, visiting);
+                visiting.pop();
+                value
+            }
+        }
+    }
+
+    fn evaluate_formula(&self, formula: &Formula, visiting: &mut Vec<(usize, usize)>) -> Result<f64, EvalError> {
+        let (ColumnRef(name), average) = match formula {
+            Formula::Sum(column) => (column, false),
+            Formula::Avg(column) => (column, true),
+        };
+        let col = self
+            .column_index(name)
+            .ok_or_else(|| EvalError::MissingColumn(name.clone()))?;
+
+        // like SUM() in a real spreadsheet, text cells in the column are ignored
+        let mut total = 0.0;
+        let mut count = 0;
+        for row in 0..self.rows.len() {
+            match self.evaluate_cell(row, col, visiting) {
+                Ok(value) => {
+                    total += value;
+                    count += 1;
+                }
+                Err(EvalError::NotNumeric { .. }) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        if !average {
+            Ok(total)
+        } else if count == 0 {
+            Err(EvalError::NoValues(name.clone()))
+        } else {
+            Ok(total / count as f64)
+        }
+    }
+}
+
+fn schema(message: &str) -> JsonError {
+    JsonError::Schema(message.to_string())
+}
+
+// Every cell is an object tagged with its "type"
+fn cell_to_json(cell: &SpreadsheetCell) -> JsonValue {
+    let tagged = |tag: &str, key: &str, value: JsonValue| {
+        JsonValue::Object(vec![
+            (String::from("type"), JsonValue::String(tag.to_string())),
+            (key.to_string(), value),
+        ])
+    };
+    match cell {
+        SpreadsheetCell::Int(n) => tagged("int", "value", JsonValue::Number(f64::from(*n))),
+        SpreadsheetCell::Float(x) if x.is_finite() => tagged("float", "value", JsonValue::Number(*x)),
+        // JSON numbers can't be NaN or infinite, so those are written as "NaN", "inf" or "-inf"
+        SpreadsheetCell::Float(x) => tagged("float", "value", JsonValue::String(x.to_string())),
+        SpreadsheetCell::Text(s) => tagged("text", "value", JsonValue::String(s.clone())),
+        SpreadsheetCell::Formula(Formula::Sum(ColumnRef(c))) => tagged("sum", "column", JsonValue::String(c.clone())),
+        SpreadsheetCell::Formula(Formula::Avg(ColumnRef(c))) => tagged("avg", "column", JsonValue::String(c.clone())),
+    }
+}
+
+fn cell_from_json(value: &JsonValue) -> Result<SpreadsheetCell, JsonError> {
+    let tag = match value.get("type") {
+        Some(JsonValue::String(tag)) => tag.as_str(),
+        _ => return Err(schema("cell must have a \"type\"")),
+    };
+    let column = || match value.get("column") {
+        Some(JsonValue::String(c)) => Ok(ColumnRef(c.clone())),
+        _ => Err(schema(&format!("{tag} cell must have a \"column\" string"))),
+    };
+
+    match (tag, value.get("value")) {
+        ("int", Some(&JsonValue::Number(x))) => {
+            // every i32 is exactly representable as an f64, so this only rejects numbers that were never i32s
+            if x.fract() == 0.0 && x >= f64::from(i32::MIN) && x <= f64::from(i32::MAX) {
+                Ok(SpreadsheetCell::Int(x as i32))
+            } else {
+                Err(schema(&format!("{x} is not an i32")))
+            }
+        }
+        ("float", Some(&JsonValue::Number(x))) => Ok(SpreadsheetCell::Float(x)),
+        ("float", Some(JsonValue::String(s))) => s
+            .parse()
+            .map(SpreadsheetCell::Float)
+            .map_err(|_| schema(&format!("`{s}` is not a float"))),
+        ("text", Some(JsonValue::String(s))) => Ok(SpreadsheetCell::Text(s.clone())),
+        ("sum", _) => Ok(SpreadsheetCell::Formula(Formula::Sum(column()?))),
+        ("avg", _) => Ok(SpreadsheetCell::Formula(Formula::Avg(column()?))),
+        ("int" | "float" | "text", _) => Err(schema(&format!("{tag} cell has a missing or wrong \"value\""))),
+        _ => Err(schema(&format!("unknown cell type `{tag}`"))),
+    }
+}
+
+// Adapters for any iterator of cells, borrowed (`row.iter()`) or owned (`row.into_iter()`),
+//    e.g. sheet.column("price").numbers().sum::<f64>()
+// Like other iterator adapters they do nothing until consumed.
+pub trait CellIterExt: Iterator + Sized
+where
+    Self::Item: Borrow<SpreadsheetCell>,
+{
+    // Int and Float cells as f64; formulas are not evaluated
+    fn numbers(self) -> impl Iterator<Item = f64> {
+        self.filter_map(|cell| match *cell.borrow() {
+            SpreadsheetCell::Int(n) => Some(f64::from(n)),
+            SpreadsheetCell::Float(x) => Some(x),
+            _ => None,
+        })
+    }
+
+    fn texts(self) -> impl Iterator<Item = String> {
+        self.filter_map(|cell| match cell.borrow() {
+            SpreadsheetCell::Text(s) => Some(s.clone()),
+            _ => None,
+        })
+    }
+
+    // Cells that hold something. Every variant does for now, so this passes everything through.
+    fn filled(self) -> impl Iterator<Item = Self::Item> {
+        self.filter(|cell| match cell.borrow() {
+            SpreadsheetCell::Int(_) | SpreadsheetCell::Float(_) | SpreadsheetCell::Text(_) | SpreadsheetCell::Formula(_) => true,
+        })
+    }
+}
+
+impl<I> CellIterExt for I
+where
+    I: Iterator,
+    I::Item: Borrow<SpreadsheetCell>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet() -> Spreadsheet {
+        let mut sheet = Spreadsheet::new(&["item", "price", "total"]);
+        sheet.push_row(vec![
+            SpreadsheetCell::Text(String::from("apple")),
+            SpreadsheetCell::Int(3),
+            SpreadsheetCell::Formula(Formula::Sum(ColumnRef::new("price"))),
+        ]);
+        sheet.push_row(vec![
+            SpreadsheetCell::Text(String::from("pear")),
+            SpreadsheetCell::Float(1.5),
+            SpreadsheetCell::Formula(Formula::Avg(ColumnRef::new("price"))),
+        ]);
+        sheet.push_row(vec![
+            SpreadsheetCell::Text(String::from("plum")),
+            SpreadsheetCell::Text(String::from("n/a")),
+            SpreadsheetCell::Float(0.0),
+        ]);
+        sheet
+    }
+
+    #[test]
+    fn sum_and_avg_over_mixed_numeric_column() {
+        let sheet = sheet();
+        assert_eq!(sheet.evaluate(0, 2), Ok(4.5));
+        assert_eq!(sheet.evaluate(1, 2), Ok(2.25));
+        assert_eq!(sheet.evaluate(0, 1), Ok(3.0));
+    }
+
+    #[test]
+    fn plain_cells_evaluate_to_themselves_or_fail() {
+        let sheet = sheet();
+        assert_eq!(sheet.evaluate(1, 1), Ok(1.5));
+        assert_eq!(sheet.evaluate(0, 0), Err(EvalError::NotNumeric { row: 0, col: 0 }));
+        assert_eq!(sheet.evaluate(9, 0), Err(EvalError::OutOfBounds { row: 9, col: 0 }));
+    }
+
+    #[test]
+    fn missing_column_is_an_error() {
+        let mut sheet = sheet();
+        sheet.push_row(vec![
+            SpreadsheetCell::Text(String::from("bad")),
+            SpreadsheetCell::Formula(Formula::Sum(ColumnRef::new("quantity"))),
+            SpreadsheetCell::Int(0),
+        ]);
+        assert_eq!(sheet.evaluate(3, 1), Err(EvalError::MissingColumn(String::from("quantity"))));
+    }
+
+    #[test]
+    fn self_reference_is_reported_not_recursed() {
+        let mut sheet = Spreadsheet::new(&["total"]);
+        sheet.push_row(vec![SpreadsheetCell::Int(1)]);
+        sheet.push_row(vec![SpreadsheetCell::Formula(Formula::Sum(ColumnRef::new("total")))]);
+
+        assert_eq!(sheet.evaluate(1, 0), Err(EvalError::Cycle { row: 1, col: 0 }));
+    }
+
+    #[test]
+    fn indirect_cycle_is_reported() {
+        let mut sheet = Spreadsheet::new(&["a", "b"]);
+        sheet.push_row(vec![
+            SpreadsheetCell::Formula(Formula::Sum(ColumnRef::new("b"))),
+            SpreadsheetCell::Formula(Formula::Sum(ColumnRef::new("a"))),
+        ]);
+        assert_eq!(sheet.evaluate(0, 0), Err(EvalError::Cycle { row: 0, col: 0 }));
+    }
+
+    #[test]
+    fn avg_without_numbers() {
+        let mut sheet = Spreadsheet::new(&["name", "avg"]);
+        sheet.push_row(vec![
+            SpreadsheetCell::Text(String::from("x")),
+            SpreadsheetCell::Formula(Formula::Avg(ColumnRef::new("name"))),
+        ]);
+        assert_eq!(sheet.evaluate(0, 1), Err(EvalError::NoValues(String::from("name"))));
+    }
+
+    #[test]
+    fn formula_cells_display_their_formula() {
+        let sheet = sheet();
+        let shown: Vec<String> = sheet.rows()[0].iter().map(|c| c.to_string()).collect();
+        assert_eq!(shown, vec!["apple", "3", "=SUM(price)"]);
+        assert_eq!(sheet.rows()[1][2].to_string(), "=AVG(price)");
+    }
+
+    fn demo_row() -> Vec<SpreadsheetCell> {
+        vec![
+            SpreadsheetCell::Int(3),
+            SpreadsheetCell::Text(String::from("blue")),
+            SpreadsheetCell::Float(10.12),
+        ]
+    }
+
+    #[test]
+    fn numbers_and_texts_of_a_row() {
+        let row = demo_row();
+        assert_eq!(row.iter().numbers().sum::<f64>(), 13.12);
+        assert_eq!(row.iter().numbers().max_by(|a, b| a.total_cmp(b)), Some(10.12));
+        assert_eq!(row.iter().texts().collect::<Vec<_>>(), vec!["blue"]);
+        assert_eq!(row.iter().filled().count(), 3);
+
+        // owned cells work the same way
+        let owned: Vec<f64> = demo_row().into_iter().numbers().collect();
+        assert_eq!(owned, vec![3.0, 10.12]);
+        let filled: Vec<SpreadsheetCell> = demo_row().into_iter().filled().collect();
+        assert_eq!(filled, demo_row());
+    }
+
+    #[test]
+    fn column_math() {
+        let sheet = sheet();
+        assert_eq!(sheet.column("price").numbers().sum::<f64>(), 4.5);
+        assert_eq!(sheet.column("item").texts().collect::<Vec<_>>(), vec!["apple", "pear", "plum"]);
+        // formulas are not evaluated, so only the plain 0.0 counts
+        assert_eq!(sheet.column("total").numbers().collect::<Vec<_>>(), vec![0.0]);
+        assert_eq!(sheet.column("nope").count(), 0);
+    }
+
+    #[test]
+    fn adapters_are_lazy() {
+        let mut seen = 0;
+        let first = demo_row()
+            .into_iter()
+            .inspect(|_| seen += 1)
+            .numbers()
+            .next();
+        assert_eq!(first, Some(3.0));
+        assert_eq!(seen, 1);
+    }
+
+    #[test]
+    fn json_round_trip_with_every_cell_kind() {
+        let mut sheet = sheet();
+        sheet.push_row(vec![
+            SpreadsheetCell::Text(String::from("\"quoted\" \\ back\nslash\t\u{1} 🍐")),
+            SpreadsheetCell::Float(0.1 + 0.2),
+            SpreadsheetCell::Int(i32::MIN),
+        ]);
+        sheet.push_row(vec![
+            SpreadsheetCell::Text(String::new()),
+            SpreadsheetCell::Float(-1.5e-300),
+            SpreadsheetCell::Float(f64::INFINITY),
+        ]);
+
+        let json = sheet.to_json();
+        assert!(json.starts_with(r#"{"columns":["item","price","total"],"rows":[[{"type":"text","value":"apple"},{"type":"int","value":3},{"type":"sum","column":"price"}]"#));
+        assert_eq!(Spreadsheet::from_json(&json), Ok(sheet));
+    }
+
+    #[test]
+    fn json_nan_round_trips() {
+        let mut sheet = Spreadsheet::new(&["x"]);
+        sheet.push_row(vec![SpreadsheetCell::Float(f64::NAN)]);
+        let back = Spreadsheet::from_json(&sheet.to_json()).unwrap();
+        assert!(matches!(back.cell(0, 0), Some(SpreadsheetCell::Float(x)) if x.is_nan()));
+    }
+
+    #[test]
+    fn malformed_json_is_an_error() {
+        assert_eq!(
+            Spreadsheet::from_json(r#"{"columns":["a"],"rows":[[{"type":"int","value":1}]"#),
+            Err(JsonError::UnexpectedEnd)
+        );
+        assert!(matches!(Spreadsheet::from_json("{\"columns\" [\"a\"]}"), Err(JsonError::UnexpectedChar { ch: '[', .. })));
+
+        let wrong_shape = [
+            r#"{"rows":[]}"#,
+            r#"{"columns":[1],"rows":[]}"#,
+            r#"{"columns":["a","b"],"rows":[[{"type":"int","value":1}]]}"#,
+            r#"{"columns":["a"],"rows":[[{"type":"int","value":1.5}]]}"#,
+            r#"{"columns":["a"],"rows":[[{"type":"text","value":3}]]}"#,
+            r#"{"columns":["a"],"rows":[[{"type":"sum"}]]}"#,
+            r#"{"columns":["a"],"rows":[[{"type":"date","value":"today"}]]}"#,
+        ];
+        for input in wrong_shape {
+            assert!(matches!(Spreadsheet::from_json(input), Err(JsonError::Schema(_))), "{input}");
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    // population variance (divided by n, not n - 1)
+    pub variance: f64,
+    pub std_dev: f64,
+}
+
+// What to do when the input contains NaN
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    Reject,
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsError {
+    NaN { index: usize },
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsError::NaN { index } => write!(f, "value at index {index} is NaN"),
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+// Summary of the values ignoring NaNs; None when nothing is left to describe.
+pub fn describe(values: &[f64]) -> Option<Summary> {
+    describe_with(values, NanPolicy::Skip).unwrap_or(None)
+}
+
+// One pass over the values with Welford's algorithm, through RunningStats:
+//    updating the mean incrementally and accumulating squared distances from the *current* mean
+//    avoids the catastrophic cancellation of the naive sum-of-squares formula.
+pub fn describe_with(values: &[f64], nan: NanPolicy) -> Result<Option<Summary>, StatsError> {
+    let mut stats = RunningStats::new();
+    let mut min = f64::INFINITY;
+    let mut max = f64::NEG_INFINITY;
+
+    for (index, &value) in values.iter().enumerate() {
+        if value.is_nan() {
+            match nan {
+                NanPolicy::Reject => return Err(StatsError::NaN { index }),
+                NanPolicy::Skip => continue,
+            }
+        }
+
+        min = min.min(value);
+        max = max.max(value);
+        stats.update(value);
+    }
+
+    if stats.count() == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(Summary {
+        count: usize::try_from(stats.count()).expect("count is at most values.len()"),
+        min,
+        max,
+        mean: stats.mean(),
+        variance: stats.variance(),
+        std_dev: stats.std_dev(),
+    }))
+}
+
+pub fn describe_i32(values: &[i32]) -> Option<Summary> {
+    let values: Vec<f64> = values.iter().map(|&v| f64::from(v)).collect();
+    describe(&values)
+}
+
+// The Welford update describe_with is built on, kept open: values are fed in one at a
+//    time as they arrive, so a stream can be summarised without storing it. Population
+//    variance, as in Summary. Before the first value there's nothing to average, so mean
+//    and variance are NaN.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    // sum of squared distances from the mean
+    m2: f64,
+}
+
+impl RunningStats {
+    pub fn new() -> RunningStats {
+        RunningStats::default()
+    }
+
+    pub fn update(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 { f64::NAN } else { self.mean }
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 { f64::NAN } else { self.m2 / self.count as f64 }
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    // The statistics of both streams together, as if every value had gone into one
+    //    (Chan et al.): the means are weighted by count, and m2 gains a term for how far
+    //    apart the two means are
+    pub fn merge(&self, other: &RunningStats) -> RunningStats {
+        let count = self.count + other.count;
+        if count == 0 {
+            return RunningStats::default();
+        }
+        let (n_a, n_b, n) = (self.count as f64, other.count as f64, count as f64);
+        let delta = other.mean - self.mean;
+        RunningStats {
+            count,
+            mean: self.mean + delta * n_b / n,
+            m2: self.m2 + other.m2 + delta * delta * n_a * n_b / n,
+        }
+    }
+}
+
+impl Extend<f64> for RunningStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, values: I) {
+        for value in values {
+            self.update(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    #[test]
+    fn hand_computed_values() {
+        let summary = describe(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(summary.count, 8);
+        assert_eq!(summary.min, 2.0);
+        assert_eq!(summary.max, 9.0);
+        assert!((summary.mean - 5.0).abs() < EPSILON);
+        assert!((summary.variance - 4.0).abs() < EPSILON);
+        assert!((summary.std_dev - 2.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn i32_wrapper() {
+        let summary = describe_i32(&[-3, 0, 3]).unwrap();
+        assert_eq!(summary.min, -3.0);
+        assert_eq!(summary.max, 3.0);
+        assert!(summary.mean.abs() < EPSILON);
+        assert!((summary.variance - 6.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn empty_slice_is_none() {
+        assert_eq!(describe(&[]), None);
+        assert_eq!(describe_i32(&[]), None);
+    }
+
+    #[test]
+    fn single_value_has_zero_variance() {
+        let summary = describe(&[42.0]).unwrap();
+        assert_eq!(summary.mean, 42.0);
+        assert_eq!(summary.variance, 0.0);
+    }
+
+    #[test]
+    fn nan_rejected_or_skipped() {
+        let values = [1.0, f64::NAN, 3.0];
+        assert_eq!(
+            describe_with(&values, NanPolicy::Reject),
+            Err(StatsError::NaN { index: 1 })
+        );
+
+        let summary = describe_with(&values, NanPolicy::Skip).unwrap().unwrap();
+        assert_eq!(summary.count, 2);
+        assert!((summary.mean - 2.0).abs() < EPSILON);
+
+        assert_eq!(describe(&[f64::NAN]), None);
+    }
+
+    #[test]
+    fn matches_naive_two_pass_on_large_input() {
+        // large offset makes the naive sum-of-squares approach lose precision; two-pass is fine
+        let values: Vec<f64> = (0..100_000)
+            .map(|i| 1e6 + ((i * 7919) % 1000) as f64 * 0.01)
+            .collect();
+
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+
+        let summary = describe(&values).unwrap();
+        assert!((summary.mean - mean).abs() < 1e-6);
+        assert!((summary.variance - variance).abs() < 1e-6);
+    }
+
+    fn running(values: &[f64]) -> RunningStats {
+        let mut stats = RunningStats::new();
+        stats.extend(values.iter().copied());
+        stats
+    }
+
+    #[test]
+    fn running_stats_by_hand() {
+        // mean 6; squared distances 16 + 4 + 0 + 4 + 16 = 40; variance 40 / 5 = 8
+        let stats = running(&[2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(stats.count(), 5);
+        assert!((stats.mean() - 6.0).abs() < EPSILON);
+        assert!((stats.variance() - 8.0).abs() < EPSILON);
+        assert!((stats.std_dev() - 8f64.sqrt()).abs() < EPSILON);
+
+        let empty = RunningStats::new();
+        assert_eq!(empty.count(), 0);
+        assert!(empty.mean().is_nan() && empty.variance().is_nan());
+        assert_eq!(running(&[3.5]).variance(), 0.0);
+    }
+
+    #[test]
+    fn running_stats_agree_with_describe() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let summary = describe(&values).unwrap();
+        let stats = running(&values);
+        assert_eq!(stats.count(), summary.count as u64);
+        assert!((stats.mean() - summary.mean).abs() < EPSILON);
+        assert!((stats.variance() - summary.variance).abs() < EPSILON);
+    }
+
+    #[test]
+    fn merge_pools_two_streams() {
+        let values: Vec<f64> = (0..50).map(|i| ((i * 37) % 11) as f64 * 1.5 - 4.0).collect();
+        let whole = running(&values);
+        for split in [0, 1, 17, 25, 49, 50] {
+            let merged = running(&values[..split]).merge(&running(&values[split..]));
+            assert_eq!(merged.count(), whole.count(), "split at {split}");
+            assert!((merged.mean() - whole.mean()).abs() < EPSILON, "split at {split}");
+            assert!((merged.variance() - whole.variance()).abs() < EPSILON, "split at {split}");
+        }
+
+        // {1, 3} and {5, 7, 9}: all five have mean 5 and variance (16 + 4 + 0 + 4 + 16) / 5
+        let merged = running(&[1.0, 3.0]).merge(&running(&[5.0, 7.0, 9.0]));
+        assert!((merged.mean() - 5.0).abs() < EPSILON);
+        assert!((merged.variance() - 8.0).abs() < EPSILON);
+        assert_eq!(RunningStats::new().merge(&RunningStats::new()), RunningStats::new());
+    }
+}
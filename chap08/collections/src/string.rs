@@ -1,6 +1,51 @@
 
 
-pub fn main() {
+use std::error::Error;
+use std::ops::Range;
+use std::ptr;
+
+use collections::interner::Interner;
+
+// First n chars of s (the whole string if it is shorter); never cuts a char in half.
+pub fn truncate_chars(s: &str, n: usize) -> &str {
+    match s.char_indices().nth(n) {
+        Some((byte_idx, _)) => &s[..byte_idx],
+        None => s,
+    }
+}
+
+// &s[range] without the panic: None when a bound is out of range or inside a char.
+//    With snap, bounds are clamped to the length and moved back to the previous char boundary instead.
+pub fn safe_slice(s: &str, range: Range<usize>, snap: bool) -> Option<&str> {
+    let (start, end) = if snap {
+        (floor_char_boundary(s, range.start), floor_char_boundary(s, range.end))
+    } else {
+        (range.start, range.end)
+    };
+    // str::get already checks the bounds and char boundaries for us
+    s.get(start..end)
+}
+
+fn floor_char_boundary(s: &str, byte_idx: usize) -> usize {
+    let mut idx = byte_idx.min(s.len());
+    while !s.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+// Which char starts at byte_idx; None if byte_idx is inside a char or past the end.
+//    byte_idx == s.len() maps to the char count, like an end-of-string position.
+pub fn byte_to_char_index(s: &str, byte_idx: usize) -> Option<usize> {
+    if !s.is_char_boundary(byte_idx) {
+        return None;
+    }
+    Some(s[..byte_idx].chars().count())
+}
+
+// the bindings only show the API, so not all of them are read
+#[allow(unused_variables, unused_mut)]
+pub fn main() -> Result<(), Box<dyn Error>> {
 
     // The String type is a growable, mutable, owned, UTF-8 encoded string type.
     // both String and string slices are UTF-8 encoded
@@ -76,6 +121,10 @@ pub fn main() {
     let hello = "Здравствуйте";
     let s = &hello[0..4];  // 4 bytes => 'Зд'
     // let s = &hello[0..1];  //  panick => byte index 1 is not a char boundary; it is inside 'З'
+    println!("{:?}", safe_slice(hello, 0..1, false));  // None instead of a panic
+    println!("{:?}", safe_slice(hello, 0..5, true));   // Some("Зд"), end snapped back to byte 4
+    println!("{}", truncate_chars(hello, 2));          // "Зд"
+    println!("{:?}", byte_to_char_index(hello, 4));    // Some(2): byte 4 starts the third char 'р'
 
     // Iterating Over Strings
     for c in "Зд".chars() {
@@ -89,4 +138,64 @@ pub fn main() {
     // Be sure to remember that valid Unicode scalar values may be made up of more than one byte.
     // Getting grapheme clusters from strings, as with the Devanagari script, is complex, 
     //     so this functionality is not provided by the standard library.
+
+    // Interning: keep one copy of each distinct string, so equal strings are the same pointer
+    let mut interner = Interner::new();
+    let a = interner.intern("hello");
+    let b = interner.intern(&String::from("hello"));
+    println!("same pointer: {}, {} distinct string(s)", ptr::eq(a, b), interner.interned_count());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CYRILLIC: &str = "Здравствуйте";
+    const KOREAN: &str = "안녕하세요";
+    const EMOJI: &str = "hi 👋🏽!";
+
+    #[test]
+    fn truncate_counts_chars_not_bytes() {
+        assert_eq!(truncate_chars(CYRILLIC, 2), "Зд");
+        assert_eq!(truncate_chars(KOREAN, 2), "안녕");
+        assert_eq!(truncate_chars(KOREAN, 0), "");
+        assert_eq!(truncate_chars(KOREAN, 100), KOREAN);
+        // 👋🏽 is two chars (hand + skin tone modifier)
+        assert_eq!(truncate_chars(EMOJI, 4), "hi 👋");
+    }
+
+    #[test]
+    fn safe_slice_rejects_non_boundaries() {
+        assert_eq!(safe_slice(CYRILLIC, 0..1, false), None);
+        assert_eq!(safe_slice(CYRILLIC, 0..4, false), Some("Зд"));
+        assert_eq!(safe_slice(KOREAN, 3..6, false), Some("녕"));
+        assert_eq!(safe_slice(KOREAN, 1..6, false), None);
+        assert_eq!(safe_slice(EMOJI, 3..5, false), None);
+        assert_eq!(safe_slice(EMOJI, 0..100, false), None);
+    }
+
+    #[test]
+    fn safe_slice_snaps_to_previous_boundary() {
+        assert_eq!(safe_slice(CYRILLIC, 0..1, true), Some(""));
+        assert_eq!(safe_slice(CYRILLIC, 1..5, true), Some("Зд"));
+        assert_eq!(safe_slice(KOREAN, 4..8, true), Some("녕"));
+        assert_eq!(safe_slice(EMOJI, 0..5, true), Some("hi "));
+        assert_eq!(safe_slice(KOREAN, 10..100, true), Some("세요"));
+        let reversed = Range { start: 9, end: 3 };
+        assert_eq!(safe_slice(KOREAN, reversed, true), None);
+    }
+
+    #[test]
+    fn byte_to_char_index_on_boundaries_only() {
+        assert_eq!(byte_to_char_index(CYRILLIC, 0), Some(0));
+        assert_eq!(byte_to_char_index(CYRILLIC, 4), Some(2));
+        assert_eq!(byte_to_char_index(CYRILLIC, 1), None);
+        assert_eq!(byte_to_char_index(KOREAN, 6), Some(2));
+        assert_eq!(byte_to_char_index(KOREAN, KOREAN.len()), Some(5));
+        assert_eq!(byte_to_char_index(KOREAN, KOREAN.len() + 1), None);
+        assert_eq!(byte_to_char_index(EMOJI, 7), Some(4));
+        assert_eq!(byte_to_char_index(EMOJI, 4), None);
+    }
 }
@@ -1,11 +1,13 @@
 
-enum SpreadsheetCell {
-    Int(i32),
-    Float(f64),
-    Text(String),
-}
+use std::error::Error;
+
+use collections::buffer_pool::BufferPool;
+// enum SpreadsheetCell { Int(i32), Float(f64), Text(String), Formula(Formula) }
+use collections::spreadsheet::{CellIterExt, ColumnRef, Formula, Spreadsheet, SpreadsheetCell};
 
-pub fn main() {
+// the bindings only show the API, so not all of them are read
+#[allow(unused_variables, clippy::useless_vec, clippy::vec_init_then_push)]
+pub fn main() -> Result<(), Box<dyn Error>> {
     // std::collections::Vec<T>
     //    Vectors allow you to store more than one value in a single data structure
     //        that puts all the values next to each other in memory.
@@ -45,6 +47,33 @@ pub fn main() {
         SpreadsheetCell::Text(String::from("blue")),
         SpreadsheetCell::Float(10.12),
     ];
+    // only the Int and Float cells, as f64
+    println!("numbers in the row add up to {}", row.iter().numbers().sum::<f64>());
+
+    // Formula cells keep the formula and compute it only when evaluated
+    let mut sheet = Spreadsheet::new(&["qty", "color", "price"]);
+    sheet.push_row(row);
+    sheet.push_row(vec![
+        SpreadsheetCell::Formula(Formula::Avg(ColumnRef::new("price"))),
+        SpreadsheetCell::Text(String::from("average price")),
+        SpreadsheetCell::Float(2.5),
+    ]);
+    println!("{} = {}", sheet.rows()[1][0], sheet.evaluate(1, 0)?);
+    println!("price column adds up to {}", sheet.column("price").numbers().sum::<f64>());
+
+    let json = sheet.to_json();
+    println!("{json}");
+    assert_eq!(Spreadsheet::from_json(&json)?, sheet);
+
+    // a cleared Vec keeps its allocation, so buffers can be handed out again and again
+    let pool = BufferPool::new(2);
+    for request in ["/", "/about", "/contact"] {
+        let mut response = pool.acquire();
+        response.extend_from_slice(b"HTTP/1.1 200 OK\r\n\r\n");
+        response.extend_from_slice(request.as_bytes());
+        println!("{} bytes for {request}, buffer capacity {}", response.len(), response.capacity());
+    }
+    println!("(idle, acquired) = {:?}", pool.stats());
 
     {
         let v = vec![1, 2, 3, 4];
@@ -52,4 +81,5 @@ pub fn main() {
         // do stuff with v
     } // <- v goes out  of scope and is freed here
 
+    Ok(())
 }
@@ -0,0 +1,87 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// Removes duplicates while keeping the first occurrence of each value in place.
+//    Vec::dedup() only removes *consecutive* duplicates, and sort + dedup loses the original order,
+//    so remember what we've already seen in a HashSet instead.
+pub fn dedup_stable<T: Eq + Hash + Clone>(v: &[T]) -> Vec<T> {
+    let mut seen = HashSet::new();
+    v.iter()
+        .filter(|item| seen.insert(*item))
+        .cloned()
+        .collect()
+}
+
+// Returns references to the k largest elements according to `cmp`, largest first.
+//    Only a window of k candidates is kept sorted (bounded selection), so this is O(n·k)
+//    instead of sorting the whole slice. Equal elements keep their original relative order.
+pub fn top_k_by<T, F>(v: &[T], k: usize, cmp: F) -> Vec<&T>
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let mut top: Vec<&T> = Vec::with_capacity(k.min(v.len()));
+    if k == 0 {
+        return top;
+    }
+
+    for item in v {
+        if top.len() == k && cmp(item, top[k - 1]) != Ordering::Greater {
+            continue;
+        }
+        // everything not smaller than item stays in front of it
+        let pos = top.partition_point(|x| cmp(x, item) != Ordering::Less);
+        if top.len() == k {
+            top.pop();
+        }
+        top.insert(pos, item);
+    }
+
+    top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_keeps_first_occurrence_order() {
+        let v = vec![3, 1, 3, 2, 1, 4, 2, 3];
+        assert_eq!(dedup_stable(&v), vec![3, 1, 2, 4]);
+
+        let words = vec!["b", "a", "b", "c", "a"];
+        assert_eq!(dedup_stable(&words), vec!["b", "a", "c"]);
+
+        let empty: Vec<i32> = Vec::new();
+        assert!(dedup_stable(&empty).is_empty());
+    }
+
+    #[test]
+    fn top_k_returns_largest_first() {
+        let v = vec![5, 1, 9, 3, 7, 9, 2];
+        assert_eq!(top_k_by(&v, 3, |a, b| a.cmp(b)), vec![&9, &9, &7]);
+    }
+
+    #[test]
+    fn top_k_with_zero_k_is_empty() {
+        let v = vec![5, 1, 9];
+        assert!(top_k_by(&v, 0, |a, b| a.cmp(b)).is_empty());
+    }
+
+    #[test]
+    fn top_k_with_k_at_least_len_is_fully_sorted() {
+        let v = vec![2, 8, 4];
+        assert_eq!(top_k_by(&v, 3, |a, b| a.cmp(b)), vec![&8, &4, &2]);
+        assert_eq!(top_k_by(&v, 10, |a, b| a.cmp(b)), vec![&8, &4, &2]);
+        // k only bounds the result; it isn't allocated up front
+        assert_eq!(top_k_by(&v, usize::MAX, |a, b| a.cmp(b)), vec![&8, &4, &2]);
+    }
+
+    #[test]
+    fn top_k_by_absolute_value() {
+        let v: Vec<i32> = vec![-10, 3, 7, -8, 1, 10];
+        let top = top_k_by(&v, 3, |a, b| a.abs().cmp(&b.abs()));
+        // -10 and 10 tie; the earlier one stays first
+        assert_eq!(top, vec![&-10, &10, &-8]);
+    }
+}
@@ -0,0 +1,145 @@
+// One error type for the whole program, instead of io::Error for some functions and
+//    ParseIntError for others: the caller matches on a single enum, and the From impls
+//    let `?` convert either kind automatically.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum AppError {
+    Io(io::Error),
+    // line numbers start at 1, as in an editor; None when the number didn't come from a
+    //    numbered line (a bare ParseIntError turned into an AppError by `?`)
+    Parse { line: Option<usize>, source: ParseIntError },
+    // every line parsed, but adding this one took the total past i64::MAX (or below i64::MIN)
+    Overflow { line: usize },
+    // not produced by read_and_sum; for input that has to name its fields
+    #[allow(dead_code)]
+    MissingField(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::Io(e) => write!(f, "I/O error: {e}"),
+            AppError::Parse { line: Some(line), source } => write!(f, "line {line}: {source}"),
+            AppError::Parse { line: None, source } => write!(f, "invalid number: {source}"),
+            AppError::Overflow { line } => write!(f, "line {line}: the sum no longer fits in an i64"),
+            AppError::MissingField(name) => write!(f, "missing field `{name}`"),
+        }
+    }
+}
+
+impl Error for AppError {
+    // the underlying error, for callers that want to walk the whole chain
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppError::Io(e) => Some(e),
+            AppError::Parse { source, .. } => Some(source),
+            AppError::Overflow { .. } | AppError::MissingField(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> AppError {
+        AppError::Io(e)
+    }
+}
+
+// A bare ParseIntError doesn't know where it came from, so this one has no line number;
+//    prefer building AppError::Parse with the line when it's known
+impl From<ParseIntError> for AppError {
+    fn from(source: ParseIntError) -> AppError {
+        AppError::Parse { line: None, source }
+    }
+}
+
+// Sum of a file with one integer per line; blank lines are skipped
+pub fn read_and_sum(path: impl AsRef<Path>) -> Result<i64, AppError> {
+    let text = fs::read_to_string(path)?; // io::Error -> AppError::Io
+    let mut sum: i64 = 0;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let n: i64 = line
+            .parse()
+            .map_err(|source| AppError::Parse { line: Some(index + 1), source })?;
+        sum = sum.checked_add(n).ok_or(AppError::Overflow { line: index + 1 })?;
+    }
+    Ok(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn sums_every_line() {
+        let dir = TempDir::new("sum-ok");
+        let path = dir.write("numbers.txt", "1\n2\n\n-10\n  40  \n");
+        assert_eq!(read_and_sum(&path).unwrap(), 33);
+    }
+
+    #[test]
+    fn missing_file_is_io() {
+        let err = read_and_sum("/definitely/not/here.txt").unwrap_err();
+        match &err {
+            AppError::Io(e) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            other => panic!("expected Io, got {other:?}"),
+        }
+        assert!(err.source().is_some());
+        assert!(err.to_string().starts_with("I/O error: "));
+    }
+
+    #[test]
+    fn bad_line_reports_its_number() {
+        let dir = TempDir::new("sum-bad");
+        let path = dir.write("numbers.txt", "1\n2\nthree\n4\n");
+        let err = read_and_sum(&path).unwrap_err();
+        assert!(matches!(err, AppError::Parse { line: Some(3), .. }), "{err:?}");
+        assert_eq!(err.to_string(), "line 3: invalid digit found in string");
+        assert_eq!(err.source().unwrap().to_string(), "invalid digit found in string");
+    }
+
+    #[test]
+    fn overflow_is_an_error_not_a_panic() {
+        let dir = TempDir::new("sum-overflow");
+        let path = dir.write("numbers.txt", "9223372036854775807\n1\n");
+        let err = read_and_sum(&path).unwrap_err();
+        assert!(matches!(err, AppError::Overflow { line: 2 }), "{err:?}");
+        assert_eq!(err.to_string(), "line 2: the sum no longer fits in an i64");
+        assert!(err.source().is_none());
+
+        // below i64::MIN too; right at the limit is still fine
+        fs::write(&path, "-9223372036854775807\n\n-1\n-1\n").unwrap();
+        assert!(matches!(read_and_sum(&path), Err(AppError::Overflow { line: 4 })));
+        fs::write(&path, "9223372036854775806\n1\n").unwrap();
+        assert_eq!(read_and_sum(&path).unwrap(), i64::MAX);
+    }
+
+    #[test]
+    fn question_mark_converts_both_kinds() {
+        fn parse(s: &str) -> Result<i32, AppError> {
+            Ok(s.parse::<i32>()?)
+        }
+        fn open() -> Result<fs::File, AppError> {
+            Ok(fs::File::open("/definitely/not/here.txt")?)
+        }
+        let err = parse("x").unwrap_err();
+        assert!(matches!(err, AppError::Parse { line: None, .. }), "{err:?}");
+        assert_eq!(err.to_string(), "invalid number: invalid digit found in string");
+        assert!(matches!(open(), Err(AppError::Io(_))));
+
+        let missing = AppError::MissingField(String::from("name"));
+        assert_eq!(missing.to_string(), "missing field `name`");
+        assert!(missing.source().is_none());
+    }
+}
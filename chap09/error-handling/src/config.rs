@@ -0,0 +1,198 @@
+// Reading a settings file of `key = value` lines into a typed struct:
+//
+//     # comments take a whole line
+//     name = my-app
+//     retries = 5
+//     verbose = true
+//
+// Every way it can go wrong is its own ConfigError variant, so a caller can tell "no
+//    such file" (often fine: use the defaults) from "the file is there but wrong" (never
+//    fine to ignore).
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::source::ContentSource;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub name: String,
+    pub retries: u32,
+    pub verbose: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            name: String::from("error-handling"),
+            retries: 3,
+            verbose: false,
+        }
+    }
+}
+
+// Line numbers start at 1, as in an editor
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    // a required key never appeared
+    MissingKey(String),
+    // the key is known but its value isn't the right type
+    ParseValue { key: String, line: usize },
+    UnknownKey { key: String, line: usize },
+    // a line that is neither blank, a comment nor `key = value`
+    Syntax { line: usize },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "could not read the config: {e}"),
+            ConfigError::MissingKey(key) => write!(f, "missing required key `{key}`"),
+            ConfigError::ParseValue { key, line } => write!(f, "line {line}: invalid value for `{key}`"),
+            ConfigError::UnknownKey { key, line } => write!(f, "line {line}: unknown key `{key}`"),
+            ConfigError::Syntax { line } => write!(f, "line {line}: expected `key = value`"),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> ConfigError {
+        ConfigError::Io(e)
+    }
+}
+
+impl Config {
+    pub fn load(source: &impl ContentSource, name: &str) -> Result<Config, ConfigError> {
+        let text = source.read(name)?;
+        Config::parse(&text)
+    }
+
+    // A missing file means "use the defaults"; any other failure (unreadable, or there
+    //    but invalid) is still an error, so a typo in the file doesn't go unnoticed
+    pub fn load_or_default(source: &impl ContentSource, name: &str) -> Result<Config, ConfigError> {
+        match Config::load(source, name) {
+            Err(ConfigError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            result => result,
+        }
+    }
+
+    // `name` is required; `retries` and `verbose` keep their defaults when left out.
+    //    If a key appears twice, the later line wins.
+    pub fn parse(text: &str) -> Result<Config, ConfigError> {
+        let mut config = Config::default();
+        let mut name = None;
+        for (index, line) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ConfigError::Syntax { line: line_no });
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || ConfigError::ParseValue { key: key.to_string(), line: line_no };
+            match key {
+                "name" if value.is_empty() => return Err(invalid()),
+                "name" => name = Some(value.to_string()),
+                "retries" => config.retries = value.parse().map_err(|_| invalid())?,
+                "verbose" => config.verbose = value.parse().map_err(|_| invalid())?,
+                _ => return Err(ConfigError::UnknownKey { key: key.to_string(), line: line_no }),
+            }
+        }
+        config.name = name.ok_or_else(|| ConfigError::MissingKey(String::from("name")))?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{FailingSource, FsSource, MapSource};
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn parses_every_key() {
+        let text = "# settings\n\nname = server\n  retries=5  \nverbose = true\n";
+        let config = Config::parse(text).unwrap();
+        assert_eq!(
+            config,
+            Config { name: String::from("server"), retries: 5, verbose: true }
+        );
+
+        // only name is required
+        let config = Config::parse("name = x").unwrap();
+        assert_eq!((config.retries, config.verbose), (3, false));
+        // the later line wins
+        assert_eq!(Config::parse("name = a\nname = b").unwrap().name, "b");
+    }
+
+    #[test]
+    fn missing_key() {
+        let err = Config::parse("# nothing but a comment\nretries = 1\n").unwrap_err();
+        assert!(matches!(&err, ConfigError::MissingKey(key) if key == "name"), "{err:?}");
+        assert_eq!(err.to_string(), "missing required key `name`");
+    }
+
+    #[test]
+    fn bad_values_name_the_key_and_line() {
+        let err = Config::parse("name = x\n\nretries = many\n").unwrap_err();
+        assert!(matches!(&err, ConfigError::ParseValue { key, line: 3 } if key == "retries"), "{err:?}");
+        assert_eq!(err.to_string(), "line 3: invalid value for `retries`");
+
+        for text in ["verbose = yes", "retries = -1", "name ="] {
+            assert!(matches!(Config::parse(text), Err(ConfigError::ParseValue { line: 1, .. })), "{text}");
+        }
+    }
+
+    #[test]
+    fn unknown_key_and_bad_syntax() {
+        let err = Config::parse("name = x\nretry = 2\n").unwrap_err();
+        assert!(matches!(&err, ConfigError::UnknownKey { key, line: 2 } if key == "retry"), "{err:?}");
+        assert_eq!(err.to_string(), "line 2: unknown key `retry`");
+
+        let err = Config::parse("name = x\nverbose\n").unwrap_err();
+        assert!(matches!(err, ConfigError::Syntax { line: 2 }), "{err:?}");
+    }
+
+    #[test]
+    fn load_reads_the_file() {
+        let dir = TempDir::new("config");
+        dir.write("app.conf", "name = from-file\nverbose = true\n");
+        let config = Config::load(&FsSource::new(dir.path()), "app.conf").unwrap();
+        assert_eq!(config.name, "from-file");
+        assert!(config.verbose);
+
+        let err = Config::load(&FsSource::new(dir.path()), "other.conf").unwrap_err();
+        assert!(matches!(&err, ConfigError::Io(e) if e.kind() == io::ErrorKind::NotFound));
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn defaults_only_when_the_file_is_missing() {
+        let config = Config::load_or_default(&MapSource::default(), "app.conf").unwrap();
+        assert_eq!(config, Config::default());
+        let config = Config::load_or_default(&FailingSource(io::ErrorKind::NotFound), "app.conf").unwrap();
+        assert_eq!(config, Config::default());
+
+        // there but invalid: the error comes through
+        let source = MapSource::new(&[("app.conf", "name = x\nretries = lots\n")]);
+        assert!(matches!(Config::load_or_default(&source, "app.conf"), Err(ConfigError::ParseValue { .. })));
+
+        // there but unreadable: other I/O errors come through too
+        let unreadable = FailingSource(io::ErrorKind::PermissionDenied);
+        let err = Config::load_or_default(&unreadable, "app.conf").unwrap_err();
+        assert!(matches!(&err, ConfigError::Io(e) if e.kind() == io::ErrorKind::PermissionDenied), "{err:?}");
+    }
+}
@@ -0,0 +1,130 @@
+// "No such file or directory" doesn't say which file, or what the program was doing
+//    with it. Context adds that to an io::Error on its way up:
+//
+//     fs::read_to_string(path).context("reading username").with_path(path)?
+//
+// fails with `reading username from "hello.txt": No such file or directory (os error 2)`.
+//    The io::Error is kept whole as the source(), so its kind() is still there to match on.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub struct ContextError {
+    // what was being done, e.g. "reading username"; may be empty
+    context: String,
+    path: Option<PathBuf>,
+    source: io::Error,
+}
+
+impl ContextError {
+    pub fn new(context: impl Into<String>, source: io::Error) -> ContextError {
+        ContextError { context: context.into(), path: None, source }
+    }
+
+    pub fn kind(&self) -> io::ErrorKind {
+        self.source.kind()
+    }
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.path, self.context.is_empty()) {
+            (Some(path), false) => write!(f, "{} from {path:?}", self.context)?,
+            (Some(path), true) => write!(f, "{path:?}")?,
+            (None, _) => f.write_str(&self.context)?,
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl Error for ContextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+pub trait Context<T> {
+    fn context(self, msg: impl Into<String>) -> Result<T, ContextError>;
+    fn with_path(self, path: &Path) -> Result<T, ContextError>;
+}
+
+impl<T> Context<T> for Result<T, io::Error> {
+    fn context(self, msg: impl Into<String>) -> Result<T, ContextError> {
+        self.map_err(|e| ContextError::new(msg, e))
+    }
+
+    fn with_path(self, path: &Path) -> Result<T, ContextError> {
+        self.context("").with_path(path)
+    }
+}
+
+// So the two can be chained, in either order. A second context goes in front of the first.
+impl<T> Context<T> for Result<T, ContextError> {
+    fn context(self, msg: impl Into<String>) -> Result<T, ContextError> {
+        self.map_err(|mut e| {
+            let msg = msg.into();
+            e.context = match e.context.is_empty() {
+                true => msg,
+                false => format!("{msg}: {}", e.context),
+            };
+            e
+        })
+    }
+
+    fn with_path(self, path: &Path) -> Result<T, ContextError> {
+        self.map_err(|mut e| {
+            e.path = Some(path.to_path_buf());
+            e
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn not_found() -> io::Result<String> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "No such file or directory"))
+    }
+
+    #[test]
+    fn message_names_the_operation_and_path() {
+        let path = Path::new("hello.txt");
+        let err = not_found().context("reading username").with_path(path).unwrap_err();
+        assert_eq!(err.to_string(), r#"reading username from "hello.txt": No such file or directory"#);
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        // the same the other way round
+        let err = not_found().with_path(path).context("reading username").unwrap_err();
+        assert_eq!(err.to_string(), r#"reading username from "hello.txt": No such file or directory"#);
+
+        let err = not_found().with_path(path).unwrap_err();
+        assert_eq!(err.to_string(), r#""hello.txt": No such file or directory"#);
+        let err = not_found().context("starting up").context("main").unwrap_err();
+        assert_eq!(err.to_string(), "main: starting up: No such file or directory");
+    }
+
+    #[test]
+    fn source_is_the_original_io_error() {
+        let path = Path::new("/definitely/not/here.txt");
+        let err = fs::read_to_string(path).context("reading username").with_path(path).unwrap_err();
+        let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().ends_with(&source.to_string()));
+
+        // and through a Box<dyn Error>, as `?` in main would hand it on
+        let boxed: Box<dyn Error> = Box::new(err);
+        assert!(boxed.downcast_ref::<ContextError>().is_some());
+        assert!(boxed.source().unwrap().is::<io::Error>());
+    }
+
+    #[test]
+    fn ok_values_pass_through() {
+        let ok: io::Result<i32> = Ok(7);
+        assert_eq!(ok.context("unused").with_path(Path::new("x")).unwrap(), 7);
+    }
+}
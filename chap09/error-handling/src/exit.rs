@@ -0,0 +1,127 @@
+// What the program says, and which code it exits with, when run() fails.
+//
+// Exit codes follow sysexits.h, so a calling script can tell "you gave me the wrong
+//    input" from "something else went wrong". The message is for whoever ran the
+//    program: plain words first, with the whole error chain only when asked for
+//    (`--verbose`), since "Is a directory (os error 21)" means little to most people.
+
+use std::error::Error;
+use std::io::ErrorKind;
+
+use crate::app_error::AppError;
+
+// sysexits.h: the input data was incorrect in some way
+pub const EX_DATAERR: i32 = 65;
+// sysexits.h: an input file did not exist or was not readable
+pub const EX_NOINPUT: i32 = 66;
+
+pub fn exit_code_for(err: &AppError) -> i32 {
+    match err {
+        AppError::Io(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::PermissionDenied) => EX_NOINPUT,
+        // read_to_string on a file that isn't UTF-8
+        AppError::Io(e) if e.kind() == ErrorKind::InvalidData => EX_DATAERR,
+        AppError::Io(_) => 1,
+        AppError::Parse { .. } | AppError::Overflow { .. } | AppError::MissingField(_) => EX_DATAERR,
+    }
+}
+
+// One line, or with `verbose` the error and each source() behind it on lines of their own:
+//
+//     a file the program needs could not be read: permission denied
+//       error: I/O error: hello2.txt: Permission denied (os error 13)
+//       caused by: hello2.txt: Permission denied (os error 13)
+pub fn user_message(err: &AppError, verbose: bool) -> String {
+    let mut message = match err {
+        AppError::Io(e) => match e.kind() {
+            ErrorKind::NotFound => String::from("a file the program needs is missing"),
+            ErrorKind::PermissionDenied => {
+                String::from("a file the program needs could not be read: permission denied")
+            }
+            ErrorKind::InvalidData => String::from("a file the program needs is not valid text"),
+            _ => String::from("a file could not be read or written"),
+        },
+        AppError::Parse { line: Some(line), .. } => format!("line {line} of the input isn't a whole number"),
+        AppError::Parse { line: None, .. } => String::from("the input holds something that isn't a whole number"),
+        AppError::Overflow { line } => format!("the numbers up to line {line} add up to more than fits in a sum"),
+        AppError::MissingField(name) => format!("the input is missing the `{name}` field"),
+    };
+    if verbose {
+        message.push_str(&format!("\n  error: {err}"));
+        let mut source = err.source();
+        while let Some(cause) = source {
+            message.push_str(&format!("\n  caused by: {cause}"));
+            source = cause.source();
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    fn io(kind: ErrorKind) -> AppError {
+        AppError::Io(io::Error::new(kind, "hello2.txt: details"))
+    }
+
+    #[test]
+    fn exit_codes_by_error_kind() {
+        assert_eq!(exit_code_for(&io(ErrorKind::NotFound)), 66);
+        assert_eq!(exit_code_for(&io(ErrorKind::PermissionDenied)), 66);
+        assert_eq!(exit_code_for(&io(ErrorKind::InvalidData)), 65);
+        for kind in [ErrorKind::Other, ErrorKind::Interrupted, ErrorKind::AlreadyExists, ErrorKind::WriteZero] {
+            assert_eq!(exit_code_for(&io(kind)), 1, "{kind:?}");
+        }
+        let parse = AppError::Parse { line: Some(2), source: "x".parse::<i64>().unwrap_err() };
+        assert_eq!(exit_code_for(&parse), 65);
+        assert_eq!(exit_code_for(&"x".parse::<i64>().map_err(AppError::from).unwrap_err()), 65);
+        assert_eq!(exit_code_for(&AppError::Overflow { line: 2 }), 65);
+        assert_eq!(exit_code_for(&AppError::MissingField(String::from("name"))), 65);
+    }
+
+    #[test]
+    fn messages_hide_the_details() {
+        let cases = [
+            (io(ErrorKind::NotFound), "a file the program needs is missing"),
+            (io(ErrorKind::PermissionDenied), "a file the program needs could not be read: permission denied"),
+            (io(ErrorKind::InvalidData), "a file the program needs is not valid text"),
+            (io(ErrorKind::Other), "a file could not be read or written"),
+            (
+                AppError::Parse { line: Some(2), source: "two".parse::<i64>().unwrap_err() },
+                "line 2 of the input isn't a whole number",
+            ),
+            (
+                "two".parse::<i64>().map_err(AppError::from).unwrap_err(),
+                "the input holds something that isn't a whole number",
+            ),
+            (AppError::Overflow { line: 2 }, "the numbers up to line 2 add up to more than fits in a sum"),
+            (AppError::MissingField(String::from("name")), "the input is missing the `name` field"),
+        ];
+        for (err, expected) in cases {
+            assert_eq!(user_message(&err, false), expected);
+        }
+    }
+
+    #[test]
+    fn verbose_appends_the_source_chain() {
+        assert_eq!(
+            user_message(&io(ErrorKind::NotFound), true),
+            "a file the program needs is missing\n  \
+             error: I/O error: hello2.txt: details\n  \
+             caused by: hello2.txt: details"
+        );
+        let parse = AppError::Parse { line: Some(7), source: "".parse::<i64>().unwrap_err() };
+        assert_eq!(
+            user_message(&parse, true),
+            "line 7 of the input isn't a whole number\n  \
+             error: line 7: cannot parse integer from empty string\n  \
+             caused by: cannot parse integer from empty string"
+        );
+        // nothing behind it, so just the error itself
+        assert_eq!(
+            user_message(&AppError::MissingField(String::from("id")), true),
+            "the input is missing the `id` field\n  error: missing field `id`"
+        );
+    }
+}
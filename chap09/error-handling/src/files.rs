@@ -0,0 +1,238 @@
+// File helpers that avoid the check-then-act race in main.rs: between seeing NotFound
+//    and calling File::create, another process can create the file too, and then one of
+//    them truncates what the other just wrote.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::SystemTime;
+
+// Opens path for reading and writing, first creating it with default_contents if it
+//    doesn't exist. create_new makes "does it exist?" and "create it" one step, so exactly
+//    one caller creates the file and writes the default; an existing file is never touched.
+//
+// Either way the file comes back positioned at the start. Errors name the path, since
+//    "No such file or directory" alone doesn't say which one was missing.
+//
+// If writing the default fails, the new file is removed again: left there empty or half
+//    written, every later call would find it and never write the default.
+pub fn ensure_file_with_default(path: &Path, default_contents: &str) -> io::Result<File> {
+    let with_path = |e: io::Error| with_path(path, e);
+
+    match OpenOptions::new().read(true).write(true).create_new(true).open(path) {
+        Ok(mut file) => {
+            let written = file
+                .write_all(default_contents.as_bytes())
+                .and_then(|()| file.seek(SeekFrom::Start(0)));
+            remove_on_error(path, written).map_err(with_path)?;
+            Ok(file)
+        }
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            OpenOptions::new().read(true).write(true).open(path).map_err(with_path)
+        }
+        Err(e) => Err(with_path(e)),
+    }
+}
+
+// result, after removing path if it is an error; the original error is the one returned
+fn remove_on_error<T>(path: &Path, result: io::Result<T>) -> io::Result<T> {
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+// e, with the path in front of its message; the kind stays the same, so callers can
+//    still match on NotFound and friends
+pub fn with_path(path: &Path, e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {e}", path.display()))
+}
+
+// path with `suffix` added to its file name: hello.txt -> hello.txt.bak
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+// A name next to path that nothing else is using: .hello.txt.<random>.tmp. Next to it,
+//    because a rename only replaces the file in one step within the same file system.
+//    RandomState is seeded randomly per process, so two processes don't pick the same name.
+fn temp_sibling(path: &Path) -> PathBuf {
+    let random = RandomState::new().hash_one((process::id(), SystemTime::now()));
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(".{random:016x}.tmp"));
+    path.with_file_name(name)
+}
+
+// Replaces path's contents so that anyone reading it (even after a crash) sees either
+//    the old contents or the new, never half of each: the new contents go to a temporary
+//    file, which is synced to disk and then renamed over path in one step. On any error
+//    the temporary file is removed and path is as it was.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = temp_sibling(path);
+    let result = (|| {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(&temp)?;
+        file.write_all(contents)?;
+        // without this the rename could reach the disk before the data does
+        file.sync_all()?;
+        fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result.map_err(|e| with_path(path, e))
+}
+
+// write_atomic, keeping the previous contents in path.bak (replacing an older backup).
+//    A path that doesn't exist yet is simply written, with no backup.
+pub fn backup_and_write(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let backup = with_suffix(path, ".bak");
+    match fs::copy(path, &backup) {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(with_path(&backup, e)),
+    }
+    write_atomic(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Read;
+    use crate::test_util::TempDir;
+
+    fn read_all(file: &mut File) -> String {
+        let mut text = String::new();
+        file.read_to_string(&mut text).unwrap();
+        text
+    }
+
+    #[test]
+    fn creates_missing_file_with_defaults() {
+        let dir = TempDir::new("ensure-new");
+        let path = dir.join("settings.txt");
+
+        let mut file = ensure_file_with_default(&path, "volume = 11\n").unwrap();
+        // positioned at the start, so the default can be read straight back
+        assert_eq!(read_all(&mut file), "volume = 11\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "volume = 11\n");
+    }
+
+    #[test]
+    fn existing_file_is_left_alone() {
+        let dir = TempDir::new("ensure-existing");
+        let path = dir.join("settings.txt");
+        fs::write(&path, "volume = 3\n").unwrap();
+
+        let mut file = ensure_file_with_default(&path, "volume = 11\n").unwrap();
+        assert_eq!(read_all(&mut file), "volume = 3\n");
+        // and it's open for writing too
+        file.write_all(b"bass = 2\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "volume = 3\nbass = 2\n");
+    }
+
+    #[test]
+    fn missing_parent_names_the_path() {
+        let dir = TempDir::new("ensure-no-parent");
+        let path = dir.join("no").join("such").join("dir.txt");
+
+        let err = ensure_file_with_default(&path, "x").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().starts_with(&path.display().to_string()), "{err}");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn failed_default_write_removes_the_new_file() {
+        let dir = TempDir::new("ensure-cleanup");
+        let path = dir.write("settings.txt", "volume = 1");
+
+        let err = remove_on_error::<()>(&path, Err(io::Error::other("disk full"))).unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+        assert!(!path.exists());
+        // so the next call starts over and writes the default
+        let mut file = ensure_file_with_default(&path, "volume = 11\n").unwrap();
+        assert_eq!(read_all(&mut file), "volume = 11\n");
+
+        // success leaves it alone
+        assert_eq!(remove_on_error(&path, Ok(5)).unwrap(), 5);
+        assert!(path.exists());
+    }
+
+    // Everything in dir, to check nothing was left behind
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn write_atomic_replaces_the_contents() {
+        let dir = TempDir::new("atomic-write");
+        let path = dir.join("hello.txt");
+        write_atomic(&path, b"first\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first\n");
+        write_atomic(&path, b"second\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second\n");
+        // no temporary files left over
+        assert_eq!(entries(dir.path()), vec!["hello.txt"]);
+    }
+
+    #[test]
+    fn temp_names_are_hidden_siblings() {
+        let path = Path::new("/some/dir/hello.txt");
+        let temp = temp_sibling(path);
+        assert_eq!(temp.parent(), path.parent());
+        let name = temp.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with(".hello.txt.") && name.ends_with(".tmp"), "{name}");
+        assert_ne!(temp_sibling(path), temp);
+        assert_eq!(with_suffix(path, ".bak"), Path::new("/some/dir/hello.txt.bak"));
+    }
+
+    #[test]
+    fn backup_keeps_the_previous_contents() {
+        let dir = TempDir::new("atomic-backup");
+        let path = dir.join("hello.txt");
+        // nothing to back up yet
+        backup_and_write(&path, b"one").unwrap();
+        assert_eq!(entries(dir.path()), vec!["hello.txt"]);
+
+        backup_and_write(&path, b"two").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two");
+        assert_eq!(fs::read_to_string(dir.join("hello.txt.bak")).unwrap(), "one");
+
+        // an older backup is replaced
+        backup_and_write(&path, b"three").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "three");
+        assert_eq!(fs::read_to_string(dir.join("hello.txt.bak")).unwrap(), "two");
+        assert_eq!(entries(dir.path()), vec!["hello.txt", "hello.txt.bak"]);
+    }
+
+    #[test]
+    fn failed_write_leaves_everything_as_it_was() {
+        let dir = TempDir::new("atomic-fail");
+        // a file can't be renamed over a directory that has something in it
+        let target = dir.join("target");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("inside.txt"), "keep me").unwrap();
+
+        let err = write_atomic(&target, b"new").unwrap_err();
+        assert!(err.to_string().starts_with(&target.display().to_string()), "{err}");
+        assert_eq!(fs::read_to_string(target.join("inside.txt")).unwrap(), "keep me");
+        // and the temporary file was cleaned up
+        assert_eq!(entries(dir.path()), vec!["target"]);
+
+        // no directory to put the temporary file in
+        let nowhere = dir.join("missing").join("hello.txt");
+        assert_eq!(write_atomic(&nowhere, b"x").unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+}
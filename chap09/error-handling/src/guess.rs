@@ -0,0 +1,133 @@
+// The book's Guess (chapter 9.3) panics when the value is out of range: fine when a bad
+//    value can only be a bug, but a guess typed by a player is going to be wrong sometimes.
+//    This one reports why instead, and the caller decides what to do about it.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+pub const MIN: i32 = 1;
+pub const MAX: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Guess {
+    value: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    TooSmall(i32),
+    TooLarge(i32),
+    // the (trimmed) input; numbers too big for an i32 end up here as well
+    NotANumber(String),
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::TooSmall(value) => write!(f, "{value} is too small, the guess must be at least {MIN}"),
+            GuessError::TooLarge(value) => write!(f, "{value} is too large, the guess must be at most {MAX}"),
+            GuessError::NotANumber(input) => write!(f, "`{input}` is not a number"),
+        }
+    }
+}
+
+impl Error for GuessError {}
+
+impl Guess {
+    pub fn new(value: i32) -> Result<Guess, GuessError> {
+        if value < MIN {
+            Err(GuessError::TooSmall(value))
+        } else if value > MAX {
+            Err(GuessError::TooLarge(value))
+        } else {
+            Ok(Guess { value })
+        }
+    }
+
+    // Surrounding whitespace is ignored, so a line straight from stdin works
+    pub fn parse(input: &str) -> Result<Guess, GuessError> {
+        let input = input.trim();
+        let value = input.parse().map_err(|_| GuessError::NotANumber(input.to_string()))?;
+        Guess::new(value)
+    }
+
+    // The book's version, for comparison: out of range is a panic
+    pub fn new_or_panic(value: i32) -> Guess {
+        if !(MIN..=MAX).contains(&value) {
+            panic!("Guess value must be between {MIN} and {MAX}, got {value}.");
+        }
+        Guess { value }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+}
+
+impl TryFrom<i32> for Guess {
+    type Error = GuessError;
+
+    fn try_from(value: i32) -> Result<Guess, GuessError> {
+        Guess::new(value)
+    }
+}
+
+impl FromStr for Guess {
+    type Err = GuessError;
+
+    fn from_str(s: &str) -> Result<Guess, GuessError> {
+        Guess::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries_are_inclusive() {
+        assert_eq!(Guess::new(1).unwrap().value(), 1);
+        assert_eq!(Guess::new(100).unwrap().value(), 100);
+        assert_eq!(Guess::new(0), Err(GuessError::TooSmall(0)));
+        assert_eq!(Guess::new(101), Err(GuessError::TooLarge(101)));
+        assert_eq!(Guess::new(i32::MIN), Err(GuessError::TooSmall(i32::MIN)));
+        assert_eq!(GuessError::TooLarge(101).to_string(), "101 is too large, the guess must be at most 100");
+    }
+
+    #[test]
+    fn parse_checks_both_number_and_range() {
+        assert_eq!(Guess::parse(" 42\n").unwrap().value(), 42);
+        assert_eq!(Guess::parse("0"), Err(GuessError::TooSmall(0)));
+        assert_eq!(Guess::parse("-5"), Err(GuessError::TooSmall(-5)));
+        assert_eq!(Guess::parse("101"), Err(GuessError::TooLarge(101)));
+        for garbage in ["", "abc", "4 2", "42.0", "99999999999"] {
+            assert_eq!(
+                Guess::parse(garbage),
+                Err(GuessError::NotANumber(garbage.to_string())),
+                "{garbage:?}"
+            );
+        }
+        assert_eq!(Guess::parse("  seven ").unwrap_err().to_string(), "`seven` is not a number");
+    }
+
+    #[test]
+    fn conversions() {
+        let guess: Guess = 50.try_into().unwrap();
+        assert_eq!(guess.value(), 50);
+        assert_eq!(Guess::try_from(200), Err(GuessError::TooLarge(200)));
+        assert_eq!("7".parse::<Guess>().unwrap().value(), 7);
+        assert!("x".parse::<Guess>().is_err());
+    }
+
+    #[test]
+    fn new_or_panic_accepts_the_same_range() {
+        assert_eq!(Guess::new_or_panic(100).value(), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "between 1 and 100, got 0")]
+    fn new_or_panic_panics_out_of_range() {
+        Guess::new_or_panic(0);
+    }
+}
@@ -1,57 +1,318 @@
+mod app_error;
+mod config;
+mod context;
+mod exit;
+mod files;
+mod guess;
+mod numbers;
+mod panics;
+mod report;
+mod retry;
+mod source;
+#[cfg(test)]
+mod test_util;
+mod username;
 
+use std::env;
+use std::error::Error;
 use std::fs::{self,File};
-use std::io::{self, Read, ErrorKind};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
-fn main() {
-    let greeting_file_result = File::open("hello.txt");
+use app_error::AppError;
+use context::{Context, ContextError};
+use report::{ErrorReporter, FileReporter};
+use source::{ContentSource, FsSource};
+
+// Every file the demo touches, so tests can point run() at a temporary directory
+struct Paths {
+    // where all of them are; the FsSource for the username and config readers
+    dir: PathBuf,
+    hello: PathBuf,
+    // must exist: run() fails without it
+    hello2: PathBuf,
+    numbers: PathBuf,
+    // the sum of numbers.txt is written here
+    sum: PathBuf,
+    // where the errors run() recovers from are recorded
+    error_log: PathBuf,
+}
+
+impl Paths {
+    // everything in dir, including what run() writes
+    fn in_dir(dir: &Path) -> Paths {
+        Paths {
+            dir: dir.to_path_buf(),
+            hello: dir.join("hello.txt"),
+            hello2: dir.join("hello2.txt"),
+            numbers: dir.join("numbers.txt"),
+            sum: dir.join("sum.txt"),
+            error_log: dir.join("errors.log"),
+        }
+    }
+
+    // the same, but what run() writes goes in out_dir instead, so `cargo run` leaves
+    //    nothing behind in the crate
+    fn with_output_in(self, out_dir: &Path) -> Paths {
+        Paths { sum: out_dir.join("sum.txt"), error_log: out_dir.join("errors.log"), ..self }
+    }
+}
+
+// Errors end the program with a one-line message instead of a panic, and the exit code
+//    tells a calling script what went wrong; `--verbose` adds the full error chain
+fn main() -> Result<(), Box<dyn Error>> {
+    let verbose = env::args().skip(1).any(|arg| arg == "--verbose");
+    let out_dir = env::temp_dir().join("error-handling");
+    fs::create_dir_all(&out_dir)?;
+    let paths = Paths::in_dir(&env::current_dir()?).with_output_in(&out_dir);
+    let reporter = FileReporter::new(&paths.error_log);
+    if let Err(e) = run(&paths, &reporter) {
+        eprintln!("Error: {}", exit::user_message(&e, verbose));
+        process::exit(exit::exit_code_for(&e));
+    }
+    Ok(())
+}
+
+// Recovering from an error shouldn't lose it; a reporter that fails is only a warning
+fn recovered(reporter: &dyn ErrorReporter, err: &dyn Error, context: &str) {
+    if let Err(e) = reporter.report(err, context) {
+        eprintln!("Warning: could not record an error ({context}: {err}): {e}");
+    }
+}
+
+fn run(paths: &Paths, reporter: &dyn ErrorReporter) -> Result<(), AppError> {
+    let greeting_file_result = File::open(&paths.hello);
     let _greeting_file = match greeting_file_result {
         Ok(file) => file,
         // Err(error) => panic!("Problem opening the file: {error:?}"),
         Err(error) => match error.kind() {
-            ErrorKind::NotFound => match File::create("hello.txt") {
-                Ok(fc) => fc,
-                Err(e) => panic!("Problem creating the file: {e:?}"),
-            },
-            other_error => panic!("Problem opening the file: {other_error:?}"),
+            ErrorKind::NotFound => {
+                recovered(reporter, &files::with_path(&paths.hello, error), "opening greeting, creating it");
+                File::create(&paths.hello).map_err(|e| files::with_path(&paths.hello, e))?
+            }
+            _ => return Err(files::with_path(&paths.hello, error).into()),
         }
     };
 
+    // the same thing without the race: exactly one process creates the file
+    let _greeting_file = files::ensure_file_with_default(&paths.hello, "")?;
+
+    // the same open, but an interrupted call gets two more tries; NotFound is final at once
+    match retry::retry(3, Duration::from_millis(10), || File::open(&paths.hello), |e| {
+        e.kind() == ErrorKind::Interrupted
+    }) {
+        Ok(_) => println!("opened {}", paths.hello.display()),
+        Err(e) => println!("Could not open {}: {e}: {}", paths.hello.display(), e.last),
+    }
+
+    // the same file again; an error here would say which file, and what was being done
+    match fs::read_to_string(&paths.hello).context("reading greeting").with_path(&paths.hello) {
+        Ok(text) => println!("greeting: {:?}", text.trim()),
+        Err(e) if e.kind() == ErrorKind::InvalidData => println!("The greeting isn't UTF-8: {e}"),
+        Err(e) => println!("{e}"),
+    }
+
+    // the username from hello.txt, or $USER, or a fallback; an unreadable hello.txt is an error
+    let source = FsSource::new(&paths.dir);
+    let opts = username::ResolveOpts::new("hello.txt", "USER").with_default("guest");
+    match username::resolve_username(&source, opts) {
+        Ok(resolved) => println!("username {:?} (from the {})", resolved.value, resolved.source),
+        Err(e) => {
+            println!("No username: {e}");
+            recovered(reporter, &e, "resolving username");
+        }
+    }
+
+    // every file is tried; a missing one doesn't stop the others from being read
+    let (names, missing) = username::read_usernames(&source, &["hello.txt", "hello2.txt"]);
+    println!("read {} username file(s), {} failed", names.len(), missing.len());
+    if let Err(e) = username::read_usernames_strict(&source, &["hello2.txt", "hello.txt"]) {
+        println!("Strict read stopped at the first error: {e}");
+    }
+
+    // hello.txt starts out empty, so this one is expected to be refused
+    match username::load_valid_username(&source, "hello.txt") {
+        Ok(name) => println!("valid username {name} ({} characters)", name.as_str().len()),
+        Err(e) => {
+            println!("No valid username: {e}");
+            recovered(reporter, &e, "loading valid username");
+        }
+    }
+
+    // every line checked, not just up to the first bad one
+    match numbers::parse_numbers(&paths.numbers) {
+        Ok(values) => println!("numbers.txt holds {values:?}"),
+        Err(report) => {
+            println!("numbers.txt has problems: {report}");
+            recovered(reporter, &report, "checking numbers");
+        }
+    }
+
+    // one error type covering both "can't read the file" and "can't parse a line"
+    let sum = app_error::read_and_sum(&paths.numbers)?;
+    println!("numbers.txt adds up to {sum}");
+    // replaced in one step, so sum.txt is never half-written; the last one stays in sum.txt.bak
+    files::backup_and_write(&paths.sum, format!("{sum}\n").as_bytes())?;
+
+    // no app.conf in this project, so these are the defaults; a broken one would be reported
+    match config::Config::load_or_default(&source, "app.conf") {
+        Ok(config) => println!("{} runs with {} retries", config.name, config.retries),
+        Err(e) => {
+            println!("Bad app.conf: {e}");
+            recovered(reporter, &e, "loading config, using defaults");
+        }
+    }
+
+    // a guess that can be wrong without taking the program down with it
+    for input in ["42", "0", "lots"] {
+        match input.parse::<guess::Guess>() {
+            Ok(guess) => println!("guessed {}", guess.value()),
+            Err(e) => println!("Bad guess: {e}"),
+        }
+    }
+    // an unwrap that's not ours to fix, caught and reported instead of ending the program
+    if let Err(e) = panics::catch_as_error(|| "lots".parse::<i32>().unwrap()) {
+        println!("Caught: {e}");
+        recovered(reporter, &e, "parsing with unwrap");
+    }
+    // the same parse without the unwrap: its own error comes through, in the same Result
+    match panics::catch_result(|| "lots".parse::<i32>()) {
+        Ok(n) => println!("parsed {n}"),
+        Err(panics::CaughtError::Failed(e)) => println!("Failed: {e}"),
+        Err(panics::CaughtError::Panicked(e)) => println!("Caught: {e}"),
+    }
+
+    // the book's way: fine for a value the program chose itself
+    let _guess = guess::Guess::new_or_panic(50);
+
     // let _greeting_file = File::open("hello2.txt").unwrap();
-    let _greeting_file = File::open("hello2.txt")
-        .expect("hello2.txt should be included in this project");
+    // let _greeting_file = File::open("hello2.txt")
+    //     .expect("hello2.txt should be included in this project");
+    let _greeting_file = File::open(&paths.hello2).map_err(|e| files::with_path(&paths.hello2, e))?;
+    Ok(())
 }
 
-fn _read_username_from_file_verbose() -> Result<String, io::Error> {
-    let username_file_result = File::open("hello.txt");
-    let mut username_file = match username_file_result {
-        Ok(file) => file,
+// the long way round on purpose: this is what `?` below expands to
+#[allow(clippy::question_mark)]
+fn _read_username_from_file_verbose(source: &impl ContentSource) -> Result<String, ContextError> {
+    let path = source.path_of("hello.txt");
+    let username_result = source.read("hello.txt").context("reading username").with_path(&path);
+    let username = match username_result {
+        Ok(username) => username,
         Err(e) => return Err(e),
     };
-
-    let mut username = String::new();
-    match username_file.read_to_string(&mut username) {
-        Ok(_) => Ok(username),
-        Err(e) => Err(e),
-    }
+    Ok(username)
 }
 
-fn _read_username_from_file_shortcut() -> Result<String, io::Error> {
-    let mut username_file = File::open("hello.txt")?;
-    let mut username = String::new();
-    username_file.read_to_string(&mut username)?;
+fn _read_username_from_file_shortcut(source: &impl ContentSource) -> Result<String, ContextError> {
+    let path = source.path_of("hello.txt");
+    let username = source.read("hello.txt").context("reading username").with_path(&path)?;
     Ok(username)
 }
 
-fn _read_username_from_file() -> Result<String, io::Error> {
-    let mut username = String::new();
-    File::open("hello.txt")?.read_to_string(&mut username)?;
-    Ok(username)
+fn _read_username_from_file(source: &impl ContentSource) -> Result<String, ContextError> {
+    let path = source.path_of("hello.txt");
+    source
+        .read("hello.txt")
+        .map_err(|e| ContextError::new("reading username", e))
+        .with_path(&path)
 }
 
-fn _read_username_from_file_oneline() -> Result<String, io::Error> {
-    fs::read_to_string("hello.txt")
+// any source will do: FsSource for the real file, a MapSource in the tests
+fn _read_username_from_file_oneline(source: &impl ContentSource) -> Result<String, ContextError> {
+    source.read("hello.txt").context("reading username").with_path(&source.path_of("hello.txt"))
 }
 
 fn _last_char_of_first_line(text: &str) -> Option<char> {
     text.lines().next()?.chars().last()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use report::MemoryReporter;
+    use source::{FailingSource, MapSource};
+    use test_util::TempDir;
+
+    // A fresh directory with the files run() needs, apart from those in `skip`
+    fn demo_dir(name: &str, skip: &[&str]) -> TempDir {
+        let dir = TempDir::new(name);
+        for (file, contents) in [("hello2.txt", "hi\n"), ("numbers.txt", "1\n2\n")] {
+            if !skip.contains(&file) {
+                dir.write(file, contents);
+            }
+        }
+        dir
+    }
+
+    #[test]
+    fn output_can_go_elsewhere() {
+        let paths = Paths::in_dir(Path::new("project")).with_output_in(Path::new("/tmp/out"));
+        assert_eq!(paths.sum, Path::new("/tmp/out/sum.txt"));
+        assert_eq!(paths.error_log, Path::new("/tmp/out/errors.log"));
+        // the inputs stay where they were
+        assert_eq!(paths.dir, Path::new("project"));
+        assert_eq!(paths.numbers, Path::new("project/numbers.txt"));
+    }
+
+    #[test]
+    fn run_succeeds_with_every_file_present() {
+        let dir = demo_dir("run-ok", &[]);
+        let reporter = MemoryReporter::default();
+        run(&Paths::in_dir(dir.path()), &reporter).unwrap();
+        // the greeting file was created on the way, and that was recorded
+        assert!(dir.join("hello.txt").exists());
+        assert_eq!(fs::read_to_string(dir.join("sum.txt")).unwrap(), "3\n");
+        let records = reporter.records();
+        assert!(records[0].contains("[opening greeting, creating it] "), "{records:?}");
+        assert!(records.iter().any(|r| r.contains("[parsing with unwrap] panicked: ")), "{records:?}");
+    }
+
+    #[test]
+    fn missing_file_is_named_in_the_error() {
+        let dir = demo_dir("run-missing", &["hello2.txt"]);
+        let err = run(&Paths::in_dir(dir.path()), &MemoryReporter::default()).unwrap_err();
+        let expected = format!("I/O error: {}: ", dir.join("hello2.txt").display());
+        assert!(err.to_string().starts_with(&expected), "{err}");
+        assert_eq!(exit::exit_code_for(&err), exit::EX_NOINPUT);
+    }
+
+    #[test]
+    fn bad_numbers_stop_the_run() {
+        let dir = demo_dir("run-bad-numbers", &["numbers.txt"]);
+        dir.write("numbers.txt", "1\ntwo\n");
+        let err = run(&Paths::in_dir(dir.path()), &MemoryReporter::default()).unwrap_err();
+        assert_eq!(err.to_string(), "line 2: invalid digit found in string");
+        assert_eq!(exit::exit_code_for(&err), exit::EX_DATAERR);
+    }
+
+    // the four _read_username_from_file versions, in order
+    fn read_every_way(source: &impl ContentSource) -> Vec<Result<String, ContextError>> {
+        vec![
+            _read_username_from_file_verbose(source),
+            _read_username_from_file_shortcut(source),
+            _read_username_from_file(source),
+            _read_username_from_file_oneline(source),
+        ]
+    }
+
+    #[test]
+    fn every_way_to_read_the_username_agrees() {
+        let source = MapSource::new(&[("hello.txt", "ferris\n")]);
+        for result in read_every_way(&source) {
+            assert_eq!(result.unwrap(), "ferris\n");
+        }
+        for result in read_every_way(&MapSource::default()) {
+            let err = result.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::NotFound);
+            assert!(err.to_string().starts_with(r#"reading username from "hello.txt": "#), "{err}");
+        }
+        for kind in [ErrorKind::NotFound, ErrorKind::PermissionDenied] {
+            for result in read_every_way(&FailingSource(kind)) {
+                assert_eq!(result.unwrap_err().kind(), kind);
+            }
+        }
+    }
+}
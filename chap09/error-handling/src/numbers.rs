@@ -0,0 +1,146 @@
+// Checking a whole data file instead of stopping at its first bad line: every line is
+//    parsed, and all the failures come back together, so they can be fixed in one go.
+//
+// One integer per line. Blank lines and lines starting with `#` are skipped, and
+//    whitespace around a number doesn't matter.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::path::Path;
+
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    // (line number from 1, the line without surrounding whitespace, what was wrong), in
+    //    file order
+    pub bad_lines: Vec<(usize, String, ParseIntError)>,
+    // set when the file couldn't be read at all; bad_lines is empty then
+    pub io: Option<io::Error>,
+}
+
+impl ParseReport {
+    pub fn is_empty(&self) -> bool {
+        self.bad_lines.is_empty() && self.io.is_none()
+    }
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(e) = &self.io {
+            return write!(f, "could not read the numbers: {e}");
+        }
+        write!(f, "{} bad line(s)", self.bad_lines.len())?;
+        for (line, content, e) in &self.bad_lines {
+            write!(f, "\n  line {line}: `{content}`: {e}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseReport {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.io.as_ref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+fn parse_text(text: &str) -> (Vec<i64>, ParseReport) {
+    let mut numbers = Vec::new();
+    let mut report = ParseReport::default();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.parse() {
+            Ok(n) => numbers.push(n),
+            Err(e) => report.bad_lines.push((index + 1, line.to_string(), e)),
+        }
+    }
+    (numbers, report)
+}
+
+// All the numbers, or a report of every line that isn't one
+pub fn parse_numbers(path: impl AsRef<Path>) -> Result<Vec<i64>, ParseReport> {
+    let (numbers, report) = parse_numbers_lossy(path);
+    if report.is_empty() { Ok(numbers) } else { Err(report) }
+}
+
+// The numbers that did parse, and the report on the rest; an empty report means all is well
+pub fn parse_numbers_lossy(path: impl AsRef<Path>) -> (Vec<i64>, ParseReport) {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_text(&text),
+        Err(e) => (Vec::new(), ParseReport { io: Some(e), ..ParseReport::default() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    const FIXTURE: &str = "# readings for monday\n\
+                           12\n\
+                           7   \n\
+                           \n\
+                           twelve\n\
+                           \t-3\n\
+                           # 99 is commented out\n\
+                           4.5\n\
+                           99999999999999999999\n\
+                           +8\n";
+
+    fn summary(report: &ParseReport) -> Vec<(usize, &str, String)> {
+        report.bad_lines.iter().map(|(line, content, e)| (*line, content.as_str(), e.to_string())).collect()
+    }
+
+    #[test]
+    fn every_bad_line_is_reported() {
+        let dir = TempDir::new("numbers-mixed");
+        let path = dir.write("numbers.txt", FIXTURE);
+        let report = parse_numbers(&path).unwrap_err();
+        assert_eq!(
+            summary(&report),
+            vec![
+                (5, "twelve", String::from("invalid digit found in string")),
+                (8, "4.5", String::from("invalid digit found in string")),
+                (9, "99999999999999999999", String::from("number too large to fit in target type")),
+            ]
+        );
+        assert!(report.io.is_none());
+        assert_eq!(
+            report.to_string(),
+            "3 bad line(s)\n  line 5: `twelve`: invalid digit found in string\n  \
+             line 8: `4.5`: invalid digit found in string\n  \
+             line 9: `99999999999999999999`: number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn lossy_keeps_the_good_values() {
+        let dir = TempDir::new("numbers-lossy");
+        let path = dir.write("numbers.txt", FIXTURE);
+        let (numbers, report) = parse_numbers_lossy(&path);
+        assert_eq!(numbers, vec![12, 7, -3, 8]);
+        assert_eq!(report.bad_lines.len(), 3);
+    }
+
+    #[test]
+    fn clean_file_parses() {
+        let dir = TempDir::new("numbers-clean");
+        let path = dir.write("numbers.txt", "# header\n1\n\n  2  \n3");
+        assert_eq!(parse_numbers(&path).unwrap(), vec![1, 2, 3]);
+        let (_, report) = parse_numbers_lossy(&path);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn unreadable_file() {
+        let report = parse_numbers("/definitely/not/here.txt").unwrap_err();
+        assert_eq!(report.io.as_ref().unwrap().kind(), io::ErrorKind::NotFound);
+        assert!(report.bad_lines.is_empty());
+        assert!(report.source().is_some());
+        assert!(report.to_string().starts_with("could not read the numbers: "));
+    }
+}
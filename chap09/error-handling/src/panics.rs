@@ -0,0 +1,195 @@
+// Turning a panic back into an error, for code that panics where a Result would do
+//    (an unwrap deep inside a library, say). catch_unwind stops the unwinding at the
+//    closure's edge and hands back whatever was passed to panic!.
+//
+// This only works when panics unwind: with `panic = "abort"` there is nothing to catch.
+//    And it's for other people's panics; code of our own should return a Result.
+
+use std::any::Any;
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, UnwindSafe};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicError {
+    pub message: String,
+}
+
+impl PanicError {
+    // panic!("literal") carries a &str, panic!("{x}") a String; anything else
+    //    (std::panic::panic_any(42)) has no message we can show
+    fn from_payload(payload: Box<dyn Any + Send>) -> PanicError {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => String::from("non-string panic payload"),
+            },
+        };
+        PanicError { message }
+    }
+}
+
+impl fmt::Display for PanicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "panicked: {}", self.message)
+    }
+}
+
+impl Error for PanicError {}
+
+// The panic hook is one global for the whole process, so swapping it is serialized:
+//    otherwise two threads could each "restore" the other's quiet hook. The flag lets a
+//    thread that already holds the lock (a nested call) go ahead instead of deadlocking.
+static HOOK_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    static HOLDS_HOOK_LOCK: Cell<bool> = const { Cell::new(false) };
+}
+
+struct HookLock(Option<MutexGuard<'static, ()>>);
+
+fn lock_hook() -> HookLock {
+    if HOLDS_HOOK_LOCK.get() {
+        return HookLock(None);
+    }
+    let guard = HOOK_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    HOLDS_HOOK_LOCK.set(true);
+    HookLock(Some(guard))
+}
+
+impl Drop for HookLock {
+    fn drop(&mut self) {
+        if self.0.is_some() {
+            HOLDS_HOOK_LOCK.set(false);
+        }
+    }
+}
+
+// Runs f, with a panic coming back as Err instead of unwinding further. The usual
+//    "thread 'main' panicked at ..." message is suppressed while f runs (for every
+//    thread, since the hook is global) and the previous hook is put back afterwards.
+pub fn catch_as_error<T>(f: impl FnOnce() -> T + UnwindSafe) -> Result<T, PanicError> {
+    let _lock = lock_hook();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(f);
+    panic::set_hook(previous);
+    result.map_err(PanicError::from_payload)
+}
+
+// The message only says which of the two it was; what went wrong is the source()
+#[derive(Debug)]
+pub enum CaughtError<E> {
+    Panicked(PanicError),
+    // f returned an error of its own
+    Failed(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CaughtError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CaughtError::Panicked(_) => f.write_str("the closure panicked"),
+            CaughtError::Failed(_) => f.write_str("the closure returned an error"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for CaughtError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CaughtError::Panicked(e) => Some(e),
+            CaughtError::Failed(e) => Some(e),
+        }
+    }
+}
+
+// catch_as_error for a closure that already returns a Result: one Result out, not two
+pub fn catch_result<T, E>(f: impl FnOnce() -> Result<T, E> + UnwindSafe) -> Result<T, CaughtError<E>> {
+    match catch_as_error(f) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(CaughtError::Failed(e)),
+        Err(panicked) => Err(CaughtError::Panicked(panicked)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::ParseIntError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn panics_become_errors() {
+        let err = catch_as_error(|| -> i32 { panic!("plain message") }).unwrap_err();
+        assert_eq!(err.message, "plain message");
+        assert_eq!(err.to_string(), "panicked: plain message");
+
+        let n = 7;
+        let err = catch_as_error(|| -> i32 { panic!("formatted {n}") }).unwrap_err();
+        assert_eq!(err.message, "formatted 7");
+
+        let err = catch_as_error(|| "seven".parse::<i32>().unwrap()).unwrap_err();
+        assert!(err.message.starts_with("called `Result::unwrap()` on an `Err` value"), "{err}");
+
+        let err = catch_as_error(|| panic::panic_any(42)).unwrap_err();
+        assert_eq!(err.message, "non-string panic payload");
+    }
+
+    #[test]
+    fn values_pass_through() {
+        assert_eq!(catch_as_error(|| 1 + 1), Ok(2));
+        assert_eq!(catch_as_error(|| catch_as_error(|| -> () { panic!("inner") }).is_err()), Ok(true));
+    }
+
+    #[test]
+    fn results_are_flattened() {
+        assert_eq!(catch_result(|| "42".parse::<i32>()).unwrap(), 42);
+        match catch_result(|| "x".parse::<i32>()) {
+            Err(CaughtError::Failed(e)) => assert_eq!(e.to_string(), "invalid digit found in string"),
+            other => panic!("expected Failed, got {other:?}"),
+        }
+        match catch_result(|| -> Result<i32, ParseIntError> { panic!("gave up") }) {
+            Err(CaughtError::Panicked(e)) => assert_eq!(e.message, "gave up"),
+            other => panic!("expected Panicked, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn caught_errors_name_the_kind_and_keep_the_cause() {
+        let err = catch_result(|| "x".parse::<i32>()).unwrap_err();
+        assert_eq!(err.to_string(), "the closure returned an error");
+        assert_eq!(err.source().unwrap().to_string(), "invalid digit found in string");
+
+        let err = catch_result(|| -> Result<i32, ParseIntError> { panic!("gave up") }).unwrap_err();
+        assert_eq!(err.to_string(), "the closure panicked");
+        assert_eq!(err.source().unwrap().to_string(), "panicked: gave up");
+    }
+
+    #[test]
+    fn previous_hook_is_restored() {
+        // holding the lock keeps the other tests from swapping the hook meanwhile
+        let _lock = lock_hook();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        // other tests may panic meanwhile (should_panic ones); only count this thread's
+        let this_thread = std::thread::current().id();
+        panic::set_hook(Box::new(move |_| {
+            if std::thread::current().id() == this_thread {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        }));
+
+        // silenced inside catch_as_error...
+        assert!(catch_as_error(|| -> () { panic!("quiet") }).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        // ...and our hook is back in place after it
+        assert!(panic::catch_unwind(|| -> () { panic!("loud") }).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let _ = panic::take_hook();
+    }
+}
@@ -0,0 +1,139 @@
+// A record of the errors the program recovered from. They don't stop it, so nobody sees
+//    them at the time; a line in a log file means they can still be looked at later.
+//
+// One line per error: when, what was being done, the error, and every source() behind it:
+//
+//     1712345678.042 [reading config] invalid value | caused by: invalid digit found in string
+
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub trait ErrorReporter {
+    fn report(&self, err: &dyn Error, context: &str) -> io::Result<()>;
+}
+
+pub fn format_record(err: &dyn Error, context: &str, at: SystemTime) -> String {
+    let since_epoch = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let mut line = format!(
+        "{}.{:03} [{context}] {err}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_millis()
+    );
+    let mut source = err.source();
+    while let Some(cause) = source {
+        line.push_str(&format!(" | caused by: {cause}"));
+        source = cause.source();
+    }
+    // a multi-line message would look like several records
+    line.replace('\n', " ")
+}
+
+// Appends to the file, creating it first if it isn't there
+pub struct FileReporter {
+    path: PathBuf,
+}
+
+impl FileReporter {
+    pub fn new(path: impl Into<PathBuf>) -> FileReporter {
+        FileReporter { path: path.into() }
+    }
+}
+
+impl ErrorReporter for FileReporter {
+    // Opened for every record: nothing is held open between errors, and a file deleted
+    //    in the meantime is simply created again
+    fn report(&self, err: &dyn Error, context: &str) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", format_record(err, context, SystemTime::now()))
+    }
+}
+
+// Keeps the records in memory, for tests
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryReporter {
+    records: std::cell::RefCell<Vec<String>>,
+}
+
+#[cfg(test)]
+impl MemoryReporter {
+    pub fn records(&self) -> Vec<String> {
+        self.records.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+impl ErrorReporter for MemoryReporter {
+    fn report(&self, err: &dyn Error, context: &str) -> io::Result<()> {
+        self.records.borrow_mut().push(format_record(err, context, SystemTime::now()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Context, ContextError};
+    use crate::test_util::TempDir;
+    use crate::username::LoadUsernameError;
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn nested_error() -> LoadUsernameError {
+        let io = io::Error::new(io::ErrorKind::NotFound, "No such file or directory");
+        let err: Result<(), ContextError> = Err(io).context("reading username").with_path(Path::new("hello.txt"));
+        LoadUsernameError::Io(err.unwrap_err())
+    }
+
+    #[test]
+    fn record_lists_the_whole_chain() {
+        let at = UNIX_EPOCH + Duration::from_millis(1_712_345_678_042);
+        assert_eq!(
+            format_record(&nested_error(), "loading username", at),
+            "1712345678.042 [loading username] could not read the username file \
+             | caused by: reading username from \"hello.txt\": No such file or directory \
+             | caused by: No such file or directory"
+        );
+
+        let plain = io::Error::other("disk full");
+        assert_eq!(format_record(&plain, "saving", at), "1712345678.042 [saving] disk full");
+    }
+
+    #[test]
+    fn memory_reporter_keeps_every_record() {
+        let reporter = MemoryReporter::default();
+        reporter.report(&nested_error(), "first").unwrap();
+        reporter.report(&io::Error::other("line one\nline two"), "second").unwrap();
+        let records = reporter.records();
+        assert_eq!(records.len(), 2);
+        assert!(records[0].contains("[first] could not read the username file"), "{}", records[0]);
+        assert!(records[1].ends_with("[second] line one line two"), "{}", records[1]);
+    }
+
+    #[test]
+    fn file_reporter_appends() {
+        let dir = TempDir::new("report");
+        let reporter = FileReporter::new(dir.join("errors.log"));
+        reporter.report(&io::Error::other("one"), "a").unwrap();
+        reporter.report(&io::Error::other("two"), "b").unwrap();
+
+        let log = fs::read_to_string(dir.join("errors.log")).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[a] one") && lines[1].ends_with("[b] two"), "{log}");
+    }
+
+    #[test]
+    fn unwritable_log_is_an_error_not_a_panic() {
+        // a directory can't be opened for appending, even by root
+        let reporter = FileReporter::new(std::env::temp_dir());
+        assert!(reporter.report(&io::Error::other("lost"), "nowhere").is_err());
+        let reporter = FileReporter::new("/definitely/not/here/errors.log");
+        let err = reporter.report(&io::Error::other("lost"), "nowhere").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,134 @@
+// Retrying an operation that can fail for a moment (an interrupted read, a busy file).
+//    Only errors the caller says are worth retrying are retried; anything else is
+//    returned at once, since trying again wouldn't change the answer.
+
+use std::error::Error;
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+// The error from the last attempt, and how many attempts were made. The message only
+//    gives the count; the last error is the source()
+#[derive(Debug, PartialEq, Eq)]
+pub struct RetryError<E> {
+    pub attempts: u32,
+    pub last: E,
+}
+
+impl<E> fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = if self.attempts == 1 { "" } else { "s" };
+        write!(f, "failed after {} attempt{plural}", self.attempts)
+    }
+}
+
+impl<E: Error + 'static> Error for RetryError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.last)
+    }
+}
+
+// Runs op up to `attempts` times. Between attempts it sleeps backoff, then twice that,
+//    then four times, and so on.
+//
+// Panics if attempts is zero: with no attempt there's no result and no error to return.
+pub fn retry<T, E>(
+    attempts: u32,
+    backoff: Duration,
+    mut op: impl FnMut() -> Result<T, E>,
+    should_retry: impl Fn(&E) -> bool,
+) -> Result<T, RetryError<E>> {
+    assert!(attempts > 0, "retry needs at least one attempt");
+    let mut wait = backoff;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < attempts && should_retry(&e) => {
+                thread::sleep(wait);
+                wait = wait.saturating_mul(2);
+                attempt += 1;
+            }
+            Err(last) => return Err(RetryError { attempts: attempt, last }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, ErrorKind};
+    use std::time::Instant;
+
+    fn interrupted() -> io::Error {
+        io::Error::from(ErrorKind::Interrupted)
+    }
+
+    fn retry_interrupted(e: &io::Error) -> bool {
+        e.kind() == ErrorKind::Interrupted
+    }
+
+    #[test]
+    fn fails_twice_then_succeeds() {
+        let mut calls = 0;
+        let start = Instant::now();
+        let result = retry(
+            5,
+            Duration::from_millis(10),
+            || {
+                calls += 1;
+                if calls <= 2 { Err(interrupted()) } else { Ok(calls) }
+            },
+            retry_interrupted,
+        );
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls, 3);
+        // slept 10ms, then 20ms
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn non_retryable_error_short_circuits() {
+        let mut calls = 0;
+        let start = Instant::now();
+        let result: Result<(), _> = retry(
+            5,
+            Duration::from_secs(10),
+            || {
+                calls += 1;
+                Err(io::Error::from(ErrorKind::NotFound))
+            },
+            retry_interrupted,
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.attempts, 1);
+        assert_eq!(err.last.kind(), ErrorKind::NotFound);
+        assert_eq!(calls, 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn gives_up_after_the_last_attempt() {
+        let mut calls = 0;
+        let result: Result<(), _> = retry(
+            3,
+            Duration::from_millis(1),
+            || {
+                calls += 1;
+                Err(interrupted())
+            },
+            retry_interrupted,
+        );
+        let err = result.unwrap_err();
+        assert_eq!((err.attempts, calls), (3, 3));
+        assert_eq!(err.to_string(), "failed after 3 attempts");
+        assert_eq!(err.source().unwrap().to_string(), "operation interrupted");
+    }
+
+    #[test]
+    fn a_single_attempt_never_sleeps() {
+        let result = retry(1, Duration::from_secs(10), || Err::<(), _>("busy"), |_| true);
+        assert_eq!(result, Err(RetryError { attempts: 1, last: "busy" }));
+        assert_eq!(result.unwrap_err().to_string(), "failed after 1 attempt");
+    }
+}
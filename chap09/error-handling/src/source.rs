@@ -0,0 +1,103 @@
+// Where the readers in this crate get their text from. The program reads files (FsSource);
+//    tests hand them a HashMap (MapSource) or a source that always fails (FailingSource), so
+//    "no such file" versus "permission denied" can be tested without creating, chmod-ing
+//    and cleaning up real files.
+
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::io::ErrorKind;
+
+pub trait ContentSource {
+    fn read(&self, name: &str) -> io::Result<String>;
+
+    // What to call `name` in an error message; FsSource gives the whole path
+    fn path_of(&self, name: &str) -> PathBuf {
+        PathBuf::from(name)
+    }
+}
+
+// Files under `root`: read("hello.txt") reads root/hello.txt
+#[derive(Debug, Clone)]
+pub struct FsSource(pub PathBuf);
+
+impl FsSource {
+    pub fn new(root: impl AsRef<Path>) -> FsSource {
+        FsSource(root.as_ref().to_path_buf())
+    }
+}
+
+impl ContentSource for FsSource {
+    fn read(&self, name: &str) -> io::Result<String> {
+        std::fs::read_to_string(self.path_of(name))
+    }
+
+    fn path_of(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+// name -> contents; a name that isn't in the map is NotFound, like a missing file
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct MapSource(pub HashMap<String, String>);
+
+#[cfg(test)]
+impl MapSource {
+    pub fn new(files: &[(&str, &str)]) -> MapSource {
+        MapSource(files.iter().map(|&(name, text)| (name.to_string(), text.to_string())).collect())
+    }
+}
+
+#[cfg(test)]
+impl ContentSource for MapSource {
+    fn read(&self, name: &str) -> io::Result<String> {
+        self.0
+            .get(name)
+            .cloned()
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, format!("no {name:?} in the map")))
+    }
+}
+
+// Every read fails with this kind
+#[cfg(test)]
+#[derive(Debug, Clone, Copy)]
+pub struct FailingSource(pub ErrorKind);
+
+#[cfg(test)]
+impl ContentSource for FailingSource {
+    fn read(&self, name: &str) -> io::Result<String> {
+        Err(io::Error::new(self.0, format!("reading {name:?} failed on purpose")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn fs_source_reads_under_its_root() {
+        let dir = TempDir::new("source");
+        dir.write("hello.txt", "hi\n");
+
+        let source = FsSource::new(dir.path());
+        assert_eq!(source.read("hello.txt").unwrap(), "hi\n");
+        assert_eq!(source.read("missing.txt").unwrap_err().kind(), ErrorKind::NotFound);
+        assert_eq!(source.path_of("hello.txt"), dir.join("hello.txt"));
+    }
+
+    #[test]
+    fn map_and_failing_sources() {
+        let source = MapSource::new(&[("hello.txt", "hi")]);
+        assert_eq!(source.read("hello.txt").unwrap(), "hi");
+        assert_eq!(source.read("other.txt").unwrap_err().kind(), ErrorKind::NotFound);
+        assert_eq!(source.path_of("hello.txt"), Path::new("hello.txt"));
+
+        let err = FailingSource(ErrorKind::PermissionDenied).read("hello.txt").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(err.to_string(), r#"reading "hello.txt" failed on purpose"#);
+    }
+}
@@ -0,0 +1,41 @@
+// Shared by the tests: a scratch directory per test, so no test sees another's files.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// error-handling-<name>-<pid> in the system temp dir, created empty (anything a crashed
+//    earlier run left there is removed first) and removed again when dropped, even if
+//    the test fails
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("error-handling-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, file: impl AsRef<Path>) -> PathBuf {
+        self.path.join(file)
+    }
+
+    // Writes `file` in the directory and returns its path
+    pub fn write(&self, file: &str, contents: &str) -> PathBuf {
+        let path = self.join(file);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
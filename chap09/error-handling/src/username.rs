@@ -0,0 +1,447 @@
+// Where the username comes from, in order: a file, then an environment variable, then a
+//    built-in default. Only "not there" moves on to the next source: a file that exists
+//    but can't be read (no permission, say) is an error, not a reason to quietly pick
+//    another name.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::context::{Context, ContextError};
+use crate::source::ContentSource;
+
+pub type EnvLookup = Box<dyn Fn(&str) -> Option<String>>;
+
+pub struct ResolveOpts {
+    // read from the ContentSource given to resolve_username
+    pub name: String,
+    pub env_var: String,
+    pub default: Option<String>,
+    // reads an environment variable; swapped out in tests so they don't touch the real one
+    pub lookup_env: EnvLookup,
+}
+
+impl ResolveOpts {
+    // No default, and the process environment
+    pub fn new(name: &str, env_var: &str) -> ResolveOpts {
+        ResolveOpts {
+            name: name.to_string(),
+            env_var: env_var.to_string(),
+            default: None,
+            lookup_env: Box::new(|name| env::var(name).ok()),
+        }
+    }
+
+    pub fn with_default(mut self, default: &str) -> ResolveOpts {
+        self.default = Some(default.to_string());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    File,
+    Env,
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Source::File => "file",
+            Source::Env => "environment",
+            Source::Default => "default",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved {
+    pub value: String,
+    pub source: Source,
+}
+
+#[derive(Debug)]
+pub enum ResolveError {
+    // the file is there but reading it failed; the reason is the source()
+    Io(ContextError),
+    // no file, no variable and no default
+    NotFound { path: PathBuf, env_var: String },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResolveError::Io(_) => f.write_str("could not read the username file"),
+            ResolveError::NotFound { path, env_var } => {
+                write!(f, "no username: {path:?} does not exist and ${env_var} is not set")
+            }
+        }
+    }
+}
+
+impl Error for ResolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ResolveError::Io(e) => Some(e),
+            ResolveError::NotFound { .. } => None,
+        }
+    }
+}
+
+// The file's first line (trimmed), else the variable, else the default
+pub fn resolve_username(source: &impl ContentSource, opts: ResolveOpts) -> Result<Resolved, ResolveError> {
+    let path = source.path_of(&opts.name);
+    match source.read(&opts.name).context("reading username").with_path(&path) {
+        Ok(text) => {
+            let value = text.lines().next().unwrap_or("").trim().to_string();
+            return Ok(Resolved { value, source: Source::File });
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(ResolveError::Io(e)),
+    }
+    if let Some(value) = (opts.lookup_env)(&opts.env_var) {
+        return Ok(Resolved { value, source: Source::Env });
+    }
+    match opts.default {
+        Some(value) => Ok(Resolved { value, source: Source::Default }),
+        None => Err(ResolveError::NotFound { path, env_var: opts.env_var }),
+    }
+}
+
+// (file name, username)
+pub type Usernames = Vec<(String, String)>;
+pub type Failures = Vec<(String, io::Error)>;
+
+fn read_username(source: &impl ContentSource, name: &str) -> io::Result<String> {
+    let text = source.read(name)?;
+    Ok(text.trim_end_matches(['\n', '\r']).to_string())
+}
+
+// Reads every file, even after some have failed: (what was read, what failed), both in
+//    the order of names. Trailing newlines are trimmed, any other whitespace is kept.
+pub fn read_usernames(source: &impl ContentSource, names: &[&str]) -> (Usernames, Failures) {
+    let mut read = Vec::new();
+    let mut failed = Vec::new();
+    for &name in names {
+        match read_username(source, name) {
+            Ok(username) => read.push((name.to_string(), username)),
+            Err(e) => failed.push((name.to_string(), e)),
+        }
+    }
+    (read, failed)
+}
+
+// The fail-fast version: stops at the first file that can't be read, so later files
+//    aren't even tried, and only that first error is reported
+pub fn read_usernames_strict(source: &impl ContentSource, names: &[&str]) -> Result<Usernames, ContextError> {
+    names
+        .iter()
+        .map(|&name| {
+            let username =
+                read_username(source, name).context("reading username").with_path(&source.path_of(name))?;
+            Ok((name.to_string(), username))
+        })
+        .collect()
+}
+
+pub const MAX_USERNAME_LEN: usize = 32;
+
+// A username that has passed validate_username; there's no other way to make one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Username(String);
+
+impl Username {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Username {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsernameError {
+    Empty,
+    // (the maximum, the actual length), both in characters
+    TooLong(usize, usize),
+    // index counts characters, not bytes
+    InvalidChar { ch: char, index: usize },
+    StartsWithDigit,
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UsernameError::Empty => write!(f, "the username is empty"),
+            UsernameError::TooLong(max, actual) => {
+                write!(f, "the username is {actual} characters long, the limit is {max}")
+            }
+            UsernameError::InvalidChar { ch, index } => {
+                write!(f, "{ch:?} at position {index} is not allowed in a username")
+            }
+            UsernameError::StartsWithDigit => write!(f, "the username must not start with a digit"),
+        }
+    }
+}
+
+impl Error for UsernameError {}
+
+// ASCII letters, digits, `_` and `-`, not starting with a digit, at most MAX_USERNAME_LEN
+pub fn validate_username(s: &str) -> Result<Username, UsernameError> {
+    let len = s.chars().count();
+    if len == 0 {
+        return Err(UsernameError::Empty);
+    }
+    if len > MAX_USERNAME_LEN {
+        return Err(UsernameError::TooLong(MAX_USERNAME_LEN, len));
+    }
+    if s.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(UsernameError::StartsWithDigit);
+    }
+    let allowed = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if let Some((index, ch)) = s.chars().enumerate().find(|&(_, c)| !allowed(c)) {
+        return Err(UsernameError::InvalidChar { ch, index });
+    }
+    Ok(Username(s.to_string()))
+}
+
+// For both, the message only says which step failed; the reason is the source()
+#[derive(Debug)]
+pub enum LoadUsernameError {
+    Io(ContextError),
+    Invalid(UsernameError),
+}
+
+impl fmt::Display for LoadUsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadUsernameError::Io(_) => f.write_str("could not read the username file"),
+            LoadUsernameError::Invalid(_) => f.write_str("invalid username"),
+        }
+    }
+}
+
+impl Error for LoadUsernameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadUsernameError::Io(e) => Some(e),
+            LoadUsernameError::Invalid(e) => Some(e),
+        }
+    }
+}
+
+impl From<ContextError> for LoadUsernameError {
+    fn from(e: ContextError) -> LoadUsernameError {
+        LoadUsernameError::Io(e)
+    }
+}
+
+impl From<UsernameError> for LoadUsernameError {
+    fn from(e: UsernameError) -> LoadUsernameError {
+        LoadUsernameError::Invalid(e)
+    }
+}
+
+// Reads the file (trailing newlines trimmed, nothing else) and validates what's in it
+pub fn load_valid_username(source: &impl ContentSource, name: &str) -> Result<Username, LoadUsernameError> {
+    let text = read_username(source, name).context("reading username").with_path(&source.path_of(name))?;
+    Ok(validate_username(&text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{FailingSource, FsSource, MapSource};
+    use crate::test_util::TempDir;
+    use std::fs;
+
+    // $APP_USER set to `env` (or not set), without touching the real environment
+    fn opts(name: &str, env: Option<&'static str>) -> ResolveOpts {
+        ResolveOpts {
+            lookup_env: Box::new(move |name| {
+                assert_eq!(name, "APP_USER");
+                env.map(String::from)
+            }),
+            ..ResolveOpts::new(name, "APP_USER")
+        }
+    }
+
+    fn no_env_lookup() -> ResolveOpts {
+        ResolveOpts {
+            lookup_env: Box::new(|_| panic!("looked up the variable after a real error")),
+            ..ResolveOpts::new("hello.txt", "APP_USER").with_default("guest")
+        }
+    }
+
+    #[test]
+    fn file_comes_first() {
+        let source = MapSource::new(&[("hello.txt", "ferris  \nsecond line\n")]);
+        let opts = opts("hello.txt", Some("from-env")).with_default("guest");
+        let resolved = resolve_username(&source, opts).unwrap();
+        assert_eq!(resolved, Resolved { value: String::from("ferris"), source: Source::File });
+    }
+
+    #[test]
+    fn env_when_there_is_no_file() {
+        let opts = opts("hello.txt", Some("from-env")).with_default("guest");
+        let resolved = resolve_username(&MapSource::default(), opts).unwrap();
+        assert_eq!(resolved, Resolved { value: String::from("from-env"), source: Source::Env });
+    }
+
+    #[test]
+    fn default_when_neither() {
+        let opts = opts("hello.txt", None).with_default("guest");
+        let resolved = resolve_username(&FailingSource(io::ErrorKind::NotFound), opts).unwrap();
+        assert_eq!(resolved, Resolved { value: String::from("guest"), source: Source::Default });
+        assert_eq!(resolved.source.to_string(), "default");
+    }
+
+    #[test]
+    fn nothing_at_all_is_an_error() {
+        let err = resolve_username(&MapSource::default(), opts("hello.txt", None)).unwrap_err();
+        assert!(matches!(&err, ResolveError::NotFound { env_var, .. } if env_var == "APP_USER"));
+        assert_eq!(err.to_string(), r#"no username: "hello.txt" does not exist and $APP_USER is not set"#);
+
+        // from files, the message gives the whole path
+        let source = FsSource::new("/definitely/not");
+        let err = resolve_username(&source, opts("here.txt", None)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"no username: "/definitely/not/here.txt" does not exist and $APP_USER is not set"#
+        );
+    }
+
+    #[test]
+    fn unreadable_file_does_not_fall_back() {
+        let err = resolve_username(&FailingSource(io::ErrorKind::PermissionDenied), no_env_lookup()).unwrap_err();
+        match &err {
+            ResolveError::Io(e) => assert_eq!(e.kind(), io::ErrorKind::PermissionDenied),
+            other => panic!("expected Io, got {other:?}"),
+        }
+        assert_eq!(err.to_string(), "could not read the username file");
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            r#"reading username from "hello.txt": reading "hello.txt" failed on purpose"#
+        );
+    }
+
+    // Permissions can't be taken away from root, which the tests may run as; a directory
+    //    is just as unreadable as a file, and fails with something other than NotFound too
+    #[test]
+    fn unreadable_real_file_does_not_fall_back() {
+        let dir = TempDir::new("username-unreadable");
+        fs::create_dir(dir.join("hello.txt")).unwrap();
+        let err = resolve_username(&FsSource::new(dir.path()), no_env_lookup()).unwrap_err();
+        assert!(matches!(&err, ResolveError::Io(e) if e.kind() != io::ErrorKind::NotFound), "{err:?}");
+        let expected = format!("reading username from {:?}: ", dir.join("hello.txt"));
+        assert!(err.source().unwrap().to_string().starts_with(&expected), "{err:?}");
+    }
+
+    // a.txt, b.txt and d.txt exist; c.txt and e.txt don't
+    const NAMES: [&str; 5] = ["a.txt", "c.txt", "b.txt", "e.txt", "d.txt"];
+
+    fn mixed_source() -> MapSource {
+        MapSource::new(&[("a.txt", "alice\n"), ("b.txt", "  bob\r\n\n"), ("d.txt", "dave")])
+    }
+
+    #[test]
+    fn every_file_is_tried_in_order() {
+        let (read, failed) = read_usernames(&mixed_source(), &NAMES);
+
+        let read: Vec<(&str, &str)> = read.iter().map(|(file, name)| (file.as_str(), name.as_str())).collect();
+        assert_eq!(read, vec![("a.txt", "alice"), ("b.txt", "  bob"), ("d.txt", "dave")]);
+        let failed_names: Vec<&str> = failed.iter().map(|(file, _)| file.as_str()).collect();
+        assert_eq!(failed_names, vec!["c.txt", "e.txt"]);
+        assert!(failed.iter().all(|(_, e)| e.kind() == io::ErrorKind::NotFound));
+
+        let (read, failed) = read_usernames(&mixed_source(), &[]);
+        assert!(read.is_empty() && failed.is_empty());
+
+        // every one fails, and is still tried
+        let (read, failed) = read_usernames(&FailingSource(io::ErrorKind::PermissionDenied), &NAMES);
+        assert!(read.is_empty());
+        assert_eq!(failed.len(), 5);
+        assert!(failed.iter().all(|(_, e)| e.kind() == io::ErrorKind::PermissionDenied));
+    }
+
+    #[test]
+    fn strict_stops_at_the_first_failure() {
+        let err = read_usernames_strict(&mixed_source(), &NAMES).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        // c.txt, not e.txt
+        assert!(err.to_string().starts_with(r#"reading username from "c.txt""#), "{err}");
+
+        let read = read_usernames_strict(&mixed_source(), &["d.txt", "a.txt"]).unwrap();
+        assert_eq!(
+            read,
+            vec![(String::from("d.txt"), String::from("dave")), (String::from("a.txt"), String::from("alice"))]
+        );
+    }
+
+    #[test]
+    fn strict_reads_real_files() {
+        let dir = TempDir::new("usernames");
+        dir.write("a.txt", "alice\n");
+        let source = FsSource::new(dir.path());
+        assert_eq!(read_usernames_strict(&source, &["a.txt"]).unwrap()[0].1, "alice");
+        let err = read_usernames_strict(&source, &["a.txt", "c.txt"]).unwrap_err();
+        assert!(err.to_string().starts_with(&format!("reading username from {:?}", dir.join("c.txt"))), "{err}");
+    }
+
+    #[test]
+    fn valid_usernames() {
+        for name in ["ferris", "a", "under_score", "dash-ed", "CamelCase99", &"x".repeat(32)] {
+            let username = validate_username(name).unwrap();
+            assert_eq!(username.as_str(), name);
+            assert_eq!(username.to_string(), name);
+        }
+    }
+
+    #[test]
+    fn each_way_to_be_invalid() {
+        assert_eq!(validate_username(""), Err(UsernameError::Empty));
+        assert_eq!(validate_username(&"x".repeat(33)), Err(UsernameError::TooLong(32, 33)));
+        // counted in characters: 66 bytes, but 33 characters
+        assert_eq!(validate_username(&"é".repeat(33)), Err(UsernameError::TooLong(32, 33)));
+        assert_eq!(validate_username("9lives"), Err(UsernameError::StartsWithDigit));
+        assert_eq!(validate_username("has space"), Err(UsernameError::InvalidChar { ch: ' ', index: 3 }));
+        assert_eq!(validate_username("a.b"), Err(UsernameError::InvalidChar { ch: '.', index: 1 }));
+        assert_eq!(
+            UsernameError::InvalidChar { ch: ' ', index: 3 }.to_string(),
+            "' ' at position 3 is not allowed in a username"
+        );
+    }
+
+    #[test]
+    fn invalid_char_index_counts_characters() {
+        // 'ü' is two bytes, so '한' is at byte 3 but character 2
+        assert_eq!(validate_username("aü한"), Err(UsernameError::InvalidChar { ch: 'ü', index: 1 }));
+        assert_eq!(validate_username("ab한c"), Err(UsernameError::InvalidChar { ch: '한', index: 2 }));
+        assert_eq!(validate_username("🦀rust"), Err(UsernameError::InvalidChar { ch: '🦀', index: 0 }));
+    }
+
+    #[test]
+    fn load_fails_with_either_kind_of_error() {
+        let source = MapSource::new(&[("good.txt", "ferris\n"), ("bad.txt", "not valid\n")]);
+        assert_eq!(load_valid_username(&source, "good.txt").unwrap().as_str(), "ferris");
+
+        let err = load_valid_username(&source, "bad.txt").unwrap_err();
+        assert!(matches!(err, LoadUsernameError::Invalid(UsernameError::InvalidChar { ch: ' ', index: 3 })));
+        assert_eq!(err.to_string(), "invalid username");
+        assert_eq!(err.source().unwrap().to_string(), "' ' at position 3 is not allowed in a username");
+
+        let err = load_valid_username(&source, "missing.txt").unwrap_err();
+        assert!(matches!(&err, LoadUsernameError::Io(e) if e.kind() == io::ErrorKind::NotFound));
+        assert_eq!(err.to_string(), "could not read the username file");
+        assert!(err.source().unwrap().is::<ContextError>());
+
+        let err = load_valid_username(&FailingSource(io::ErrorKind::PermissionDenied), "good.txt").unwrap_err();
+        assert!(matches!(&err, LoadUsernameError::Io(e) if e.kind() == io::ErrorKind::PermissionDenied));
+    }
+}
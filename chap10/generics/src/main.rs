@@ -1,4 +1,7 @@
+mod windows;
 
+// the two copies largest() replaces, kept for comparison
+#[allow(dead_code)]
 fn largest_i32(list: &[i32]) -> &i32 {
     let mut largest = &list[0];
     for item in list {
@@ -9,6 +12,7 @@ fn largest_i32(list: &[i32]) -> &i32 {
     largest
 }
 
+#[allow(dead_code)]
 fn largest_char(list: &[char]) -> &char {
     let mut largest = &list[0];
     for item in list {
@@ -75,6 +79,8 @@ fn main() {
 
     let p = Point { x: 5, y: 10 };
     println!("p.x = {}", p.x());
+    let p = Point { x: 3.0_f32, y: 4.0 };
+    println!("p is {} from the origin", p.distance_from_origin());
 
     let _both_integer = Point2 { x: 5, y: 10 };
     let _both_float = Point2 { x: 1.0, y: 4.0 };
@@ -86,4 +92,12 @@ fn main() {
     let p3 = p1.mixup(p2);
 
     println!("p3.x = {}, p3.y = {}", p3.x, p3.y);
+
+    let readings = [3, 1, 4, 1, 5, 9, 2, 6];
+    for window in windows::sliding_windows(&readings, 3) {
+        println!("window {window:?}");
+    }
+    println!("sliding max {:?}", windows::max_sliding_window(&readings, 3));
+    let readings: Vec<f64> = readings.iter().map(|&r| f64::from(r)).collect();
+    println!("moving average {:?}", windows::moving_average(&readings, 3));
 }
@@ -0,0 +1,160 @@
+// Every run of `size` consecutive elements, like the standard slice::windows.
+//
+// The window borrows from the slice, so Windows needs the slice's lifetime 'a: the
+//    &'a [T] it hands out stays valid after the iterator itself is gone.
+
+use std::collections::VecDeque;
+
+pub struct Windows<'a, T> {
+    slice: &'a [T],
+    size: usize,
+    // where the next window starts; never past the end, so slice.len() - pos can't underflow,
+    //    while pos + size could overflow
+    pos: usize,
+}
+
+// A size of 0 has no sensible windows, so it yields none (slice::windows panics instead)
+pub fn sliding_windows<T>(slice: &[T], size: usize) -> Windows<'_, T> {
+    Windows { slice, size, pos: 0 }
+}
+
+impl<'a, T> Iterator for Windows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        if self.size == 0 || self.size > self.slice.len() - self.pos {
+            return None;
+        }
+        let window = &self.slice[self.pos..self.pos + self.size];
+        self.pos += 1;
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = match self.size {
+            0 => 0,
+            size => (self.slice.len() - self.pos).checked_sub(size).map_or(0, |rest| rest + 1),
+        };
+        (left, Some(left))
+    }
+}
+
+// The largest value in each window of k, in O(n) overall.
+//
+// The deque holds indices whose values decrease from front to back. A new value first
+//    pushes out every smaller one behind it (they can never be a maximum again: the new
+//    one is bigger and stays in the window longer), and the front drops off once it has
+//    slid out of the window. The front is then always the current maximum.
+pub fn max_sliding_window(data: &[i32], k: usize) -> Vec<i32> {
+    if k == 0 || k > data.len() {
+        return Vec::new();
+    }
+    let mut result = Vec::with_capacity(data.len() - k + 1);
+    let mut candidates: VecDeque<usize> = VecDeque::with_capacity(k);
+    for (i, &value) in data.iter().enumerate() {
+        while candidates.back().is_some_and(|&j| data[j] <= value) {
+            candidates.pop_back();
+        }
+        candidates.push_back(i);
+        if candidates[0] + k <= i {
+            candidates.pop_front();
+        }
+        if i + 1 >= k {
+            result.push(data[candidates[0]]);
+        }
+    }
+    result
+}
+
+// The mean of each window of k, keeping a running sum instead of re-adding every window
+pub fn moving_average(data: &[f64], k: usize) -> Vec<f64> {
+    if k == 0 || k > data.len() {
+        return Vec::new();
+    }
+    let mut sum: f64 = data[..k].iter().sum();
+    let mut averages = vec![sum / k as f64];
+    for i in k..data.len() {
+        sum += data[i] - data[i - k];
+        averages.push(sum / k as f64);
+    }
+    averages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force_max(data: &[i32], k: usize) -> Vec<i32> {
+        if k == 0 {
+            return Vec::new();
+        }
+        data.windows(k).map(|w| *w.iter().max().unwrap()).collect()
+    }
+
+    #[test]
+    fn window_counts() {
+        let data: Vec<i32> = (0..10).collect();
+        for len in 0..=10 {
+            for size in 0..=12 {
+                let windows = sliding_windows(&data[..len], size);
+                let expected = if size == 0 || size > len { 0 } else { len - size + 1 };
+                assert_eq!(windows.size_hint(), (expected, Some(expected)), "len {len}, size {size}");
+                assert_eq!(windows.count(), expected, "len {len}, size {size}");
+            }
+        }
+    }
+
+    #[test]
+    fn huge_sizes_do_not_overflow() {
+        let mut windows = sliding_windows(&[1], usize::MAX);
+        assert_eq!(windows.size_hint(), (0, Some(0)));
+        assert_eq!(windows.next(), None);
+        assert_eq!(sliding_windows(&[1, 2], usize::MAX - 1).count(), 0);
+
+        // a slice of zero-sized values can be usize::MAX long
+        let units = vec![(); usize::MAX];
+        assert_eq!(sliding_windows(&units, 1).size_hint(), (usize::MAX, Some(usize::MAX)));
+        assert_eq!(sliding_windows(&units, usize::MAX).count(), 1);
+    }
+
+    #[test]
+    fn windows_match_the_standard_ones() {
+        let data = [1, 2, 3, 4, 5];
+        let ours: Vec<&[i32]> = sliding_windows(&data, 3).collect();
+        assert_eq!(ours, vec![&[1, 2, 3][..], &[2, 3, 4], &[3, 4, 5]]);
+        for size in 1..=6 {
+            assert!(sliding_windows(&data, size).eq(data.windows(size)), "size {size}");
+        }
+    }
+
+    #[test]
+    fn sliding_max_matches_brute_force() {
+        let data = [1, 3, -1, -3, 5, 3, 6, 7];
+        assert_eq!(max_sliding_window(&data, 3), vec![3, 3, 5, 5, 6, 7]);
+
+        // a fixed pseudo-random sequence, with plenty of repeats
+        let mut x: u32 = 12345;
+        let data: Vec<i32> = (0..200)
+            .map(|_| {
+                x = x.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (x >> 16) as i32 % 21 - 10
+            })
+            .collect();
+        for k in 0..=data.len() + 1 {
+            assert_eq!(max_sliding_window(&data, k), brute_force_max(&data, k), "k = {k}");
+        }
+        let descending: Vec<i32> = (0..50).rev().collect();
+        assert_eq!(max_sliding_window(&descending, 5), brute_force_max(&descending, 5));
+    }
+
+    #[test]
+    fn moving_average_of_each_window() {
+        let data = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(moving_average(&data, 2), vec![1.5, 2.5, 3.5, 4.5]);
+        assert_eq!(moving_average(&data, 5), vec![3.0]);
+        assert_eq!(moving_average(&data, 1), data.to_vec());
+        assert!(moving_average(&data, 0).is_empty());
+        assert!(moving_average(&data, 6).is_empty());
+        assert!(moving_average(&[], 1).is_empty());
+    }
+}
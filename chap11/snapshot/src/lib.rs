@@ -0,0 +1,55 @@
+// Snapshot tests: the expected output lives in tests/snapshots/<name>.txt of the crate
+//    under test, which takes this one as a dev-dependency:
+//
+//     [dev-dependencies]
+//     snapshot = { path = "../../chap11/snapshot" }
+//
+//     snapshot::assert_snapshot!("post_draft", &format!("{post}"));
+//
+// When the formatting changes on purpose, regenerate the files and commit them:
+//    UPDATE_SNAPSHOTS=1 cargo test
+//    git diff tests/snapshots    # check that only the intended lines changed
+// Without UPDATE_SNAPSHOTS a mismatch (or a missing file) fails the test, which is what CI runs.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// A macro so that CARGO_MANIFEST_DIR is the calling crate's, not this one's
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr) => {
+        $crate::check_snapshot(
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots"),
+            $name,
+            $actual,
+        )
+    };
+}
+
+// What assert_snapshot! runs, with the snapshot directory spelled out
+pub fn check_snapshot(dir: &Path, name: &str, actual: &str) {
+    let path = dir.join(format!("{name}.txt"));
+
+    if env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1") {
+        fs::create_dir_all(dir).unwrap();
+        // stored with a trailing newline so the files are tidy to diff and edit
+        fs::write(&path, format!("{actual}\n")).unwrap();
+        return;
+    }
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => panic!(
+            "no snapshot {} ({e}); run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        ),
+    };
+    let expected = expected.strip_suffix('\n').unwrap_or(&expected);
+    assert_eq!(
+        expected,
+        actual,
+        "output differs from snapshot {}; rerun with UPDATE_SNAPSHOTS=1 if the change is intended",
+        path.display()
+    );
+}
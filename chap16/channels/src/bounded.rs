@@ -0,0 +1,154 @@
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, SendError, SyncSender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A channel that holds at most `capacity` messages; senders have to wait (backpressure)
+//    instead of letting the queue grow without bound like mpsc::channel().
+pub fn bounded<T>(capacity: usize) -> (BoundedSender<T>, Receiver<T>) {
+    let (tx, rx) = mpsc::sync_channel(capacity);
+    (BoundedSender { inner: tx, capacity }, rx)
+}
+
+#[derive(Debug)]
+pub struct BoundedSender<T> {
+    inner: SyncSender<T>,
+    capacity: usize,
+}
+
+// derive(Clone) would require T: Clone, but only the sender is cloned
+impl<T> Clone for BoundedSender<T> {
+    fn clone(&self) -> Self {
+        BoundedSender {
+            inner: self.inner.clone(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+// Both variants hand the value back so the caller can retry or keep it
+#[derive(Debug, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    Full(T),
+    Disconnected(T),
+}
+
+impl<T> TrySendError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(value) | TrySendError::Disconnected(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "channel is full"),
+            TrySendError::Disconnected(_) => write!(f, "receiver is gone"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for TrySendError<T> {}
+
+const MAX_BACKOFF: Duration = Duration::from_millis(10);
+
+impl<T> BoundedSender<T> {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // Waits as long as it takes for room in the queue
+    pub fn send_blocking(&self, value: T) -> Result<(), SendError<T>> {
+        self.inner.send(value)
+    }
+
+    // Never waits: a full queue gives the value straight back
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(value).map_err(|e| match e {
+            mpsc::TrySendError::Full(value) => TrySendError::Full(value),
+            mpsc::TrySendError::Disconnected(value) => TrySendError::Disconnected(value),
+        })
+    }
+
+    // Keeps retrying try_send, sleeping a little longer each time (up to MAX_BACKOFF),
+    //    until it succeeds or `timeout` has passed, in which case the value comes back as Full.
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), TrySendError<T>> {
+        let deadline = Instant::now() + timeout;
+        let mut backoff = Duration::from_micros(100);
+        let mut value = value;
+        loop {
+            match self.try_send(value) {
+                Err(TrySendError::Full(v)) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(TrySendError::Full(v));
+                    }
+                    thread::sleep(backoff.min(deadline - now));
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    value = v;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_send_fails_at_capacity_and_returns_value() {
+        let (tx, rx) = bounded(2);
+        assert_eq!(tx.capacity(), 2);
+        tx.try_send(String::from("one")).unwrap();
+        tx.try_send(String::from("two")).unwrap();
+
+        let err = tx.try_send(String::from("three")).unwrap_err();
+        assert_eq!(err, TrySendError::Full(String::from("three")));
+        assert_eq!(err.into_inner(), "three");
+
+        assert_eq!(rx.recv().unwrap(), "one");
+        tx.try_send(String::from("three")).unwrap();
+        assert_eq!(rx.iter().take(2).collect::<Vec<_>>(), vec!["two", "three"]);
+    }
+
+    #[test]
+    fn send_timeout_succeeds_once_consumer_drains() {
+        let (tx, rx) = bounded(1);
+        tx.send_blocking(1).unwrap();
+
+        let consumer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            rx.iter().collect::<Vec<i32>>()
+        });
+
+        let start = Instant::now();
+        tx.send_timeout(2, Duration::from_secs(5)).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        drop(tx);
+        assert_eq!(consumer.join().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn send_timeout_gives_up() {
+        let (tx, _rx) = bounded(1);
+        tx.try_send('a').unwrap();
+
+        let start = Instant::now();
+        assert_eq!(tx.send_timeout('b', Duration::from_millis(30)), Err(TrySendError::Full('b')));
+        let waited = start.elapsed();
+        assert!(waited >= Duration::from_millis(30) && waited < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn disconnected_receiver() {
+        let (tx, rx) = bounded(1);
+        drop(rx);
+        assert_eq!(tx.try_send(5), Err(TrySendError::Disconnected(5)));
+        assert_eq!(tx.send_timeout(6, Duration::from_secs(5)), Err(TrySendError::Disconnected(6)));
+        assert!(tx.send_blocking(7).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::mpsc::{self, Receiver, SendError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+// What the forwarding thread reports back for each message: how many subscribers got it,
+//    or the message itself if nobody was left to take it.
+type Delivery<T> = Result<usize, T>;
+
+// mpsc has a single consumer; this hands a clone of every message to each subscriber.
+//
+// The forwarding thread does the cloning and sending, and owns the subscriber list behind a
+//    Mutex so subscribe() can add to it at any time. A subscriber whose Receiver is gone is
+//    only noticed when a send to it fails, and is dropped from the list then.
+pub struct Broadcast<T> {
+    input: Option<Sender<(T, Sender<Delivery<T>>)>>,
+    subscribers: Arc<Mutex<Vec<Sender<T>>>>,
+    forwarder: Option<JoinHandle<()>>,
+}
+
+impl<T: Clone + Send + 'static> Broadcast<T> {
+    pub fn new() -> Broadcast<T> {
+        let (input, messages) = mpsc::channel::<(T, Sender<Delivery<T>>)>();
+        let subscribers: Arc<Mutex<Vec<Sender<T>>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let list = Arc::clone(&subscribers);
+        let forwarder = thread::spawn(move || {
+            for (value, reply) in messages {
+                let mut list = list.lock().unwrap();
+                list.retain(|subscriber| subscriber.send(value.clone()).is_ok());
+                let delivery = if list.is_empty() { Err(value) } else { Ok(list.len()) };
+                // the sender may have given up waiting; the message was forwarded anyway
+                let _ = reply.send(delivery);
+            }
+        });
+
+        Broadcast {
+            input: Some(input),
+            subscribers,
+            forwarder: Some(forwarder),
+        }
+    }
+
+    // Receives every message sent from now on, until the Broadcast is dropped
+    pub fn subscribe(&self) -> Receiver<T> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    // Forwards a clone of value to every live subscriber and returns how many there were.
+    //    Waits for the forwarding thread, so the count already leaves out dropped receivers;
+    //    with none left the value comes back in the error, like mpsc::Sender::send.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        let input = self.input.as_ref().expect("input is only taken in drop");
+        input.send((value, reply_tx)).map_err(|SendError((value, _))| SendError(value))?;
+        match reply_rx.recv() {
+            Ok(Ok(live)) => Ok(live),
+            Ok(Err(value)) => Err(SendError(value)),
+            // the forwarding thread panicked while holding our value
+            Err(_) => panic!("broadcast forwarding thread died"),
+        }
+    }
+
+    // Subscribers as of the last send; one dropped since then is still counted
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().unwrap().len()
+    }
+}
+
+impl<T: Clone + Send + 'static> Default for Broadcast<T> {
+    fn default() -> Self {
+        Broadcast::new()
+    }
+}
+
+impl<T> Drop for Broadcast<T> {
+    fn drop(&mut self) {
+        // closing the input ends the forwarding loop; the subscriber list goes with the last
+        //    Arc, which closes every subscriber's Receiver
+        drop(self.input.take());
+        if let Some(forwarder) = self.forwarder.take() {
+            let _ = forwarder.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn every_subscriber_gets_every_message() {
+        let broadcast = Broadcast::new();
+        let receivers: Vec<Receiver<u32>> = (0..3).map(|_| broadcast.subscribe()).collect();
+
+        for n in 0..10 {
+            assert_eq!(broadcast.send(n), Ok(3));
+        }
+        drop(broadcast);
+
+        for rx in receivers {
+            assert_eq!(rx.iter().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn dropped_subscriber_does_not_affect_the_others() {
+        let broadcast = Broadcast::new();
+        let first = broadcast.subscribe();
+        let second = broadcast.subscribe();
+        let third = broadcast.subscribe();
+
+        broadcast.send("a").unwrap();
+        drop(second);
+        assert_eq!(broadcast.send("b"), Ok(2));
+        assert_eq!(broadcast.subscriber_count(), 2);
+        broadcast.send("c").unwrap();
+        drop(broadcast);
+
+        assert_eq!(first.iter().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+        assert_eq!(third.iter().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn late_subscriber_only_sees_later_messages() {
+        let broadcast = Broadcast::new();
+        let early = broadcast.subscribe();
+        broadcast.send(1).unwrap();
+        let late = broadcast.subscribe();
+        assert_eq!(broadcast.send(2), Ok(2));
+
+        assert_eq!(early.recv_timeout(Duration::from_secs(1)), Ok(1));
+        assert_eq!(early.recv_timeout(Duration::from_secs(1)), Ok(2));
+        assert_eq!(late.recv_timeout(Duration::from_secs(1)), Ok(2));
+    }
+
+    #[test]
+    fn send_without_subscribers_is_an_error() {
+        let broadcast = Broadcast::new();
+        assert_eq!(broadcast.send(String::from("nobody")), Err(SendError(String::from("nobody"))));
+
+        let rx = broadcast.subscribe();
+        broadcast.send(String::from("one")).unwrap();
+        drop(rx);
+        assert_eq!(broadcast.send(String::from("gone")), Err(SendError(String::from("gone"))));
+        assert_eq!(broadcast.subscriber_count(), 0);
+    }
+}
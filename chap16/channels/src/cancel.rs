@@ -0,0 +1,167 @@
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// Asks threads to stop early. Clones share the same flag, so any of them can cancel.
+//
+// The flag alone is enough for a loop that checks it between iterations; the Condvar is
+//    there so a thread sleeping in cancellable_sleep wakes up as soon as cancel() is called
+//    instead of finishing its nap first.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    lock: Mutex<()>,
+    wake: Condvar,
+}
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        // holding the lock while setting the flag means a sleeper either sees the flag
+        //    before it waits, or is already waiting and gets the notification
+        let _guard = self.inner.lock.lock().unwrap();
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.wake.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+// Sleeps for duration, or less if the token is cancelled meanwhile.
+//    Break means cancelled, so inside a spawn_cancellable closure `cancellable_sleep(token, d)?;`
+//    ends the loop.
+pub fn cancellable_sleep(token: &CancelToken, duration: Duration) -> ControlFlow<()> {
+    let deadline = Instant::now() + duration;
+    let mut guard = token.inner.lock.lock().unwrap();
+    loop {
+        if token.is_cancelled() {
+            return ControlFlow::Break(());
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return ControlFlow::Continue(());
+        }
+        // spurious wake-ups just go round the loop again
+        guard = token.inner.wake.wait_timeout(guard, deadline - now).unwrap().0;
+    }
+}
+
+// Runs f over and over on a new thread until it returns Break or the token is cancelled.
+//    The token is checked before every call, so cancelling stops the thread within one
+//    iteration (and within a cancellable_sleep right away).
+pub fn spawn_cancellable<F>(token: CancelToken, mut f: F) -> JoinHandle<()>
+where
+    F: FnMut(&CancelToken) -> ControlFlow<()> + Send + 'static,
+{
+    thread::spawn(move || {
+        while !token.is_cancelled() {
+            if f(&token).is_break() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn cancel_interrupts_a_long_workload() {
+        let token = CancelToken::new();
+        let (tx, rx) = mpsc::channel();
+
+        // five steps of one second each, nominally
+        let mut step = 0;
+        let producer = spawn_cancellable(token.clone(), move |token| {
+            if step == 5 {
+                return ControlFlow::Break(());
+            }
+            tx.send(step).unwrap();
+            step += 1;
+            cancellable_sleep(token, Duration::from_secs(1))
+        });
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(1)), Ok(0));
+        let start = Instant::now();
+        token.cancel();
+        producer.join().unwrap();
+        assert!(start.elapsed() < Duration::from_millis(200), "took {:?}", start.elapsed());
+
+        // nothing after the cancel, and the channel is closed
+        assert_eq!(rx.iter().collect::<Vec<_>>(), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn messages_sent_before_cancel_still_arrive() {
+        let token = CancelToken::new();
+        let (tx, rx) = mpsc::channel();
+        let (sent_tx, sent) = mpsc::channel();
+
+        let mut n = 0;
+        let producer = spawn_cancellable(token.clone(), move |token| {
+            n += 1;
+            tx.send(n).unwrap();
+            if n == 3 {
+                sent_tx.send(()).unwrap();
+                return cancellable_sleep(token, Duration::from_secs(5));
+            }
+            ControlFlow::Continue(())
+        });
+
+        // cancel while all three are still queued, unread
+        sent.recv().unwrap();
+        token.cancel();
+        producer.join().unwrap();
+        assert_eq!(rx.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn producer_can_stop_on_its_own() {
+        let token = CancelToken::new();
+        let mut calls = 0;
+        spawn_cancellable(token.clone(), move |_| {
+            calls += 1;
+            if calls == 3 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        })
+        .join()
+        .unwrap();
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn sleep_runs_to_the_end_without_cancel() {
+        let token = CancelToken::new();
+        let start = Instant::now();
+        assert_eq!(cancellable_sleep(&token, Duration::from_millis(30)), ControlFlow::Continue(()));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn cancelled_token_does_not_sleep_or_run() {
+        let token = CancelToken::new();
+        token.cancel();
+        assert!(token.clone().is_cancelled());
+
+        let start = Instant::now();
+        assert_eq!(cancellable_sleep(&token, Duration::from_secs(5)), ControlFlow::Break(()));
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        let producer = spawn_cancellable(token, |_| panic!("never called"));
+        producer.join().unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use crate::pipeline::panic_message;
+
+// Which worker failed, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerError {
+    pub worker: usize,
+    pub message: String,
+}
+
+impl fmt::Display for WorkerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "worker {} failed: {}", self.worker, self.message)
+    }
+}
+
+impl std::error::Error for WorkerError {}
+
+// What goes down the channel: items as Ok, and at most one Err per worker, as its last message
+pub type WorkResult<T> = Result<T, WorkerError>;
+
+// Runs f on a new thread; f sends its items as Ok(..) on the sender it's given.
+//
+// If f returns Err, or panics, that becomes one last WorkerError on the channel, so the
+//    consumer hears about it instead of just seeing the stream end early. Items f sent
+//    before failing have already been delivered.
+pub fn spawn_fallible<T, F>(worker: usize, tx: Sender<WorkResult<T>>, f: F) -> JoinHandle<()>
+where
+    T: Send + 'static,
+    F: FnOnce(&Sender<WorkResult<T>>) -> Result<(), String> + Send + 'static,
+{
+    thread::spawn(move || {
+        let message = match panic::catch_unwind(AssertUnwindSafe(|| f(&tx))) {
+            Ok(Ok(())) => return,
+            Ok(Err(message)) => message,
+            Err(payload) => format!("panicked: {}", panic_message(payload.as_ref())),
+        };
+        // nobody listening any more is fine: there's no one left to tell
+        let _ = tx.send(Err(WorkerError { worker, message }));
+    })
+}
+
+// Every item until the channel closes, or the first worker error (items from other workers
+//    still in flight are dropped along with the receiver's remaining queue)
+pub fn collect_until_error<T>(rx: &Receiver<WorkResult<T>>) -> Result<Vec<T>, WorkerError> {
+    rx.iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn error_halfway_keeps_the_earlier_items() {
+        let (tx, rx) = mpsc::channel();
+        spawn_fallible(3, tx, |tx| {
+            for n in 0..5 {
+                if n == 3 {
+                    return Err(format!("can't process {n}"));
+                }
+                tx.send(Ok(n)).unwrap();
+            }
+            Ok(())
+        })
+        .join()
+        .unwrap();
+
+        let received: Vec<WorkResult<i32>> = rx.iter().collect();
+        let error = WorkerError { worker: 3, message: String::from("can't process 3") };
+        assert_eq!(received, vec![Ok(0), Ok(1), Ok(2), Err(error.clone())]);
+        assert_eq!(error.to_string(), "worker 3 failed: can't process 3");
+    }
+
+    #[test]
+    fn panic_is_reported_not_swallowed() {
+        let (tx, rx) = mpsc::channel::<WorkResult<u8>>();
+        let handle = spawn_fallible(1, tx, |tx| {
+            tx.send(Ok(7)).unwrap();
+            let empty: Vec<u8> = Vec::new();
+            tx.send(Ok(empty[0])).unwrap();
+            Ok(())
+        });
+        // the panic was caught, so the thread itself finished normally
+        assert!(handle.join().is_ok());
+
+        assert_eq!(rx.recv().unwrap(), Ok(7));
+        let error = rx.recv().unwrap().unwrap_err();
+        assert_eq!(error.worker, 1);
+        assert!(error.message.starts_with("panicked: index out of bounds"), "{}", error.message);
+        assert!(rx.recv().is_err());
+    }
+
+    #[test]
+    fn collect_until_error_stops_at_the_first_failure() {
+        let (tx, rx) = mpsc::channel();
+        let ok = spawn_fallible(0, tx.clone(), |tx| {
+            for n in 0..3 {
+                tx.send(Ok(n)).unwrap();
+            }
+            Ok(())
+        });
+        ok.join().unwrap();
+        spawn_fallible(1, tx, |_| Err(String::from("disk full"))).join().unwrap();
+
+        assert_eq!(
+            collect_until_error(&rx),
+            Err(WorkerError { worker: 1, message: String::from("disk full") })
+        );
+    }
+
+    #[test]
+    fn collect_until_error_without_failures() {
+        let (tx, rx) = mpsc::channel();
+        for worker in 0..3 {
+            spawn_fallible(worker, tx.clone(), move |tx| {
+                tx.send(Ok(worker * 10)).unwrap();
+                Ok(())
+            });
+        }
+        drop(tx);
+        let mut items = collect_until_error(&rx).unwrap();
+        items.sort();
+        assert_eq!(items, vec![0, 10, 20]);
+    }
+}
@@ -0,0 +1,94 @@
+pub mod bounded;
+pub mod broadcast;
+pub mod cancel;
+pub mod fallible;
+pub mod logger;
+pub mod ordered;
+pub mod pipeline;
+pub mod pool;
+pub mod priority;
+pub mod progress;
+pub mod rate;
+pub mod recv;
+pub mod responder;
+pub mod select;
+pub mod worker;
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// A producer gets its own clone of the sender and sends as much as it likes.
+pub type Producer<T> = Box<dyn FnOnce(Sender<T>) + Send>;
+
+// Runs every producer on its own thread and merges what they send into one receiver.
+//
+// The threads are detached on purpose: the caller only needs the messages, and the channel
+//    closes by itself once every producer has returned (dropping its sender), so
+//    `for msg in fan_in(...)` ends when the last producer is done.
+pub fn fan_in<T: Send + 'static>(producers: Vec<Box<dyn FnOnce(Sender<T>) + Send>>) -> Receiver<T> {
+    let (tx, rx) = mpsc::channel();
+    for producer in producers {
+        let tx = tx.clone();
+        thread::spawn(move || producer(tx));
+    }
+    // the original sender would keep the channel open forever
+    drop(tx);
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn counting(from: u32, count: u32) -> Producer<u32> {
+        Box::new(move |tx| {
+            for n in from..from + count {
+                tx.send(n).unwrap();
+            }
+        })
+    }
+
+    #[test]
+    fn producers_of_different_lengths() {
+        let rx = fan_in(vec![counting(0, 3), counting(100, 10)]);
+        let mut received: Vec<u32> = rx.iter().collect();
+        received.sort();
+
+        let mut expected: Vec<u32> = (0..3).chain(100..110).collect();
+        expected.sort();
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn no_producers_means_closed_receiver() {
+        let rx = fan_in::<String>(Vec::new());
+        assert!(rx.recv_timeout(Duration::from_secs(1)).is_err());
+        assert_eq!(rx.iter().count(), 0);
+    }
+
+    #[test]
+    fn no_message_is_lost() {
+        let producers: Vec<Producer<(usize, usize)>> = (0..8)
+            .map(|id| -> Producer<(usize, usize)> {
+                Box::new(move |tx| {
+                    for seq in 0..500 {
+                        tx.send((id, seq)).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        let mut received: Vec<(usize, usize)> = fan_in(producers).iter().collect();
+        assert_eq!(received.len(), 8 * 500);
+
+        // each producer's messages arrive in the order it sent them, interleaved with the others
+        for id in 0..8 {
+            let seqs: Vec<usize> = received.iter().filter(|m| m.0 == id).map(|m| m.1).collect();
+            assert_eq!(seqs, (0..500).collect::<Vec<_>>());
+        }
+        received.sort();
+        received.dedup();
+        assert_eq!(received.len(), 8 * 500);
+    }
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        };
+        // padded, so `{:5}` lines the messages up
+        f.pad(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub level: Level,
+    pub message: String,
+    // when log() was called, not when the writer got round to it
+    pub timestamp: SystemTime,
+    pub thread: String,
+}
+
+// `1712345678.042 INFO  [worker-1] message`
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let since_epoch = self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+        write!(
+            f,
+            "{}.{:03} {:5} [{}] {}",
+            since_epoch.as_secs(),
+            since_epoch.subsec_millis(),
+            self.level,
+            self.thread,
+            self.message
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum LogError {
+    // shutdown() has been called; the record was not written
+    Closed,
+    // the sink failed to write or flush
+    Io(io::Error),
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogError::Closed => write!(f, "the logger is shut down"),
+            LogError::Io(e) => write!(f, "could not write the log: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LogError {}
+
+enum Command {
+    Record(LogRecord),
+    // write everything before this, flush the sink, then answer
+    Flush(Sender<io::Result<()>>),
+    // like Flush, then stop reading: from then on every send fails
+    Shutdown(Sender<io::Result<()>>),
+}
+
+fn flush_sink<W: Write>(sink: &mut W, failed: &mut Option<io::Error>) -> io::Result<()> {
+    match failed.take() {
+        Some(e) => Err(e),
+        None => sink.flush(),
+    }
+}
+
+// Every thread logs through its own clone; the records all go down one channel to a
+//    single writer thread, so lines from different threads never get mixed up mid-line.
+//
+// log() doesn't wait for the write. Use flush() when the output has to be there now, and
+//    shutdown() at the end so nothing still queued is lost.
+#[derive(Clone)]
+pub struct Logger {
+    tx: Sender<Command>,
+    writer: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl Logger {
+    pub fn new<W: Write + Send + 'static>(mut sink: W) -> Logger {
+        let (tx, rx) = mpsc::channel();
+        let writer = thread::spawn(move || {
+            // a failed write is reported at the next flush (or at shutdown), not lost
+            let mut failed: Option<io::Error> = None;
+            for command in rx {
+                match command {
+                    Command::Record(record) => {
+                        if let Err(e) = writeln!(sink, "{record}") {
+                            failed.get_or_insert(e);
+                        }
+                    }
+                    Command::Flush(reply) => {
+                        let _ = reply.send(flush_sink(&mut sink, &mut failed));
+                    }
+                    Command::Shutdown(reply) => {
+                        let _ = reply.send(flush_sink(&mut sink, &mut failed));
+                        // returning drops the receiver, so later log() calls get Closed
+                        return;
+                    }
+                }
+            }
+            // every Logger was dropped without shutdown(): still write out what's left
+            let _ = flush_sink(&mut sink, &mut failed);
+        });
+        Logger {
+            tx,
+            writer: Arc::new(Mutex::new(Some(writer))),
+        }
+    }
+
+    pub fn stdout() -> Logger {
+        Logger::new(io::stdout())
+    }
+
+    pub fn to_file(path: impl AsRef<Path>) -> io::Result<Logger> {
+        Ok(Logger::new(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn log(&self, level: Level, message: impl Into<String>) -> Result<(), LogError> {
+        let current = thread::current();
+        let record = LogRecord {
+            level,
+            message: message.into(),
+            timestamp: SystemTime::now(),
+            thread: match current.name() {
+                Some(name) => name.to_string(),
+                None => format!("{:?}", current.id()),
+            },
+        };
+        self.tx.send(Command::Record(record)).map_err(|_| LogError::Closed)
+    }
+
+    // Blocks until everything logged before it (from any thread) is written and flushed
+    pub fn flush(&self) -> Result<(), LogError> {
+        let (reply_tx, reply) = mpsc::channel();
+        self.tx.send(Command::Flush(reply_tx)).map_err(|_| LogError::Closed)?;
+        // no reply means the writer stopped (shutdown from another clone) before our turn
+        reply.recv().map_err(|_| LogError::Closed)?.map_err(LogError::Io)
+    }
+
+    // Writes what is still queued, then stops the writer thread and waits for it.
+    //    Afterwards log() and flush() return Closed, on every clone; records other threads
+    //    send while the shutdown is under way may be dropped. Only the first call does anything.
+    pub fn shutdown(&self) -> Result<(), LogError> {
+        let Some(writer) = self.writer.lock().unwrap().take() else {
+            return Ok(());
+        };
+        let (reply_tx, reply) = mpsc::channel();
+        self.tx.send(Command::Shutdown(reply_tx)).map_err(|_| LogError::Closed)?;
+        let result = reply.recv().map_err(|_| LogError::Closed);
+        writer.join().expect("log writer thread panicked");
+        result?.map_err(LogError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An in-memory sink the test can still read after handing a clone to the Logger
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuf {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct BrokenSink;
+
+    impl Write for BrokenSink {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn every_thread_message_appears_once() {
+        let sink = SharedBuf::default();
+        let logger = Logger::new(sink.clone());
+
+        let handles: Vec<_> = (0..4)
+            .map(|id| {
+                let logger = logger.clone();
+                thread::Builder::new()
+                    .name(format!("worker-{id}"))
+                    .spawn(move || {
+                        for n in 0..25 {
+                            logger.log(Level::Info, format!("message {n} from {id}")).unwrap();
+                        }
+                    })
+                    .unwrap()
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        logger.shutdown().unwrap();
+
+        let lines = sink.lines();
+        assert_eq!(lines.len(), 100);
+        for id in 0..4 {
+            for n in 0..25 {
+                let expected = format!("INFO  [worker-{id}] message {n} from {id}");
+                assert_eq!(lines.iter().filter(|line| line.ends_with(&expected)).count(), 1, "{expected}");
+            }
+        }
+    }
+
+    #[test]
+    fn flush_waits_for_earlier_records() {
+        let sink = SharedBuf::default();
+        let logger = Logger::new(sink.clone());
+        logger.log(Level::Warn, "first").unwrap();
+        logger.log(Level::Error, "second").unwrap();
+        logger.flush().unwrap();
+        let lines = sink.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("WARN  [logger::tests::flush_waits_for_earlier_records] first"));
+        assert!(lines[1].ends_with("ERROR [logger::tests::flush_waits_for_earlier_records] second"));
+
+        logger.log(Level::Debug, "third").unwrap();
+        logger.flush().unwrap();
+        assert_eq!(sink.lines().len(), 3);
+        logger.shutdown().unwrap();
+    }
+
+    #[test]
+    fn record_format() {
+        let record = LogRecord {
+            level: Level::Info,
+            message: String::from("hello"),
+            timestamp: UNIX_EPOCH + std::time::Duration::from_millis(1_712_345_678_042),
+            thread: String::from("main"),
+        };
+        assert_eq!(record.to_string(), "1712345678.042 INFO  [main] hello");
+    }
+
+    #[test]
+    fn logging_after_shutdown_is_an_error() {
+        let sink = SharedBuf::default();
+        let logger = Logger::new(sink.clone());
+        let other = logger.clone();
+        logger.log(Level::Info, "before").unwrap();
+        logger.shutdown().unwrap();
+
+        assert!(matches!(other.log(Level::Info, "after"), Err(LogError::Closed)));
+        assert!(matches!(other.flush(), Err(LogError::Closed)));
+        // a second shutdown is harmless
+        assert!(other.shutdown().is_ok());
+        assert_eq!(sink.lines().len(), 1);
+    }
+
+    #[test]
+    fn write_errors_come_back_from_flush() {
+        let logger = Logger::new(BrokenSink);
+        logger.log(Level::Info, "lost").unwrap();
+        let err = logger.flush().unwrap_err();
+        assert_eq!(err.to_string(), "could not write the log: disk full");
+        // reported once, then the logger keeps going
+        assert!(logger.flush().is_ok());
+        logger.shutdown().unwrap();
+    }
+}
@@ -1,6 +1,24 @@
 use std::thread;
 use std::sync::mpsc;  // multiple producer, single consumer
-use std::time::Duration;
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+
+use channels::bounded::{self, TrySendError};
+use channels::broadcast::Broadcast;
+use channels::cancel::{cancellable_sleep, spawn_cancellable, CancelToken};
+use channels::fallible::spawn_fallible;
+use channels::logger::{Level, Logger};
+use channels::ordered::ordered_collect;
+use channels::pipeline::Pipeline;
+use channels::pool::ChannelPool;
+use channels::priority::priority_channel;
+use channels::progress::{progress_channel, ProgressAggregator};
+use channels::rate::RateLimitedSender;
+use channels::recv::{recv_batch, recv_with_timeout, TimeoutOrDisconnected};
+use channels::responder::Responder;
+use channels::select::poll_any;
+use channels::worker::Controller;
+use channels::{fan_in, Producer};
 
 fn main() {
 
@@ -21,7 +39,7 @@ fn main() {
         let (tx, rx) = mpsc::channel();
 
         thread::spawn(move || {
-            let vals: Vec<String> = vec!["hi", "from", "the", "thread"]
+            let vals: Vec<String> = ["hi", "from", "the", "thread"]
                 .iter()
                 .map(|s| String::from(*s))
                 .collect();
@@ -38,36 +56,249 @@ fn main() {
     }
 
     {
-        let (tx, rx) = mpsc::channel();
+        // multiple producers: fan_in clones the sender for each of them
+        //    (let tx1 = tx.clone(); thread::spawn(move || { ... tx1.send(val) ... });)
+        let producers: Vec<Producer<String>> = vec![
+            Box::new(|tx| send_slowly(tx, &["hi", "from", "the", "thread"])),
+            Box::new(|tx| send_slowly(tx, &["more", "messages", "for", "you"])),
+        ];
+
+        // for received in fan_in(producers) { ... } would wait silently between messages
+        let rx = fan_in(producers);
+        loop {
+            match recv_with_timeout(&rx, Duration::from_millis(500)) {
+                Ok(received) => println!("Got: {received}"),
+                Err(TimeoutOrDisconnected::TimedOut) => println!("…waiting"),
+                Err(TimeoutOrDisconnected::Disconnected) => break,
+            }
+        }
+    }
+
+    {
+        // bounded channel: a fast producer can only get 3 messages ahead of a slow consumer
+        let (tx, rx) = bounded::bounded(3);
 
-        let tx1 = tx.clone();
         thread::spawn(move || {
-            let vals: Vec<String> = vec!["hi", "from", "the", "thread"]
-                .iter()
-                .map(|s| String::from(*s))
-                .collect();
+            for n in 1..=8 {
+                if let Err(TrySendError::Full(n)) = tx.try_send(n) {
+                    println!("queue full ({} waiting), blocking on {n}", tx.capacity());
+                    tx.send_blocking(n).unwrap();
+                }
+            }
+        });
 
-            for val in vals {
-                tx1.send(val).unwrap();
-                thread::sleep(Duration::from_secs(1));
+        for received in rx {
+            println!("Got: {received}");
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    {
+        // explicit shutdown: the worker finishes what's queued, then stops and is joined
+        let controller = Controller::new(|job: String| println!("Worker got: {job}"));
+        for word in ["stop", "after", "these"] {
+            controller.dispatch(String::from(word)).unwrap();
+        }
+        controller.shutdown().unwrap();
+        println!("Worker shut down");
+    }
+
+    {
+        // a pipeline: every stage is a thread reading from the previous stage's channel
+        let labels = Pipeline::source(1..=5)
+            .stage(|x| x * 2)
+            .stage(|x| format!("#{x}"))
+            .collect()
+            .unwrap();
+        println!("Pipeline produced: {labels:?}");
+    }
+
+    {
+        // request/response: each call carries its own reply channel
+        let lengths = Responder::spawn(|word: String| word.len());
+        println!("\"channels\" has {} letters", lengths.call(String::from("channels")).unwrap());
+    }
+
+    {
+        // results from several producers arrive interleaved; sequence numbers restore the order
+        let producers: Vec<Producer<(u64, String)>> = (0..3)
+            .map(|p| -> Producer<(u64, String)> {
+                Box::new(move |tx| {
+                    for seq in (p..9).step_by(3) {
+                        tx.send((seq, format!("result {seq}"))).unwrap();
+                    }
+                })
+            })
+            .collect();
+        println!("In order: {:?}", ordered_collect(fan_in(producers), 9).unwrap());
+    }
+
+    {
+        // a worker pool out of two channels: one for jobs, one for results
+        let pool = ChannelPool::new(4);
+        let squares = pool.map_parallel((1..=10).collect(), |n: u64| n * n);
+        println!("Squares: {squares:?}");
+    }
+
+    {
+        // two channels, with the high-priority one always read first
+        let (tx, rx) = priority_channel();
+        tx.send_normal("routine report").unwrap();
+        tx.send_high("fire alarm").unwrap();
+        drop(tx);
+        for message in rx.iter() {
+            println!("Got: {message}");
+        }
+    }
+
+    {
+        // one sender, every subscriber gets its own copy of each message
+        let news = Broadcast::new();
+        let readers: Vec<_> = (0..3)
+            .map(|id| {
+                let rx = news.subscribe();
+                thread::spawn(move || {
+                    for headline in rx {
+                        println!("Reader {id} got: {headline}");
+                    }
+                })
+            })
+            .collect();
+        for headline in ["rust 2024 edition", "channels explained"] {
+            let count = news.send(String::from(headline)).unwrap();
+            println!("Sent to {count} readers");
+        }
+        drop(news);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+
+    {
+        // the producer from above, but it can be told to stop between (and during) its sleeps
+        let (tx, rx) = mpsc::channel();
+        let token = CancelToken::new();
+        let mut vals = ["hi", "from", "the", "thread"].into_iter();
+        let producer = spawn_cancellable(token.clone(), move |token| match vals.next() {
+            Some(val) => {
+                tx.send(String::from(val)).unwrap();
+                cancellable_sleep(token, Duration::from_secs(1))
             }
+            None => ControlFlow::Break(()),
         });
 
+        for received in rx.iter().take(2) {
+            println!("Got: {received}");
+        }
+        let start = Instant::now();
+        token.cancel();
+        producer.join().unwrap();
+        println!("Producer stopped {:?} after cancel", start.elapsed());
+    }
+
+    {
+        // workers report how far they are; the main thread keeps one bar per worker
+        let (reporters, rx) = progress_channel(&[40, 60, 30]);
+        for reporter in reporters {
+            thread::spawn(move || {
+                for done in (10..=reporter.total()).step_by(10) {
+                    thread::sleep(Duration::from_millis(20));
+                    if !reporter.report(done) {
+                        break;
+                    }
+                }
+            });
+        }
+        let progress = ProgressAggregator::new(3).run(&rx, |_| {});
+        println!("{}", progress.render());
+    }
+
+    {
+        // waiting on two channels at once; poll_any says which one answered
+        let (fast_tx, fast) = mpsc::channel();
+        let (slow_tx, slow) = mpsc::channel();
         thread::spawn(move || {
-            let vals: Vec<String> = vec!["more", "messages", "for", "you"]
-                .iter()
-                .map(|s| String::from(*s))
-                .collect();
+            for n in 0..3 {
+                fast_tx.send(format!("fast {n}")).unwrap();
+                thread::sleep(Duration::from_millis(10));
+            }
+        });
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(15));
+            slow_tx.send(String::from("slow")).unwrap();
+        });
+        let names = ["fast", "slow"];
+        while let Some((index, message)) = poll_any(&[&fast, &slow], Duration::from_millis(100)) {
+            println!("Got from {}: {message}", names[index]);
+        }
+    }
 
-            for val in vals {
-                tx.send(val).unwrap();
-                thread::sleep(Duration::from_secs(1));
+    {
+        // println! from several threads can interleave; one writer thread keeps lines whole
+        let logger = Logger::stdout();
+        let handles: Vec<_> = (0..3)
+            .map(|id| {
+                let logger = logger.clone();
+                thread::Builder::new()
+                    .name(format!("worker-{id}"))
+                    .spawn(move || {
+                        logger.log(Level::Info, "starting").unwrap();
+                        logger.log(Level::Debug, format!("{id} squared is {}", id * id)).unwrap();
+                    })
+                    .unwrap()
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        logger.shutdown().unwrap();
+    }
+
+    {
+        // one item every 10ms, handed on in batches of up to 5 (or whatever came within 35ms)
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for n in 0..12 {
+                tx.send(n).unwrap();
+                thread::sleep(Duration::from_millis(10));
             }
         });
+        loop {
+            let batch = recv_batch(&rx, 5, Duration::from_millis(35));
+            if batch.is_empty() {
+                break;
+            }
+            println!("Batch: {batch:?}");
+        }
+    }
 
-        for received in rx {
-            println!("Got: {received}");
+    {
+        // a worker that fails says so on the channel, instead of just going quiet
+        let (tx, rx) = mpsc::channel();
+        for (worker, input) in ["1 2 3", "4 x 6"].into_iter().enumerate() {
+            spawn_fallible(worker, tx.clone(), move |tx| {
+                for word in input.split(' ') {
+                    let n: i32 = word.parse().map_err(|e| format!("{word:?}: {e}"))?;
+                    tx.send(Ok(n)).map_err(|e| e.to_string())?;
+                }
+                Ok(())
+            });
+        }
+        drop(tx);
+        for result in rx {
+            match result {
+                Ok(n) => println!("Parsed {n}"),
+                Err(e) => println!("Error: {e}"),
+            }
         }
     }
 
 }
+
+// one word a second, without a sleep after each one
+fn send_slowly(tx: mpsc::Sender<String>, words: &[&str]) {
+    let mut tx = RateLimitedSender::new(tx, 1).with_burst(1);
+    for word in words {
+        tx.send(String::from(*word)).unwrap();
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::mpsc::Receiver;
+
+// Sequence numbers that never arrived before the channel closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceGaps {
+    pub missing: Vec<u64>,
+}
+
+impl fmt::Display for SequenceGaps {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let missing: Vec<String> = self.missing.iter().map(|n| n.to_string()).collect();
+        write!(f, "missing sequence numbers: {}", missing.join(", "))
+    }
+}
+
+impl std::error::Error for SequenceGaps {}
+
+// Puts `(sequence, item)` pairs back in order, starting from 0, yielding each item as soon
+//    as everything before it has been seen. Items that arrive early wait in a BTreeMap;
+//    a sequence number seen twice keeps the first item.
+pub struct OrderedIter<T> {
+    rx: Receiver<(u64, T)>,
+    next: u64,
+    early: BTreeMap<u64, T>,
+}
+
+impl<T> OrderedIter<T> {
+    pub fn new(rx: Receiver<(u64, T)>) -> OrderedIter<T> {
+        OrderedIter {
+            rx,
+            next: 0,
+            early: BTreeMap::new(),
+        }
+    }
+
+    // After the iterator has ended: the numbers that would have been needed to release
+    //    the items still waiting. Empty if the stream ended cleanly.
+    pub fn gaps(&self) -> Vec<u64> {
+        match self.early.keys().next_back() {
+            Some(&last) => self.missing_before(last + 1),
+            None => Vec::new(),
+        }
+    }
+
+    fn missing_before(&self, end: u64) -> Vec<u64> {
+        (self.next..end).filter(|n| !self.early.contains_key(n)).collect()
+    }
+}
+
+impl<T> Iterator for OrderedIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        loop {
+            if let Some(item) = self.early.remove(&self.next) {
+                self.next += 1;
+                return Some(item);
+            }
+            // ends when every sender is gone
+            let (seq, item) = self.rx.recv().ok()?;
+            if seq >= self.next {
+                self.early.entry(seq).or_insert(item);
+            }
+        }
+    }
+}
+
+// Exactly `expected` items (sequence numbers 0..expected) in order. Returns as soon as the
+//    last one is in, without waiting for the channel to close.
+pub fn ordered_collect<T>(rx: Receiver<(u64, T)>, expected: u64) -> Result<Vec<T>, SequenceGaps> {
+    let mut iter = OrderedIter::new(rx);
+    let items: Vec<T> = iter.by_ref().take(expected as usize).collect();
+    if items.len() as u64 == expected {
+        Ok(items)
+    } else {
+        Err(SequenceGaps {
+            missing: iter.missing_before(expected),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    // a fixed shuffle of 0..n, so failures are reproducible
+    fn shuffled(n: u64) -> Vec<u64> {
+        let mut seqs: Vec<u64> = (0..n).collect();
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for i in (1..seqs.len()).rev() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            seqs.swap(i, (state % (i as u64 + 1)) as usize);
+        }
+        seqs
+    }
+
+    fn send_all(seqs: &[u64]) -> Receiver<(u64, String)> {
+        let (tx, rx) = mpsc::channel();
+        for &seq in seqs {
+            tx.send((seq, format!("item {seq}"))).unwrap();
+        }
+        rx
+    }
+
+    #[test]
+    fn shuffled_input_comes_out_in_order() {
+        let seqs = shuffled(200);
+        assert_ne!(seqs, (0..200).collect::<Vec<_>>());
+
+        let items = ordered_collect(send_all(&seqs), 200).unwrap();
+        let expected: Vec<String> = (0..200).map(|n| format!("item {n}")).collect();
+        assert_eq!(items, expected);
+
+        let streamed: Vec<String> = OrderedIter::new(send_all(&seqs)).collect();
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn items_are_released_as_soon_as_possible() {
+        let (tx, rx) = mpsc::channel();
+        let mut iter = OrderedIter::new(rx);
+        tx.send((1, 'b')).unwrap();
+        tx.send((0, 'a')).unwrap();
+        assert_eq!(iter.next(), Some('a'));
+        // 'b' was already waiting, no need to receive anything
+        assert_eq!(iter.next(), Some('b'));
+        tx.send((2, 'c')).unwrap();
+        assert_eq!(iter.next(), Some('c'));
+    }
+
+    #[test]
+    fn ordered_collect_does_not_wait_for_close() {
+        let (tx, rx) = mpsc::channel();
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    for seq in (p..40).step_by(4) {
+                        tx.send((seq, seq * 10)).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        // `tx` is still alive here
+        let items = ordered_collect(rx, 40).unwrap();
+        assert_eq!(items, (0..40).map(|n| n * 10).collect::<Vec<_>>());
+        for producer in producers {
+            producer.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn gaps_are_reported() {
+        let seqs = [5, 0, 3, 1, 7];
+        assert_eq!(
+            ordered_collect(send_all(&seqs), 8),
+            Err(SequenceGaps { missing: vec![2, 4, 6] })
+        );
+        assert_eq!(ordered_collect(send_all(&[]), 2), Err(SequenceGaps { missing: vec![0, 1] }));
+
+        let mut iter = OrderedIter::new(send_all(&seqs));
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.gaps(), vec![2, 4, 6]);
+
+        let err = SequenceGaps { missing: vec![2, 4] };
+        assert_eq!(err.to_string(), "missing sequence numbers: 2, 4");
+    }
+
+    #[test]
+    fn duplicates_are_dropped() {
+        let items: Vec<String> = OrderedIter::new(send_all(&[0, 0, 2, 1, 2])).collect();
+        assert_eq!(items, vec!["item 0", "item 1", "item 2"]);
+    }
+}
@@ -0,0 +1,165 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+// A chain of threads connected by channels:
+//    Pipeline::source(1..=3).stage(|x| x * 2).stage(|x| x.to_string()).collect()
+//
+// Each stage loops over the previous stage's receiver, so when a thread finishes (or panics)
+//    its sender is dropped and the end of the stream travels down the chain.
+//    If a later stage goes away, the earlier ones notice their sends failing and stop too.
+pub struct Pipeline<T> {
+    rx: Receiver<T>,
+    // stage 0 is the source
+    threads: Vec<JoinHandle<()>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PipelineError {
+    pub stage: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pipeline stage {} panicked: {}", self.stage, self.message)
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+impl<T: Send + 'static> Pipeline<T> {
+    pub fn source<I>(items: I) -> Pipeline<T>
+    where
+        I: IntoIterator<Item = T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let source = thread::spawn(move || {
+            for item in items {
+                if tx.send(item).is_err() {
+                    break;
+                }
+            }
+        });
+        Pipeline { rx, threads: vec![source] }
+    }
+
+    pub fn stage<U, F>(self, mut f: F) -> Pipeline<U>
+    where
+        U: Send + 'static,
+        F: FnMut(T) -> U + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let input = self.rx;
+        let mut threads = self.threads;
+        threads.push(thread::spawn(move || {
+            for item in input {
+                if tx.send(f(item)).is_err() {
+                    break;
+                }
+            }
+        }));
+        Pipeline { rx, threads }
+    }
+
+    // The output of the last stage. The threads are left to finish on their own,
+    //    so a panicking stage just looks like an early end of the stream; use collect to find out.
+    pub fn sink(self) -> Receiver<T> {
+        self.rx
+    }
+
+    // Everything the last stage produced, or the first stage that panicked
+    pub fn collect(self) -> Result<Vec<T>, PipelineError> {
+        let items: Vec<T> = self.rx.iter().collect();
+        for (stage, thread) in self.threads.into_iter().enumerate() {
+            if let Err(payload) = thread.join() {
+                return Err(PipelineError {
+                    stage,
+                    message: panic_message(payload.as_ref()),
+                });
+            }
+        }
+        Ok(items)
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn same_output_as_iterator_chain() {
+        let piped = Pipeline::source(1..=100)
+            .stage(|x| x * 2)
+            .stage(|x| x.to_string())
+            .collect()
+            .unwrap();
+        let expected: Vec<String> = (1..=100).map(|x| x * 2).map(|x| x.to_string()).collect();
+        assert_eq!(piped, expected);
+    }
+
+    #[test]
+    fn stages_run_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let first = Arc::clone(&log);
+        let second = Arc::clone(&log);
+
+        let out = Pipeline::source(vec![1, 2])
+            .stage(move |x| {
+                first.lock().unwrap().push(format!("add {x}"));
+                x + 10
+            })
+            .stage(move |x| {
+                second.lock().unwrap().push(format!("square {x}"));
+                x * x
+            })
+            .collect()
+            .unwrap();
+        assert_eq!(out, vec![121, 144]);
+
+        // each item goes through `add` before `square`, whatever the interleaving between items
+        let log = log.lock().unwrap();
+        let position = |entry: &str| log.iter().position(|e| e == entry).unwrap();
+        assert!(position("add 1") < position("square 11"));
+        assert!(position("add 2") < position("square 12"));
+    }
+
+    #[test]
+    fn sink_ends_when_source_is_exhausted() {
+        let rx = Pipeline::source(vec!["a", "b"]).stage(str::to_uppercase).sink();
+        assert_eq!(rx.iter().collect::<Vec<_>>(), vec!["A", "B"]);
+        assert!(Pipeline::source(Vec::<u8>::new()).collect().unwrap().is_empty());
+    }
+
+    #[test]
+    fn panicking_stage_is_an_error_not_a_hang() {
+        let result = Pipeline::source(0..1_000_000)
+            .stage(|x: u32| x + 1)
+            .stage(|x| {
+                assert!(x != 5, "five is not allowed");
+                x
+            })
+            .stage(|x| x * 3)
+            .collect();
+
+        assert_eq!(
+            result,
+            Err(PipelineError {
+                stage: 2,
+                message: String::from("five is not allowed"),
+            })
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::ordered::ordered_collect;
+
+pub type Job<R> = Box<dyn FnOnce() -> R + Send>;
+
+// A job, its id, and where to send `(id, result)`: the pool's results channel for `submit`,
+//    a private one for `map_parallel`
+type Task<R> = (u64, Job<R>, Sender<(u64, R)>);
+
+// A worker pool made only of channels: workers take jobs from one shared receiver
+//    and send the results back on another channel.
+pub struct ChannelPool<R> {
+    jobs: Option<Sender<Task<R>>>,
+    results_tx: Sender<(u64, R)>,
+    results: Receiver<(u64, R)>,
+    workers: Vec<JoinHandle<()>>,
+    next_id: u64,
+}
+
+impl<R: Send + 'static> ChannelPool<R> {
+    // Panics if `n` is zero.
+    pub fn new(n: usize) -> ChannelPool<R> {
+        assert!(n > 0, "a pool needs at least one worker");
+        let (jobs, rx) = mpsc::channel::<Task<R>>();
+        // mpsc has a single consumer, so the workers take turns holding the receiver
+        let rx = Arc::new(Mutex::new(rx));
+
+        let workers = (0..n)
+            .map(|_| {
+                let rx = Arc::clone(&rx);
+                thread::spawn(move || loop {
+                    // the guard is dropped at the end of the statement, before the job runs
+                    let task = rx.lock().unwrap().recv();
+                    let Ok((id, job, reply)) = task else { break };
+                    // a panicking job loses its result, not the worker
+                    if let Ok(result) = panic::catch_unwind(AssertUnwindSafe(job)) {
+                        let _ = reply.send((id, result));
+                    }
+                })
+            })
+            .collect();
+
+        let (results_tx, results) = mpsc::channel();
+        ChannelPool {
+            jobs: Some(jobs),
+            results_tx,
+            results,
+            workers,
+            next_id: 0,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    // Queues `job`; its result shows up on `results()` tagged with the returned id
+    pub fn submit<F>(&mut self, job: F) -> u64
+    where
+        F: FnOnce() -> R + Send + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(id, Box::new(job), self.results_tx.clone());
+        id
+    }
+
+    // `(id, result)` in completion order, not submission order
+    pub fn results(&self) -> &Receiver<(u64, R)> {
+        &self.results
+    }
+
+    // `items.into_iter().map(f).collect()`, with the calls spread over the workers.
+    //    The results are put back in input order by their index.
+    //
+    // Panics if `f` panicked for any item.
+    pub fn map_parallel<T, F>(&self, items: Vec<T>, f: F) -> Vec<R>
+    where
+        T: Send + 'static,
+        F: Fn(T) -> R + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let (tx, rx) = mpsc::channel();
+        let count = items.len() as u64;
+        for (index, item) in items.into_iter().enumerate() {
+            let f = Arc::clone(&f);
+            self.send(index as u64, Box::new(move || f(item)), tx.clone());
+        }
+        // only the jobs hold senders now, so the channel closes when the last one is done
+        drop(tx);
+        ordered_collect(rx, count).unwrap_or_else(|gaps| panic!("map_parallel: a task panicked ({gaps})"))
+    }
+
+    fn send(&self, id: u64, job: Job<R>, reply: Sender<(u64, R)>) {
+        self.jobs
+            .as_ref()
+            .unwrap()
+            .send((id, job, reply))
+            .expect("workers only stop when the pool is dropped");
+    }
+}
+
+// Workers finish the queued jobs, then stop
+impl<R> Drop for ChannelPool<R> {
+    fn drop(&mut self) {
+        drop(self.jobs.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[test]
+    fn map_parallel_matches_sequential() {
+        let pool = ChannelPool::new(4);
+        let items: Vec<u64> = (0..500).collect();
+        let expected: Vec<u64> = items.iter().map(|n| n * n + 1).collect();
+
+        // uneven job lengths so the results finish out of order
+        let parallel = pool.map_parallel(items, |n| {
+            if n % 7 == 0 {
+                thread::sleep(Duration::from_millis(1));
+            }
+            n * n + 1
+        });
+        assert_eq!(parallel, expected);
+        assert!(pool.map_parallel(Vec::new(), |n: u64| n).is_empty());
+    }
+
+    #[test]
+    fn single_worker_degenerates_to_sequential() {
+        let mut pool = ChannelPool::new(1);
+        assert_eq!(pool.size(), 1);
+        assert_eq!(pool.map_parallel(vec!["a", "bb", "ccc"], str::len), vec![1, 2, 3]);
+
+        // with one worker, jobs also complete in submission order
+        let ids: Vec<u64> = (0..5).map(|n| pool.submit(move || n * 10)).collect();
+        let results: Vec<(u64, usize)> = pool.results().iter().take(5).collect();
+        assert_eq!(results, ids.iter().map(|&id| (id, id as usize * 10)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn submitted_results_are_tagged_by_id() {
+        let mut pool = ChannelPool::new(3);
+        let ids: HashSet<u64> = (0..20).map(|n| pool.submit(move || n + 100)).collect();
+        assert_eq!(ids.len(), 20);
+
+        for (id, result) in pool.results().iter().take(20) {
+            assert!(ids.contains(&id));
+            assert_eq!(result, id + 100);
+        }
+    }
+
+    #[test]
+    fn panicking_job_does_not_kill_the_pool() {
+        let pool = ChannelPool::new(2);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.map_parallel(vec![1, 2, 0, 4], |n: u32| 12 / n);
+        }));
+        assert!(result.is_err());
+        assert_eq!(pool.map_parallel(vec![1, 2, 3, 4], |n: u32| 12 / n), vec![12, 6, 4, 3]);
+    }
+}
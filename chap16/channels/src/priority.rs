@@ -0,0 +1,178 @@
+use std::sync::mpsc::{self, Receiver, RecvError, RecvTimeoutError, SendError, Sender, TryRecvError};
+use std::time::Duration;
+
+// mpsc channels have no priorities, so use two of them and always look at the high one first.
+pub fn priority_channel<T>() -> (PrioritySender<T>, PriorityReceiver<T>) {
+    let (high_tx, high) = mpsc::channel();
+    let (normal_tx, normal) = mpsc::channel();
+    (
+        PrioritySender { high: high_tx, normal: normal_tx },
+        PriorityReceiver { high, normal, poll_interval: DEFAULT_POLL_INTERVAL },
+    )
+}
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+pub struct PrioritySender<T> {
+    high: Sender<T>,
+    normal: Sender<T>,
+}
+
+impl<T> Clone for PrioritySender<T> {
+    fn clone(&self) -> Self {
+        PrioritySender {
+            high: self.high.clone(),
+            normal: self.normal.clone(),
+        }
+    }
+}
+
+impl<T> PrioritySender<T> {
+    pub fn send_high(&self, value: T) -> Result<(), SendError<T>> {
+        self.high.send(value)
+    }
+
+    pub fn send_normal(&self, value: T) -> Result<(), SendError<T>> {
+        self.normal.send(value)
+    }
+}
+
+pub struct PriorityReceiver<T> {
+    high: Receiver<T>,
+    normal: Receiver<T>,
+    poll_interval: Duration,
+}
+
+impl<T> PriorityReceiver<T> {
+    // How long recv waits on the high channel before checking the normal one again.
+    //    A normal message that arrives while both are empty can sit for up to this long;
+    //    shorter means lower latency but more wake-ups while idle.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> PriorityReceiver<T> {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    // A queued high-priority message if there is one, otherwise a normal one
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        match self.high.try_recv() {
+            Ok(value) => Ok(value),
+            Err(high) => match self.normal.try_recv() {
+                Ok(value) => Ok(value),
+                // still Empty while either side might send more
+                Err(TryRecvError::Disconnected) if high == TryRecvError::Disconnected => {
+                    Err(TryRecvError::Disconnected)
+                }
+                Err(_) => Err(TryRecvError::Empty),
+            },
+        }
+    }
+
+    // Blocks until a message arrives, high-priority ones first.
+    //    Err only once both channels are closed and empty.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        loop {
+            match self.high.try_recv() {
+                Ok(value) => return Ok(value),
+                // no more high-priority messages can come, so just wait for normal ones
+                Err(TryRecvError::Disconnected) => return self.normal.recv(),
+                Err(TryRecvError::Empty) => {}
+            }
+            match self.normal.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Disconnected) => return self.high.recv(),
+                Err(TryRecvError::Empty) => {}
+            }
+            // both empty: wait on the high channel for a moment, so a high-priority
+            //    message is picked up right away and a normal one within poll_interval
+            match self.high.recv_timeout(self.poll_interval) {
+                Ok(value) => return Ok(value),
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => continue,
+            }
+        }
+    }
+
+    // Until both channels are closed
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Instant;
+
+    #[test]
+    fn queued_high_messages_come_first() {
+        let (tx, rx) = priority_channel();
+        for n in 0..5 {
+            tx.send_normal(format!("normal {n}")).unwrap();
+            if n % 2 == 0 {
+                tx.send_high(format!("high {n}")).unwrap();
+            }
+        }
+        drop(tx);
+
+        let received: Vec<String> = rx.iter().collect();
+        assert_eq!(
+            received,
+            vec!["high 0", "high 2", "high 4", "normal 0", "normal 1", "normal 2", "normal 3", "normal 4"]
+        );
+    }
+
+    #[test]
+    fn high_message_overtakes_waiting_normal_ones() {
+        let (tx, rx) = priority_channel();
+        tx.send_normal(1).unwrap();
+        tx.send_normal(2).unwrap();
+        assert_eq!(rx.recv(), Ok(1));
+        tx.send_high(100).unwrap();
+        assert_eq!(rx.recv(), Ok(100));
+        assert_eq!(rx.recv(), Ok(2));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    }
+
+    #[test]
+    fn recv_waits_for_either_channel() {
+        let (tx, rx) = priority_channel();
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(30));
+            tx.send_normal("late normal").unwrap();
+            thread::sleep(Duration::from_millis(30));
+            tx.send_high("late high").unwrap();
+        });
+        let start = Instant::now();
+        assert_eq!(rx.recv(), Ok("late normal"));
+        assert_eq!(rx.recv(), Ok("late high"));
+        assert!(start.elapsed() >= Duration::from_millis(60));
+        sender.join().unwrap();
+        assert_eq!(rx.recv(), Err(RecvError));
+    }
+
+    #[test]
+    fn disconnected_only_when_both_are_closed() {
+        let (tx, rx) = priority_channel::<u8>();
+        let PrioritySender { high, normal } = tx;
+        drop(high);
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+        normal.send(7).unwrap();
+        assert_eq!(rx.recv(), Ok(7));
+        drop(normal);
+        assert_eq!(rx.recv(), Err(RecvError));
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn custom_poll_interval() {
+        let (tx, rx) = priority_channel();
+        let rx = rx.with_poll_interval(Duration::from_micros(100));
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            tx.send_normal('n').unwrap();
+        });
+        assert_eq!(rx.recv(), Ok('n'));
+        sender.join().unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub worker: usize,
+    pub done: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    Update(Progress),
+    // the worker's reporter was dropped, finished or not
+    Disconnected { worker: usize },
+}
+
+// All workers share one channel, so the receiver can't tell when one of them is gone;
+//    instead each worker holds a reporter that says so when it's dropped (including when
+//    the worker thread panics and unwinds).
+pub struct ProgressReporter {
+    worker: usize,
+    total: usize,
+    tx: Sender<ProgressEvent>,
+}
+
+impl ProgressReporter {
+    pub fn new(tx: Sender<ProgressEvent>, worker: usize, total: usize) -> ProgressReporter {
+        ProgressReporter { worker, total, tx }
+    }
+
+    pub fn worker(&self) -> usize {
+        self.worker
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    // false once the aggregator has stopped listening
+    pub fn report(&self, done: usize) -> bool {
+        let progress = Progress { worker: self.worker, done, total: self.total };
+        self.tx.send(ProgressEvent::Update(progress)).is_ok()
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        let _ = self.tx.send(ProgressEvent::Disconnected { worker: self.worker });
+    }
+}
+
+// One reporter per worker, all feeding the returned receiver
+pub fn progress_channel(totals: &[usize]) -> (Vec<ProgressReporter>, Receiver<ProgressEvent>) {
+    let (tx, rx) = mpsc::channel();
+    let reporters = totals
+        .iter()
+        .enumerate()
+        .map(|(worker, &total)| ProgressReporter::new(tx.clone(), worker, total))
+        .collect();
+    (reporters, rx)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WorkerState {
+    done: usize,
+    total: usize,
+    disconnected: bool,
+}
+
+impl WorkerState {
+    fn finished(&self) -> bool {
+        self.done >= self.total || self.disconnected
+    }
+}
+
+const BAR_WIDTH: usize = 20;
+
+// The consumer's view: the latest progress of every worker, rendered as one bar each.
+#[derive(Debug, Default)]
+pub struct ProgressAggregator {
+    // indexed by worker; None until the worker's first event
+    workers: Vec<Option<WorkerState>>,
+}
+
+impl ProgressAggregator {
+    // Expecting `workers` workers, so is_complete() doesn't report success while
+    //    some of them haven't said anything yet
+    pub fn new(workers: usize) -> ProgressAggregator {
+        ProgressAggregator { workers: vec![None; workers] }
+    }
+
+    fn slot(&mut self, worker: usize) -> &mut Option<WorkerState> {
+        if worker >= self.workers.len() {
+            self.workers.resize(worker + 1, None);
+        }
+        &mut self.workers[worker]
+    }
+
+    pub fn update(&mut self, progress: Progress) {
+        let slot = self.slot(progress.worker);
+        let disconnected = slot.is_some_and(|state| state.disconnected);
+        *slot = Some(WorkerState {
+            done: progress.done.min(progress.total),
+            total: progress.total,
+            disconnected,
+        });
+    }
+
+    pub fn disconnect(&mut self, worker: usize) {
+        let slot = self.slot(worker);
+        match slot {
+            Some(state) => state.disconnected = true,
+            None => *slot = Some(WorkerState { done: 0, total: 0, disconnected: true }),
+        }
+    }
+
+    pub fn handle(&mut self, event: ProgressEvent) {
+        match event {
+            ProgressEvent::Update(progress) => self.update(progress),
+            ProgressEvent::Disconnected { worker } => self.disconnect(worker),
+        }
+    }
+
+    // (done, total) summed over every worker heard from
+    pub fn overall(&self) -> (usize, usize) {
+        self.workers
+            .iter()
+            .flatten()
+            .fold((0, 0), |(done, total), state| (done + state.done, total + state.total))
+    }
+
+    // Every worker is done or gone
+    pub fn is_complete(&self) -> bool {
+        self.workers.iter().all(|state| state.is_some_and(|state| state.finished()))
+    }
+
+    // Workers that went away before finishing their share
+    pub fn abandoned(&self) -> Vec<usize> {
+        self.workers
+            .iter()
+            .enumerate()
+            .filter_map(|(worker, state)| match state {
+                Some(state) if state.disconnected && state.done < state.total => Some(worker),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (worker, state) in self.workers.iter().enumerate() {
+            let label = format!("worker {worker}");
+            match state {
+                Some(state) => {
+                    out += &line(&label, state.done, state.total);
+                    if state.disconnected && state.done < state.total {
+                        out += " (gone)";
+                    }
+                }
+                None => out += &format!("{label:<9} waiting"),
+            }
+            out.push('\n');
+        }
+        let (done, total) = self.overall();
+        out += &line("total", done, total);
+        out
+    }
+
+    // Reads events until every worker is done or gone (or every reporter is dropped),
+    //    calling on_change after each one; returns the aggregate for a final look
+    pub fn run(
+        mut self,
+        rx: &Receiver<ProgressEvent>,
+        mut on_change: impl FnMut(&ProgressAggregator),
+    ) -> ProgressAggregator {
+        while !self.is_complete() {
+            match rx.recv() {
+                Ok(event) => {
+                    self.handle(event);
+                    on_change(&self);
+                }
+                Err(_) => break,
+            }
+        }
+        self
+    }
+}
+
+// `worker 0  [##########----------]  50/100`
+fn line(label: &str, done: usize, total: usize) -> String {
+    // nothing to do counts as all done
+    let filled = (done * BAR_WIDTH).checked_div(total).unwrap_or(BAR_WIDTH);
+    format!(
+        "{label:<9} [{}{}] {done:>3}/{total}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn progress(worker: usize, done: usize, total: usize) -> ProgressEvent {
+        ProgressEvent::Update(Progress { worker, done, total })
+    }
+
+    #[test]
+    fn overall_sums_the_latest_report_of_each_worker() {
+        let mut agg = ProgressAggregator::new(2);
+        for event in [progress(0, 10, 100), progress(1, 5, 50), progress(0, 40, 100), progress(1, 50, 50)] {
+            agg.handle(event);
+        }
+        assert_eq!(agg.overall(), (90, 150));
+        assert!(!agg.is_complete());
+
+        agg.handle(progress(0, 100, 100));
+        assert_eq!(agg.overall(), (150, 150));
+        assert!(agg.is_complete());
+        assert!(agg.abandoned().is_empty());
+    }
+
+    #[test]
+    fn silent_worker_keeps_it_incomplete() {
+        let mut agg = ProgressAggregator::new(3);
+        agg.update(Progress { worker: 0, done: 1, total: 1 });
+        agg.update(Progress { worker: 2, done: 1, total: 1 });
+        assert!(!agg.is_complete());
+        assert!(agg.render().contains("worker 1  waiting"));
+    }
+
+    #[test]
+    fn early_disconnect_counts_as_finished_but_abandoned() {
+        let mut agg = ProgressAggregator::new(2);
+        for event in [
+            progress(0, 30, 100),
+            progress(1, 20, 40),
+            ProgressEvent::Disconnected { worker: 0 },
+            progress(1, 40, 40),
+        ] {
+            agg.handle(event);
+        }
+        assert!(agg.is_complete());
+        assert_eq!(agg.abandoned(), vec![0]);
+        // the work it didn't do still counts against the total
+        assert_eq!(agg.overall(), (70, 140));
+        // a stray late update doesn't bring it back
+        agg.handle(progress(0, 35, 100));
+        assert_eq!(agg.abandoned(), vec![0]);
+    }
+
+    #[test]
+    fn render_one_bar_per_worker() {
+        let mut agg = ProgressAggregator::new(2);
+        agg.handle(progress(0, 50, 100));
+        agg.handle(progress(1, 3, 4));
+        agg.handle(ProgressEvent::Disconnected { worker: 1 });
+        assert_eq!(
+            agg.render(),
+            "worker 0  [##########----------]  50/100\n\
+             worker 1  [###############-----]   3/4 (gone)\n\
+             total     [##########----------]  53/104"
+        );
+    }
+
+    #[test]
+    fn done_is_clamped_and_empty_work_is_full() {
+        let mut agg = ProgressAggregator::default();
+        agg.update(Progress { worker: 0, done: 12, total: 10 });
+        agg.update(Progress { worker: 1, done: 0, total: 0 });
+        assert_eq!(agg.overall(), (10, 10));
+        assert!(agg.is_complete());
+        assert!(agg.render().starts_with("worker 0  [####################]  10/10\n"));
+    }
+
+    #[test]
+    fn run_stops_when_workers_finish_or_drop_their_reporters() {
+        let (reporters, rx) = progress_channel(&[10, 10, 10]);
+        let handles: Vec<_> = reporters
+            .into_iter()
+            .map(|reporter| {
+                thread::spawn(move || {
+                    // worker 1 gives up halfway; dropping its reporter tells the aggregator
+                    let stop = if reporter.worker() == 1 { 5 } else { reporter.total() };
+                    for done in 1..=stop {
+                        reporter.report(done);
+                    }
+                })
+            })
+            .collect();
+
+        let mut updates = 0;
+        let agg = ProgressAggregator::new(3).run(&rx, |_| updates += 1);
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(agg.is_complete());
+        assert_eq!(agg.overall(), (25, 30));
+        assert_eq!(agg.abandoned(), vec![1]);
+        assert!(updates >= 25);
+    }
+}
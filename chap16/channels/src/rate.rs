@@ -0,0 +1,258 @@
+use std::fmt;
+use std::sync::mpsc::{SendError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Where RateLimitedSender gets the time from, so tests can use a fake one instead of sleeping.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RateLimitError<T> {
+    // sending now would go over the rate; try again in `retry_after`
+    RateLimited { value: T, retry_after: Duration },
+    Disconnected(T),
+}
+
+impl<T> RateLimitError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            RateLimitError::RateLimited { value, .. } | RateLimitError::Disconnected(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Display for RateLimitError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RateLimitError::RateLimited { retry_after, .. } => {
+                write!(f, "rate limited, retry after {retry_after:?}")
+            }
+            RateLimitError::Disconnected(_) => write!(f, "receiver is gone"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for RateLimitError<T> {}
+
+// A few messages may go out back to back after a quiet spell
+const DEFAULT_BURST: u32 = 3;
+
+// Spaces messages out to at most max_per_sec, instead of a sleep after every send.
+//
+// A token bucket holding `burst` tokens, refilled at max_per_sec. It's kept as the time the
+//    bucket will be full again (`full_at`) rather than a token count, so every wait is whole
+//    nanoseconds of Duration and tests can compare them exactly.
+pub struct RateLimitedSender<T, C: Clock = SystemClock> {
+    tx: Sender<T>,
+    clock: C,
+    interval: Duration,
+    burst: u32,
+    full_at: Option<Instant>,
+}
+
+impl<T> RateLimitedSender<T> {
+    pub fn new(tx: Sender<T>, max_per_sec: u32) -> RateLimitedSender<T> {
+        RateLimitedSender::with_clock(tx, max_per_sec, SystemClock)
+    }
+}
+
+impl<T, C: Clock> RateLimitedSender<T, C> {
+    pub fn with_clock(tx: Sender<T>, max_per_sec: u32, clock: C) -> RateLimitedSender<T, C> {
+        assert!(max_per_sec > 0, "max_per_sec must be positive");
+        RateLimitedSender {
+            tx,
+            clock,
+            interval: Duration::from_secs(1) / max_per_sec,
+            burst: DEFAULT_BURST,
+            full_at: None,
+        }
+    }
+
+    // How many messages can go out at once when the sender has been idle; 1 means none extra
+    pub fn with_burst(mut self, burst: u32) -> RateLimitedSender<T, C> {
+        assert!(burst > 0, "burst must be at least 1");
+        self.burst = burst;
+        self
+    }
+
+    // How long until a token is available; zero if one is now
+    pub fn wait_time(&self) -> Duration {
+        let now = self.clock.now();
+        match self.full_at {
+            // with one token missing the bucket is full again after one interval, so a token
+            //    is there as long as full_at is less than a whole bucket away
+            Some(full_at) => {
+                let empty_for = self.interval * self.burst;
+                full_at.saturating_duration_since(now).saturating_sub(empty_for - self.interval)
+            }
+            None => Duration::ZERO,
+        }
+    }
+
+    // Sleeps as long as needed to stay within the rate, then sends
+    pub fn send(&mut self, value: T) -> Result<(), SendError<T>> {
+        let wait = self.wait_time();
+        if !wait.is_zero() {
+            self.clock.sleep(wait);
+        }
+        self.take_token();
+        self.tx.send(value)
+    }
+
+    // Like send, but gives the value back instead of sleeping
+    pub fn try_send(&mut self, value: T) -> Result<(), RateLimitError<T>> {
+        let retry_after = self.wait_time();
+        if !retry_after.is_zero() {
+            return Err(RateLimitError::RateLimited { value, retry_after });
+        }
+        self.take_token();
+        self.tx.send(value).map_err(|SendError(value)| RateLimitError::Disconnected(value))
+    }
+
+    fn take_token(&mut self) {
+        let now = self.clock.now();
+        let full_at = self.full_at.map_or(now, |full_at| full_at.max(now));
+        self.full_at = Some(full_at + self.interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::mpsc;
+
+    // Time only moves when the sender sleeps (or a test calls advance); every sleep is recorded
+    #[derive(Clone)]
+    struct FakeClock {
+        now: Rc<RefCell<Instant>>,
+        sleeps: Rc<RefCell<Vec<Duration>>>,
+    }
+
+    impl FakeClock {
+        fn new() -> FakeClock {
+            FakeClock {
+                now: Rc::new(RefCell::new(Instant::now())),
+                sleeps: Rc::new(RefCell::new(Vec::new())),
+            }
+        }
+
+        fn advance(&self, duration: Duration) {
+            *self.now.borrow_mut() += duration;
+        }
+
+        fn sleeps(&self) -> Vec<Duration> {
+            self.sleeps.borrow().clone()
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            *self.now.borrow()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.sleeps.borrow_mut().push(duration);
+            self.advance(duration);
+        }
+    }
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn burst_goes_out_at_once_then_messages_are_spaced() {
+        let clock = FakeClock::new();
+        let (tx, rx) = mpsc::channel();
+        let mut tx = RateLimitedSender::with_clock(tx, 10, clock.clone()).with_burst(3);
+
+        for n in 0..6 {
+            tx.send(n).unwrap();
+        }
+        // three from the full bucket, then one every 100ms
+        assert_eq!(clock.sleeps(), vec![ms(100), ms(100), ms(100)]);
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), (0..6).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn idle_time_refills_the_bucket() {
+        let clock = FakeClock::new();
+        let (tx, _rx) = mpsc::channel();
+        let mut tx = RateLimitedSender::with_clock(tx, 10, clock.clone()).with_burst(2);
+
+        tx.send('a').unwrap();
+        tx.send('b').unwrap();
+        assert_eq!(tx.wait_time(), ms(100));
+
+        clock.advance(ms(30));
+        assert_eq!(tx.wait_time(), ms(70));
+
+        // long enough for both tokens, but never more than the burst
+        clock.advance(ms(1000));
+        tx.send('c').unwrap();
+        tx.send('d').unwrap();
+        assert!(clock.sleeps().is_empty());
+        tx.send('e').unwrap();
+        assert_eq!(clock.sleeps(), vec![ms(100)]);
+    }
+
+    #[test]
+    fn try_send_reports_when_to_retry() {
+        let clock = FakeClock::new();
+        let (tx, rx) = mpsc::channel();
+        let mut tx = RateLimitedSender::with_clock(tx, 4, clock.clone()).with_burst(1);
+
+        assert_eq!(tx.try_send(1), Ok(()));
+        assert_eq!(
+            tx.try_send(2),
+            Err(RateLimitError::RateLimited { value: 2, retry_after: ms(250) })
+        );
+        clock.advance(ms(200));
+        assert_eq!(tx.try_send(2).unwrap_err().to_string(), "rate limited, retry after 50ms");
+        clock.advance(ms(50));
+        assert_eq!(tx.try_send(3), Ok(()));
+
+        // nothing slept, and the rejected messages were never sent
+        assert!(clock.sleeps().is_empty());
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![1, 3]);
+    }
+
+    #[test]
+    fn disconnected_receiver() {
+        let (tx, rx) = mpsc::channel();
+        drop(rx);
+        let mut tx = RateLimitedSender::with_clock(tx, 1, FakeClock::new());
+        assert_eq!(tx.try_send("x"), Err(RateLimitError::Disconnected("x")));
+        assert_eq!(tx.send("y"), Err(SendError("y")));
+    }
+
+    #[test]
+    fn real_clock_keeps_the_rate() {
+        let (tx, rx) = mpsc::channel();
+        let mut tx = RateLimitedSender::new(tx, 100).with_burst(1);
+        let start = Instant::now();
+        for n in 0..5 {
+            tx.send(n).unwrap();
+        }
+        // the first goes out immediately, the other four 10ms apart
+        assert!(start.elapsed() >= ms(40));
+        assert_eq!(rx.try_iter().count(), 5);
+    }
+}
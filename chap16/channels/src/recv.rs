@@ -0,0 +1,170 @@
+use std::fmt;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+// Why nothing was received: all senders are gone (stop), or just not yet (maybe try again)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutOrDisconnected {
+    TimedOut,
+    Disconnected,
+}
+
+pub type RecvError2 = TimeoutOrDisconnected;
+
+impl fmt::Display for TimeoutOrDisconnected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeoutOrDisconnected::TimedOut => write!(f, "nothing received in time"),
+            TimeoutOrDisconnected::Disconnected => write!(f, "all senders are gone"),
+        }
+    }
+}
+
+impl std::error::Error for TimeoutOrDisconnected {}
+
+pub fn recv_with_timeout<T>(rx: &Receiver<T>, timeout: Duration) -> Result<T, RecvError2> {
+    rx.recv_timeout(timeout).map_err(|e| match e {
+        RecvTimeoutError::Timeout => TimeoutOrDisconnected::TimedOut,
+        RecvTimeoutError::Disconnected => TimeoutOrDisconnected::Disconnected,
+    })
+}
+
+// Everything that arrives within `window` from now. Returns early if the channel closes.
+pub fn drain_for<T>(rx: &Receiver<T>, window: Duration) -> Vec<T> {
+    let deadline = Instant::now() + window;
+    let mut received = Vec::new();
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match recv_with_timeout(rx, left) {
+            Ok(value) => received.push(value),
+            Err(_) => return received,
+        }
+    }
+}
+
+// A batch of up to max items. Waits as long as it takes for the first one, then at most
+//    max_wait more (counted from that first item, so a quiet channel doesn't shorten the
+//    batch window) for the rest. Empty only if the channel is closed with nothing left in it.
+pub fn recv_batch<T>(rx: &Receiver<T>, max: usize, max_wait: Duration) -> Vec<T> {
+    if max == 0 {
+        return Vec::new();
+    }
+    let Ok(first) = rx.recv() else {
+        return Vec::new();
+    };
+    let deadline = Instant::now() + max_wait;
+    let mut batch = vec![first];
+    while batch.len() < max {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(left) {
+            Ok(value) => batch.push(value),
+            // full window used, or no more senders: hand over what we have
+            Err(_) => break,
+        }
+    }
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[test]
+    fn times_out_then_receives_from_delayed_sender() {
+        let (tx, rx) = mpsc::channel();
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            tx.send("late").unwrap();
+        });
+
+        assert_eq!(recv_with_timeout(&rx, Duration::from_millis(10)), Err(TimeoutOrDisconnected::TimedOut));
+        assert_eq!(recv_with_timeout(&rx, Duration::from_secs(5)), Ok("late"));
+        sender.join().unwrap();
+        assert_eq!(recv_with_timeout(&rx, Duration::from_secs(5)), Err(TimeoutOrDisconnected::Disconnected));
+    }
+
+    #[test]
+    fn dropped_sender_is_disconnected_right_away() {
+        let (tx, rx) = mpsc::channel::<u8>();
+        drop(tx);
+        let start = Instant::now();
+        assert_eq!(recv_with_timeout(&rx, Duration::from_secs(5)), Err(TimeoutOrDisconnected::Disconnected));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn drain_for_collects_within_window() {
+        let (tx, rx) = mpsc::channel();
+        let sender = thread::spawn(move || {
+            for n in 0..3 {
+                tx.send(n).unwrap();
+            }
+            thread::sleep(Duration::from_millis(300));
+            // too late for the window
+            let _ = tx.send(99);
+        });
+
+        let start = Instant::now();
+        assert_eq!(drain_for(&rx, Duration::from_millis(100)), vec![0, 1, 2]);
+        let waited = start.elapsed();
+        assert!(waited >= Duration::from_millis(100) && waited < Duration::from_millis(300));
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn drain_for_stops_early_on_disconnect() {
+        let (tx, rx) = mpsc::channel();
+        tx.send('x').unwrap();
+        drop(tx);
+        let start = Instant::now();
+        assert_eq!(drain_for(&rx, Duration::from_secs(5)), vec!['x']);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn batch_returns_as_soon_as_it_is_full() {
+        let (tx, rx) = mpsc::channel();
+        for n in 0..5 {
+            tx.send(n).unwrap();
+        }
+        let start = Instant::now();
+        assert_eq!(recv_batch(&rx, 3, Duration::from_secs(5)), vec![0, 1, 2]);
+        assert_eq!(recv_batch(&rx, 2, Duration::from_secs(5)), vec![3, 4]);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn batch_window_starts_at_the_first_item() {
+        let (tx, rx) = mpsc::channel();
+        let sender = thread::spawn(move || {
+            // longer than the window: if it counted from the call, the batch would be empty
+            thread::sleep(Duration::from_millis(150));
+            tx.send(1).unwrap();
+            thread::sleep(Duration::from_millis(20));
+            tx.send(2).unwrap();
+            thread::sleep(Duration::from_millis(300));
+            let _ = tx.send(3);
+        });
+
+        let start = Instant::now();
+        assert_eq!(recv_batch(&rx, 10, Duration::from_millis(100)), vec![1, 2]);
+        let waited = start.elapsed();
+        assert!(waited >= Duration::from_millis(250) && waited < Duration::from_millis(450));
+        assert_eq!(recv_batch(&rx, 10, Duration::from_millis(100)), vec![3]);
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn batch_on_disconnect() {
+        let (tx, rx) = mpsc::channel();
+        tx.send("pending").unwrap();
+        drop(tx);
+        let start = Instant::now();
+        assert_eq!(recv_batch(&rx, 10, Duration::from_secs(5)), vec!["pending"]);
+        assert_eq!(recv_batch(&rx, 10, Duration::from_secs(5)), Vec::<&str>::new());
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(recv_batch(&rx, 0, Duration::from_secs(5)).is_empty());
+    }
+}
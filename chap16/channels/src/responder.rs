@@ -0,0 +1,142 @@
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+// A request travels together with the sender its answer should go to
+pub type Envelope<Req, Resp> = (Req, Sender<Resp>);
+
+// The client side of a request/response channel; clone it to call from several threads.
+pub struct Responder<Req, Resp> {
+    requests: Sender<Envelope<Req, Resp>>,
+}
+
+impl<Req, Resp> Clone for Responder<Req, Resp> {
+    fn clone(&self) -> Self {
+        Responder { requests: self.requests.clone() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallError {
+    // the worker stopped before answering, or was never there
+    WorkerGone,
+    TimedOut,
+}
+
+impl fmt::Display for CallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CallError::WorkerGone => write!(f, "the worker is gone"),
+            CallError::TimedOut => write!(f, "no reply in time"),
+        }
+    }
+}
+
+impl std::error::Error for CallError {}
+
+// A responder and the receiver to hand to `serve`
+pub fn responder<Req, Resp>() -> (Responder<Req, Resp>, Receiver<Envelope<Req, Resp>>) {
+    let (requests, rx) = mpsc::channel();
+    (Responder { requests }, rx)
+}
+
+// The worker side: answers requests one at a time until every Responder is dropped
+pub fn serve<Req, Resp>(rx: Receiver<Envelope<Req, Resp>>, mut handler: impl FnMut(Req) -> Resp) {
+    for (req, reply) in rx {
+        // the caller may have timed out and stopped listening; that's its business
+        let _ = reply.send(handler(req));
+    }
+}
+
+impl<Req: Send + 'static, Resp: Send + 'static> Responder<Req, Resp> {
+    // Starts a worker thread running `serve` with `handler`
+    pub fn spawn<F>(handler: F) -> Responder<Req, Resp>
+    where
+        F: FnMut(Req) -> Resp + Send + 'static,
+    {
+        let (responder, rx) = responder();
+        thread::spawn(move || serve(rx, handler));
+        responder
+    }
+}
+
+impl<Req, Resp> Responder<Req, Resp> {
+    // Sends `req` and blocks until the answer comes back
+    pub fn call(&self, req: Req) -> Result<Resp, CallError> {
+        let reply = self.send(req)?;
+        // if the worker drops the reply sender without answering, recv fails instead of hanging
+        reply.recv().map_err(|_| CallError::WorkerGone)
+    }
+
+    pub fn call_timeout(&self, req: Req, timeout: Duration) -> Result<Resp, CallError> {
+        let reply = self.send(req)?;
+        reply.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => CallError::TimedOut,
+            RecvTimeoutError::Disconnected => CallError::WorkerGone,
+        })
+    }
+
+    // a fresh one-shot channel per call, so answers can't get mixed up between callers
+    fn send(&self, req: Req) -> Result<Receiver<Resp>, CallError> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.requests
+            .send((req, reply_tx))
+            .map_err(|_| CallError::WorkerGone)?;
+        Ok(reply_rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_callers_get_their_own_answers() {
+        let responder = Responder::spawn(|(id, n): (usize, u64)| (id, n * n));
+
+        let clients: Vec<_> = (0..8)
+            .map(|id| {
+                let responder = responder.clone();
+                thread::spawn(move || {
+                    for n in 0..100 {
+                        assert_eq!(responder.call((id, n)), Ok((id, n * n)));
+                    }
+                })
+            })
+            .collect();
+        for client in clients {
+            client.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn dropped_worker_is_an_error() {
+        let (responder, rx) = responder::<u8, u8>();
+        drop(rx);
+        assert_eq!(responder.call(1), Err(CallError::WorkerGone));
+        assert_eq!(responder.call_timeout(1, Duration::from_secs(5)), Err(CallError::WorkerGone));
+    }
+
+    #[test]
+    fn worker_dying_mid_call_does_not_hang() {
+        let (responder, rx) = responder::<u8, u8>();
+        let worker = thread::spawn(move || {
+            // take the request, then go away without answering
+            let (_req, _reply) = rx.recv().unwrap();
+        });
+        assert_eq!(responder.call(1), Err(CallError::WorkerGone));
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn call_timeout_on_slow_worker() {
+        let responder = Responder::spawn(|ms: u64| {
+            thread::sleep(Duration::from_millis(ms));
+            ms
+        });
+        assert_eq!(responder.call_timeout(200, Duration::from_millis(10)), Err(CallError::TimedOut));
+        // the late answer to the first call goes nowhere and doesn't confuse the next one
+        assert_eq!(responder.call_timeout(0, Duration::from_secs(5)), Ok(0));
+    }
+}
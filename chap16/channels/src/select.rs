@@ -0,0 +1,185 @@
+use std::cell::Cell;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// std's mpsc has no select!, so waiting on several receivers means asking each of them in
+//    turn with try_recv, and napping briefly when none has anything.
+//
+// Every sweep starts one receiver further along than the previous one (kept per thread,
+//    so it carries over between calls). Otherwise a busy receiver 0 would be asked first
+//    every time and always win, and the others would never get a turn.
+
+const SWEEP_PAUSE: Duration = Duration::from_millis(1);
+
+thread_local! {
+    static NEXT_START: Cell<usize> = const { Cell::new(0) };
+}
+
+fn next_start(len: usize) -> usize {
+    NEXT_START.with(|next| {
+        let start = next.get() % len;
+        next.set(start + 1);
+        start
+    })
+}
+
+enum Sweep<T> {
+    Got(usize, T),
+    Empty,
+    // every receiver is disconnected and drained, so waiting is pointless
+    AllClosed,
+}
+
+fn sweep<T>(receivers: &[&Receiver<T>]) -> Sweep<T> {
+    if receivers.is_empty() {
+        return Sweep::AllClosed;
+    }
+    let start = next_start(receivers.len());
+    let mut open = false;
+    for offset in 0..receivers.len() {
+        let index = (start + offset) % receivers.len();
+        match receivers[index].try_recv() {
+            Ok(value) => return Sweep::Got(index, value),
+            Err(TryRecvError::Empty) => open = true,
+            Err(TryRecvError::Disconnected) => {}
+        }
+    }
+    if open { Sweep::Empty } else { Sweep::AllClosed }
+}
+
+// The first message from any of the receivers, with the index of the one it came from.
+//    None if nothing arrives within timeout, or as soon as every receiver is closed.
+pub fn poll_any<T>(receivers: &[&Receiver<T>], timeout: Duration) -> Option<(usize, T)> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match sweep(receivers) {
+            Sweep::Got(index, value) => return Some((index, value)),
+            Sweep::AllClosed => return None,
+            Sweep::Empty => {}
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return None;
+        }
+        thread::sleep(left.min(SWEEP_PAUSE));
+    }
+}
+
+// Everything queued right now, without waiting: one message from each receiver per sweep,
+//    so the result interleaves the sources instead of emptying them one after another
+pub fn drain_all<T>(receivers: &[&Receiver<T>]) -> Vec<(usize, T)> {
+    let mut drained = Vec::new();
+    loop {
+        let before = drained.len();
+        let start = if receivers.is_empty() { 0 } else { next_start(receivers.len()) };
+        for offset in 0..receivers.len() {
+            let index = (start + offset) % receivers.len();
+            if let Ok(value) = receivers[index].try_recv() {
+                drained.push((index, value));
+            }
+        }
+        if drained.len() == before {
+            return drained;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn times_out_when_nothing_arrives() {
+        let (_tx_a, a) = mpsc::channel::<i32>();
+        let (_tx_b, b) = mpsc::channel::<i32>();
+        let start = Instant::now();
+        assert_eq!(poll_any(&[&a, &b], Duration::from_millis(30)), None);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn reports_which_receiver_it_came_from() {
+        let (_tx_a, a) = mpsc::channel();
+        let (tx_b, b) = mpsc::channel();
+        let (_tx_c, c) = mpsc::channel();
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            tx_b.send("from b").unwrap();
+        });
+        assert_eq!(poll_any(&[&a, &b, &c], Duration::from_secs(5)), Some((1, "from b")));
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn all_closed_returns_right_away() {
+        let (tx_a, a) = mpsc::channel::<u8>();
+        let (tx_b, b) = mpsc::channel::<u8>();
+        drop((tx_a, tx_b));
+        let start = Instant::now();
+        assert_eq!(poll_any(&[&a, &b], Duration::from_secs(5)), None);
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(poll_any::<u8>(&[], Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn busy_receiver_does_not_starve_the_others() {
+        let (tx_busy, busy) = mpsc::channel();
+        let (tx_quiet, quiet) = mpsc::channel();
+        let (tx_other, other) = mpsc::channel();
+        for n in 0..100 {
+            tx_busy.send(n).unwrap();
+        }
+        tx_quiet.send(1000).unwrap();
+        tx_other.send(2000).unwrap();
+
+        let receivers = [&busy, &quiet, &other];
+        let sources: Vec<usize> = (0..3)
+            .map(|_| poll_any(&receivers, Duration::from_secs(1)).unwrap().0)
+            .collect();
+        let mut sorted = sources.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2], "each receiver got a turn: {sources:?}");
+    }
+
+    #[test]
+    fn start_rotates_between_sweeps() {
+        let channels: Vec<_> = (0..3).map(|_| mpsc::channel()).collect();
+        for (tx, _) in &channels {
+            for n in 0..3 {
+                tx.send(n).unwrap();
+            }
+        }
+        let receivers: Vec<&Receiver<i32>> = channels.iter().map(|(_, rx)| rx).collect();
+
+        // with every receiver busy, consecutive calls take turns
+        let sources: Vec<usize> = (0..6)
+            .map(|_| poll_any(&receivers, Duration::ZERO).unwrap().0)
+            .collect();
+        for pair in sources.windows(2) {
+            assert_eq!(pair[1], (pair[0] + 1) % 3, "{sources:?}");
+        }
+    }
+
+    #[test]
+    fn drain_all_interleaves_without_blocking() {
+        let (tx_a, a) = mpsc::channel();
+        let (tx_b, b) = mpsc::channel();
+        for n in 0..3 {
+            tx_a.send(n).unwrap();
+        }
+        tx_b.send(10).unwrap();
+        drop(tx_b);
+
+        let drained = drain_all(&[&a, &b]);
+        assert_eq!(drained.len(), 4);
+        // b's single message is in the first sweep, not left for the end
+        assert!(drained[..2].contains(&(1, 10)), "{drained:?}");
+        let from_a: Vec<i32> = drained.iter().filter(|m| m.0 == 0).map(|m| m.1).collect();
+        assert_eq!(from_a, vec![0, 1, 2]);
+
+        assert!(drain_all(&[&a, &b]).is_empty());
+        assert!(drain_all::<i32>(&[]).is_empty());
+    }
+}
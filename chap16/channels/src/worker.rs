@@ -0,0 +1,165 @@
+use std::sync::mpsc::{self, Receiver, SendError, Sender};
+use std::thread::{self, JoinHandle};
+
+// What goes down a worker's channel: work, or the request to stop
+#[derive(Debug, PartialEq)]
+pub enum Msg<T> {
+    Job(T),
+    Shutdown,
+}
+
+// Handles jobs in the order they arrive and stops at Shutdown,
+//    or when every sender is gone (nobody could ever send Shutdown).
+pub fn spawn_worker<T, F>(rx: Receiver<Msg<T>>, mut handler: F) -> JoinHandle<()>
+where
+    T: Send + 'static,
+    F: FnMut(T) + Send + 'static,
+{
+    thread::spawn(move || {
+        for msg in rx {
+            match msg {
+                Msg::Job(job) => handler(job),
+                Msg::Shutdown => break,
+            }
+        }
+    })
+}
+
+// Sends jobs to a worker; can be cloned and handed to other threads.
+//    Once the worker has stopped, dispatch returns the job back in the error.
+#[derive(Debug)]
+pub struct Dispatcher<T> {
+    sender: Sender<Msg<T>>,
+}
+
+impl<T> Clone for Dispatcher<T> {
+    fn clone(&self) -> Self {
+        Dispatcher { sender: self.sender.clone() }
+    }
+}
+
+impl<T> Dispatcher<T> {
+    pub fn dispatch(&self, job: T) -> Result<(), SendError<T>> {
+        self.sender.send(Msg::Job(job)).map_err(|SendError(msg)| match msg {
+            Msg::Job(job) => SendError(job),
+            Msg::Shutdown => unreachable!("only jobs are sent here"),
+        })
+    }
+}
+
+// Owns a worker thread.
+//    Dropping a Controller without calling shutdown leaves the worker to finish the queue on its own.
+pub struct Controller<T> {
+    dispatcher: Dispatcher<T>,
+    worker: JoinHandle<()>,
+}
+
+impl<T: Send + 'static> Controller<T> {
+    pub fn new<F>(handler: F) -> Controller<T>
+    where
+        F: FnMut(T) + Send + 'static,
+    {
+        let (sender, rx) = mpsc::channel();
+        Controller {
+            dispatcher: Dispatcher { sender },
+            worker: spawn_worker(rx, handler),
+        }
+    }
+
+    pub fn dispatch(&self, job: T) -> Result<(), SendError<T>> {
+        self.dispatcher.dispatch(job)
+    }
+
+    pub fn dispatcher(&self) -> Dispatcher<T> {
+        self.dispatcher.clone()
+    }
+
+    // Shutdown goes to the back of the queue, so every job dispatched before it still runs.
+    //    Waits for the worker; Err means the handler panicked.
+    pub fn shutdown(self) -> thread::Result<()> {
+        // if the worker already died, there's nobody to tell; join reports why
+        let _ = self.dispatcher.sender.send(Msg::Shutdown);
+        self.worker.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn recording() -> (Arc<Mutex<Vec<u32>>>, impl FnMut(u32) + Send + 'static) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&seen);
+        (seen, move |job| log.lock().unwrap().push(job))
+    }
+
+    #[test]
+    fn jobs_are_processed_in_order() {
+        let (seen, handler) = recording();
+        let controller = Controller::new(handler);
+        for job in 0..100 {
+            controller.dispatch(job).unwrap();
+        }
+        controller.shutdown().unwrap();
+        assert_eq!(*seen.lock().unwrap(), (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shutdown_drains_the_queue() {
+        let (seen, mut record) = recording();
+        // a slow handler, so the jobs are still queued when shutdown is requested
+        let controller = Controller::new(move |job| {
+            thread::sleep(Duration::from_millis(5));
+            record(job);
+        });
+        for job in 0..10 {
+            controller.dispatch(job).unwrap();
+        }
+        controller.shutdown().unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 10);
+    }
+
+    #[test]
+    fn dispatch_after_shutdown_is_an_error() {
+        let (seen, handler) = recording();
+        let controller = Controller::new(handler);
+        let dispatcher = controller.dispatcher();
+        dispatcher.dispatch(1).unwrap();
+        controller.shutdown().unwrap();
+
+        assert_eq!(dispatcher.dispatch(2), Err(SendError(2)));
+        assert_eq!(*seen.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn worker_stops_when_senders_are_gone() {
+        let (tx, rx) = mpsc::channel();
+        let (seen, handler) = recording();
+        let worker = spawn_worker(rx, handler);
+        tx.send(Msg::Job(7)).unwrap();
+        drop(tx);
+        worker.join().unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn messages_after_shutdown_are_ignored() {
+        let (tx, rx) = mpsc::channel();
+        let (seen, handler) = recording();
+        let worker = spawn_worker(rx, handler);
+        tx.send(Msg::Job(1)).unwrap();
+        tx.send(Msg::Shutdown).unwrap();
+        worker.join().unwrap();
+        assert!(tx.send(Msg::Job(2)).is_err());
+        assert_eq!(*seen.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn panicking_handler_shows_up_in_shutdown() {
+        let controller = Controller::new(|job: u32| assert!(job < 3, "job too big"));
+        controller.dispatch(5).unwrap();
+        assert!(controller.shutdown().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+// What changed between two versions of a text, word by word.
+//
+// The words both versions keep, in order, are a longest common subsequence (LCS) of the
+//    two word lists; everything else was removed from the old one or added in the new one.
+//    lcs[i][j] is the LCS length of old[i..] and new[j..], filled in from the end, so the
+//    walk from the front can always see which way keeps more words.
+
+use crate::Post;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffItem<'a> {
+    Unchanged(&'a str),
+    Added(&'a str),
+    Removed(&'a str),
+}
+
+// Words are split on whitespace, so a change in spacing alone isn't a difference.
+//    Where a word was replaced, the Removed comes before the Added.
+pub fn word_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffItem<'a>> {
+    let old: Vec<&str> = old.split_whitespace().collect();
+    let new: Vec<&str> = new.split_whitespace().collect();
+
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffItem::Unchanged(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffItem::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffItem::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|&word| DiffItem::Removed(word)));
+    diff.extend(new[j..].iter().map(|&word| DiffItem::Added(word)));
+    diff
+}
+
+// One word after another, removed ones as `-word` and added ones as `+word`:
+//    "I ate a -salad +sandwich"
+pub fn render_diff(diff: &[DiffItem]) -> String {
+    diff.iter()
+        .map(|item| match item {
+            DiffItem::Unchanged(word) => word.to_string(),
+            DiffItem::Added(word) => format!("+{word}"),
+            DiffItem::Removed(word) => format!("-{word}"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Post {
+    // From revision from_rev to to_rev (either way round); None if either doesn't exist.
+    //    Like Display this is the author's view, so it works before the post is published.
+    pub fn diff_revisions(&self, from_rev: usize, to_rev: usize) -> Option<Vec<DiffItem<'_>>> {
+        Some(word_diff(self.revision(from_rev)?, self.revision(to_rev)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DiffItem::*;
+
+    #[test]
+    fn one_changed_word() {
+        let diff = word_diff("I ate a salad for lunch", "I ate a sandwich for lunch");
+        assert_eq!(diff.iter().filter(|item| matches!(item, Removed(_))).count(), 1);
+        assert_eq!(diff.iter().filter(|item| matches!(item, Added(_))).count(), 1);
+        assert_eq!(
+            diff,
+            vec![
+                Unchanged("I"),
+                Unchanged("ate"),
+                Unchanged("a"),
+                Removed("salad"),
+                Added("sandwich"),
+                Unchanged("for"),
+                Unchanged("lunch"),
+            ]
+        );
+        assert_eq!(render_diff(&diff), "I ate a -salad +sandwich for lunch");
+    }
+
+    #[test]
+    fn additions_removals_and_empty_text() {
+        assert_eq!(render_diff(&word_diff("a b c", "a c d")), "a -b c +d");
+        assert_eq!(render_diff(&word_diff("", "new text")), "+new +text");
+        assert_eq!(render_diff(&word_diff("old text", "")), "-old -text");
+        assert!(word_diff("", "  ").is_empty());
+        // only the spacing changed
+        assert_eq!(word_diff("a  b\nc", "a b c"), vec![Unchanged("a"), Unchanged("b"), Unchanged("c")]);
+        // a repeated word is matched as early as it can be
+        assert_eq!(render_diff(&word_diff("the cat the hat", "the hat")), "the -cat -the hat");
+    }
+
+    #[test]
+    fn diff_between_post_revisions() {
+        let mut post = Post::new();
+        post.add_text("I ate a salad");
+        post.add_text(" for lunch");
+        post.revise("I ate a sandwich for lunch");
+        assert_eq!(post.revision_count(), 4);
+
+        assert_eq!(render_diff(&post.diff_revisions(0, 1).unwrap()), "+I +ate +a +salad");
+        assert_eq!(render_diff(&post.diff_revisions(1, 2).unwrap()), "I ate a salad +for +lunch");
+        assert_eq!(render_diff(&post.diff_revisions(2, 3).unwrap()), "I ate a -salad +sandwich for lunch");
+        assert_eq!(render_diff(&post.diff_revisions(3, 2).unwrap()), "I ate a -sandwich +salad for lunch");
+        assert!(post.diff_revisions(0, 4).is_none());
+        assert!(post.diff_revisions(9, 0).is_none());
+        // not published, but the author can still see what changed
+        assert_eq!(post.content(), "");
+    }
+}
@@ -0,0 +1,84 @@
+// Preview text for index pages.
+
+// The first max_sentences sentences of content, exactly as written (trimmed at both ends).
+//    A sentence ends at `.`, `!` or `?` followed by whitespace or the end of the text, so
+//    "3.5" or "?!" don't end one early, and "Really?!" is one sentence ending at the `!`.
+//    Text with fewer sentences comes back whole.
+pub fn generate_excerpt(content: &str, max_sentences: usize) -> String {
+    let content = content.trim();
+    if max_sentences == 0 {
+        return String::new();
+    }
+    let mut sentences = 0;
+    let mut chars = content.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let at_boundary = chars.peek().is_none_or(|&(_, next)| next.is_whitespace());
+        if matches!(c, '.' | '!' | '?') && at_boundary {
+            sentences += 1;
+            if sentences == max_sentences {
+                return content[..i + c.len_utf8()].to_string();
+            }
+        }
+    }
+    content.to_string()
+}
+
+// The first n characters of s, plus "…" if anything was cut off. Counts chars, not bytes,
+//    so it never splits a multi-byte character. For text with no sentence endings to stop at.
+pub fn truncate_to_chars(s: &str, n: usize) -> String {
+    match s.char_indices().nth(n) {
+        Some((end, _)) => format!("{}…", &s[..end]),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_every_terminator() {
+        let text = "First one. Second one! Third one? Fourth.";
+        assert_eq!(generate_excerpt(text, 1), "First one.");
+        assert_eq!(generate_excerpt(text, 2), "First one. Second one!");
+        assert_eq!(generate_excerpt(text, 3), "First one. Second one! Third one?");
+        assert_eq!(generate_excerpt(text, 4), text);
+        // not enough sentences: all of it
+        assert_eq!(generate_excerpt(text, 10), text);
+        assert_eq!(generate_excerpt(text, 0), "");
+    }
+
+    #[test]
+    fn punctuation_inside_a_sentence_does_not_end_it() {
+        let text = "It costs 3.5 dollars.\nReally?! Yes...  Wow";
+        assert_eq!(generate_excerpt(text, 1), "It costs 3.5 dollars.");
+        assert_eq!(generate_excerpt(text, 2), "It costs 3.5 dollars.\nReally?!");
+        assert_eq!(generate_excerpt(text, 3), "It costs 3.5 dollars.\nReally?! Yes...");
+        // the unterminated tail still counts once everything else is in
+        assert_eq!(generate_excerpt(text, 4), text);
+        assert_eq!(generate_excerpt("  no ending at all  ", 1), "no ending at all");
+        assert_eq!(generate_excerpt("", 3), "");
+    }
+
+    #[test]
+    fn never_more_than_max_sentences() {
+        let text = "A. B! C? D. E. F!";
+        for max in 1..=6 {
+            let excerpt = generate_excerpt(text, max);
+            let count = excerpt.matches(['.', '!', '?']).count();
+            assert_eq!(count, max, "{max}: {excerpt:?}");
+        }
+    }
+
+    #[test]
+    fn truncate_counts_characters_not_bytes() {
+        assert_eq!(truncate_to_chars("hello world", 5), "hello…");
+        assert_eq!(truncate_to_chars("hello", 5), "hello");
+        assert_eq!(truncate_to_chars("hello", 10), "hello");
+        assert_eq!(truncate_to_chars("안녕하세요", 2), "안녕…");
+        assert_eq!(truncate_to_chars("🦀🦀🦀", 1), "🦀…");
+        assert_eq!(truncate_to_chars("héllo", 2), "hé…");
+        assert_eq!(truncate_to_chars("abc", 0), "…");
+        assert_eq!(truncate_to_chars("", 0), "");
+    }
+}
@@ -4,27 +4,137 @@
 // 3. When the post is approved, it gets published.
 // 4. Only published blog posts return content to print, so unapproved posts can’t accidentally be published.
 
+pub mod diff;
+pub mod excerpt;
+pub mod related;
+pub mod scheduler;
+pub mod store;
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Average adult reading speed for non-fiction
+pub const WORDS_PER_MINUTE: u32 = 238;
+
+// Average word length (in characters) where reading_difficulty moves up a level
+const EASY_WORD_LENGTH: f64 = 4.5;
+const HARD_WORD_LENGTH: f64 = 6.0;
+
 pub struct Post {
     state: Option<Box<dyn State>>,
+    title: String,
     content: String,
+    // the texts revise() replaced, oldest first
+    replaced: Vec<String>,
+    // every version of the text, oldest first, as (text, length): the text is replaced[i],
+    //    or content for i == replaced.len(). add_text only appends, so each version in
+    //    between is a prefix of the text it ended up as, and nothing needs copying.
+    //    revisions[0] is the empty text of a new post.
+    revisions: Vec<(usize, usize)>,
+    created_at: SystemTime,
 }
 
 impl Post {
     pub fn new() -> Post {
         Post {
             state: Some(Box::new(Draft {})),
+            title: String::new(),
             content: String::new(),
+            replaced: Vec::new(),
+            revisions: vec![(0, 0)],
+            created_at: SystemTime::now(),
         }
     }
 
+    pub fn with_title(title: &str) -> Post {
+        let mut post = Post::new();
+        post.set_title(title);
+        post
+    }
+
+    // Unlike the text, the title can be changed in any state
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.state.as_ref().unwrap().is_published()
+    }
+
+    pub fn created_at(&self) -> SystemTime {
+        self.created_at
+    }
+
+    // How long ago the post was created; zero if the clock has since been set back
+    pub fn age(&self) -> Duration {
+        SystemTime::now().duration_since(self.created_at).unwrap_or(Duration::ZERO)
+    }
+
+    // Each call is a new revision
     pub fn add_text(&mut self, text: &str) {
         self.content.push_str(text);
+        self.revisions.push((self.replaced.len(), self.content.len()));
+    }
+
+    // Replaces the whole text, as a new revision
+    pub fn revise(&mut self, text: &str) {
+        let old = std::mem::replace(&mut self.content, text.to_string());
+        self.replaced.push(old);
+        self.revisions.push((self.replaced.len(), self.content.len()));
+    }
+
+    pub fn revision(&self, rev: usize) -> Option<&str> {
+        let &(text, len) = self.revisions.get(rev)?;
+        let text = self.replaced.get(text).unwrap_or(&self.content);
+        Some(&text[..len])
+    }
+
+    pub fn revision_count(&self) -> usize {
+        self.revisions.len()
     }
 
     pub fn content(&self) -> &str {
         self.state.as_ref().unwrap().content(self)
     }
 
+    // The first max_sentences sentences of the published text; empty before that
+    pub fn excerpt(&self, max_sentences: usize) -> String {
+        excerpt::generate_excerpt(self.content(), max_sentences)
+    }
+
+    // Minutes to read the published text at WORDS_PER_MINUTE, rounded up.
+    //    Zero for drafts and posts under review, like content().
+    pub fn read_time_estimate(&self) -> Duration {
+        let words = self.content().split_whitespace().count() as u64;
+        let minutes = words.div_ceil(u64::from(WORDS_PER_MINUTE));
+        Duration::from_secs(minutes * 60)
+    }
+
+    // By average word length (letters and digits only, so punctuation doesn't count):
+    //    under EASY_WORD_LENGTH is Easy, under HARD_WORD_LENGTH Medium, the rest Hard
+    pub fn reading_difficulty(&self) -> &'static str {
+        let (words, letters) = self
+            .content()
+            .split_whitespace()
+            .map(|word| word.chars().filter(|c| c.is_alphanumeric()).count())
+            .fold((0, 0), |(words, letters), len| (words + 1, letters + len));
+        if words == 0 {
+            return "Easy";
+        }
+        let average = letters as f64 / words as f64;
+        if average < EASY_WORD_LENGTH {
+            "Easy"
+        } else if average < HARD_WORD_LENGTH {
+            "Medium"
+        } else {
+            "Hard"
+        }
+    }
+
     pub fn request_review(&mut self) {
         if let Some(s) = self.state.take() {
             self.state = Some(s.request_review())
@@ -38,17 +148,79 @@ impl Post {
     }
 }
 
+// `YYYY-MM-DD HH:MM:SS` in UTC, without a date library: whole days since the epoch are
+//    turned into a calendar date (Howard Hinnant's days-to-civil algorithm; it counts in
+//    400-year eras that start on March 1st, so the leap day falls at the end of a year),
+//    and the rest of the seconds into the time of day.
+pub fn format_timestamp(t: SystemTime) -> String {
+    let secs: i64 = match t.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_secs() as i64,
+        // before 1970: round down to the whole second, so -0.5s is 23:59:59 the day before
+        Err(before) => {
+            let before = before.duration();
+            -(before.as_secs() as i64) - i64::from(before.subsec_nanos() > 0)
+        }
+    };
+    let days = secs.div_euclid(86_400);
+    let time_of_day = secs.rem_euclid(86_400);
+
+    let z = days + 719_468; // days since 0000-03-01
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097); // [0, 146096]
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153; // 0 = March
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        time_of_day / 3600,
+        time_of_day % 3600 / 60,
+        time_of_day % 60
+    )
+}
+
+// The author's view: the state and the full text, even before it's published
+impl fmt::Display for Post {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.state.as_ref().unwrap().name();
+        writeln!(f, "Post [{state}]")?;
+        if self.content.is_empty() {
+            write!(f, "(no text yet)")
+        } else {
+            write!(f, "{}", self.content)
+        }
+    }
+}
+
+impl Default for Post {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 trait State {
     fn request_review(self: Box<Self>) -> Box<dyn State>;
     fn approve(self: Box<Self>) -> Box<dyn State>;
     fn content<'a>(&self, _post: &'a Post) -> &'a str {
         ""
     }
+    fn name(&self) -> &'static str;
+    fn is_published(&self) -> bool {
+        false
+    }
 }
 
 struct Draft {}
 
 impl State for Draft {
+    fn name(&self) -> &'static str {
+        "draft"
+    }
+
     fn request_review(self: Box<Self>) -> Box<dyn State> {
         Box::new(PendingReview {})
     }
@@ -61,6 +233,10 @@ impl State for Draft {
 struct PendingReview {}
 
 impl State for PendingReview {
+    fn name(&self) -> &'static str {
+        "pending review"
+    }
+
     fn request_review(self: Box<Self>) -> Box<dyn State> {
         self
     }
@@ -73,6 +249,14 @@ impl State for PendingReview {
 struct Published {}
 
 impl State for Published {
+    fn name(&self) -> &'static str {
+        "published"
+    }
+
+    fn is_published(&self) -> bool {
+        true
+    }
+
     fn request_review(self: Box<Self>) -> Box<dyn State> {
         self
     }
@@ -96,6 +280,8 @@ pub struct RustDraftPost {
 }
 
 impl RustPost {
+    // a RustPost can only be made by going through the draft and review states
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> RustDraftPost {
         RustDraftPost {
             content: String::new(),
@@ -108,8 +294,30 @@ impl RustPost {
 }
 
 impl RustDraftPost {
-    pub fn add_text(&mut self, text: &str) {
+    // editing methods return the draft again, so they can be chained:
+    //    draft.add_text("hello").append_paragraph("world")
+    pub fn add_text(&mut self, text: &str) -> &mut Self {
+        self.content.push_str(text);
+        self
+    }
+
+    pub fn clear_content(&mut self) -> &mut Self {
+        self.content.clear();
+        self
+    }
+
+    // starts a new paragraph (a blank line) unless the draft is still empty
+    pub fn append_paragraph(&mut self, text: &str) -> &mut Self {
+        if !self.content.is_empty() {
+            self.content.push_str("\n\n");
+        }
         self.content.push_str(text);
+        self
+    }
+
+    pub fn prepend(&mut self, text: &str) -> &mut Self {
+        self.content.insert_str(0, text);
+        self
     }
 
     pub fn request_review(self) -> RustPendingReviewPost {
@@ -130,3 +338,143 @@ impl RustPendingReviewPost {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn published(draft: RustDraftPost) -> RustPost {
+        draft.request_review().approve()
+    }
+
+    #[test]
+    fn draft_methods_chain() {
+        let mut draft = RustPost::new();
+        draft.add_text("hello").append_paragraph("world").add_text("!");
+        assert_eq!(published(draft).content(), "hello\n\nworld!");
+    }
+
+    #[test]
+    fn append_paragraph_to_empty_draft_has_no_blank_line() {
+        let mut draft = RustPost::new();
+        draft.append_paragraph("first").append_paragraph("second");
+        assert_eq!(published(draft).content(), "first\n\nsecond");
+    }
+
+    #[test]
+    fn prepend_and_clear() {
+        let mut draft = RustPost::new();
+        draft.add_text("world").prepend("hello ");
+        let mut cleared = RustPost::new();
+        cleared.add_text("hello").clear_content();
+
+        assert_eq!(published(draft).content(), "hello world");
+        assert_eq!(published(cleared).content(), "");
+    }
+
+    #[test]
+    fn post_content_only_after_approval() {
+        let mut post = Post::default();
+        post.add_text("I ate a salad for lunch today");
+        assert_eq!(post.content(), "");
+        post.request_review();
+        assert_eq!(post.content(), "");
+        post.approve();
+        assert_eq!(post.content(), "I ate a salad for lunch today");
+    }
+
+    #[test]
+    fn format_epoch_and_known_times() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01 00:00:00");
+        let at = |secs: u64| UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(format_timestamp(at(1_712_345_678)), "2024-04-05 19:34:38");
+        // a leap day, one second before midnight
+        assert_eq!(format_timestamp(at(951_868_799)), "2000-02-29 23:59:59");
+        // sub-second parts are dropped, not rounded
+        assert_eq!(format_timestamp(at(59) + Duration::from_millis(999)), "1970-01-01 00:00:59");
+    }
+
+    #[test]
+    fn format_before_the_epoch() {
+        assert_eq!(format_timestamp(UNIX_EPOCH - Duration::from_secs(1)), "1969-12-31 23:59:59");
+        assert_eq!(format_timestamp(UNIX_EPOCH - Duration::from_millis(500)), "1969-12-31 23:59:59");
+    }
+
+    #[test]
+    fn age_is_never_negative() {
+        let post = Post::new();
+        let first = post.age();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(post.age() >= first);
+        assert!(post.age() >= Duration::from_millis(5));
+        assert!(post.created_at() <= SystemTime::now());
+    }
+
+    fn published_post(text: &str) -> Post {
+        let mut post = Post::new();
+        post.add_text(text);
+        post.request_review();
+        post.approve();
+        post
+    }
+
+    #[test]
+    fn excerpt_only_after_approval() {
+        let mut post = Post::new();
+        post.add_text("I ate a salad. It was good! The end.");
+        assert_eq!(post.excerpt(2), "");
+        post.request_review();
+        assert_eq!(post.excerpt(2), "");
+        post.approve();
+        assert_eq!(post.excerpt(2), "I ate a salad. It was good!");
+    }
+
+    #[test]
+    fn read_time_rounds_up_to_whole_minutes() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(published_post(&"word ".repeat(238)).read_time_estimate(), minute);
+        assert_eq!(published_post(&"word ".repeat(239)).read_time_estimate(), 2 * minute);
+        assert_eq!(published_post("just a few words").read_time_estimate(), minute);
+        assert_eq!(published_post("  \n ").read_time_estimate(), Duration::ZERO);
+    }
+
+    #[test]
+    fn unpublished_posts_take_no_time() {
+        let mut post = Post::new();
+        post.add_text(&"word ".repeat(500));
+        assert_eq!(post.read_time_estimate(), Duration::ZERO);
+        post.request_review();
+        assert_eq!(post.read_time_estimate(), Duration::ZERO);
+        assert_eq!(post.reading_difficulty(), "Easy");
+    }
+
+    #[test]
+    fn difficulty_by_average_word_length() {
+        assert_eq!(published_post("").reading_difficulty(), "Easy");
+        // average 3.0: the comma and the !! don't count
+        assert_eq!(published_post("I ate a salad, yummy!!").reading_difficulty(), "Easy");
+        // exactly 4.5 is no longer easy
+        assert_eq!(published_post("abcd abcde").reading_difficulty(), "Medium");
+        assert_eq!(published_post("abcde abcdef").reading_difficulty(), "Medium");
+        // exactly 6.0 is hard
+        assert_eq!(published_post("abcdef abcdef").reading_difficulty(), "Hard");
+        assert_eq!(published_post("incomprehensibilities notwithstanding").reading_difficulty(), "Hard");
+    }
+
+    #[test]
+    fn every_revision_is_kept_without_copying_the_text() {
+        let mut post = Post::new();
+        for word in ["one", " two", " three"] {
+            post.add_text(word);
+        }
+        post.revise("uno");
+        post.add_text(" dos");
+        let texts: Vec<_> = (0..post.revision_count()).map(|rev| post.revision(rev).unwrap()).collect();
+        assert_eq!(texts, ["", "one", "one two", "one two three", "uno", "uno dos"]);
+        assert_eq!(post.revision(6), None);
+
+        // one stored text per revise(), not one per add_text()
+        assert_eq!(post.replaced, ["one two three"]);
+        assert_eq!(post.content, "uno dos");
+    }
+}
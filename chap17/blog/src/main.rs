@@ -1,5 +1,6 @@
-use blog::Post;
+use blog::{format_timestamp, Post};
 use blog::RustPost;
+use blog::store::PostStore;
 
 fn main() {
 
@@ -14,15 +15,33 @@ fn main() {
     
         post.approve();
         assert_eq!("I ate a salad for lunch today", post.content());
+        println!("published a post created at {} UTC", format_timestamp(post.created_at()));
     }
 
     {
         let mut post = RustPost::new();
-        post.add_text("I ate a salad for lunch today");
+        post.add_text("I ate a salad")
+            .add_text(" for lunch today")
+            .append_paragraph("It was good.");
 
         let post = post.request_review();
         let post = post.approve();
 
-        assert_eq!("I ate a salad for lunch today", post.content());
+        assert_eq!("I ate a salad for lunch today\n\nIt was good.", post.content());
+    }
+
+    {
+        let mut store = PostStore::new();
+        let mut lunch = Post::with_title("Lunch");
+        lunch.add_text("I ate a salad for lunch today");
+        lunch.request_review();
+        lunch.approve();
+        let id = store.add(lunch);
+        store.add(Post::with_title("Dinner"));
+
+        assert_eq!(store.get(id).unwrap().title(), "Lunch");
+        assert_eq!(store.published_posts().len(), 1);
+        assert_eq!(store.search("SALAD").len(), 1);
+        assert_eq!(store.paginate(2, 1)[0].title(), "Dinner");
     }
 }
@@ -0,0 +1,118 @@
+// "More like this": posts are related when their published text shares keywords.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::ptr;
+
+use crate::store::PostStore;
+use crate::Post;
+
+// Only words longer than this count, which keeps out most of "the", "and", "with"...
+const MIN_KEYWORD_LEN: usize = 3;
+
+// The distinct words of text, lowercased and without punctuation ("Rust's" -> "rusts")
+fn keywords(text: &str) -> HashSet<String> {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| word.chars().count() > MIN_KEYWORD_LEN)
+        .collect()
+}
+
+// How many keywords the two posts' published text has in common
+pub fn keyword_overlap(a: &Post, b: &Post) -> usize {
+    keywords(a.content()).intersection(&keywords(b.content())).count()
+}
+
+impl Post {
+    // Up to max published posts from store, most shared keywords first (ties keep the
+    //    store's order). Posts with nothing in common aren't related at all, so they're
+    //    left out, and so is this post itself if it's in the store.
+    pub fn related<'a>(&self, store: &'a PostStore, max: usize) -> Vec<&'a Post> {
+        let mut scored: Vec<(usize, &Post)> = store
+            .published_posts()
+            .into_iter()
+            .filter(|&other| !ptr::eq(self, other))
+            .map(|other| (keyword_overlap(self, other), other))
+            .filter(|&(score, _)| score > 0)
+            .collect();
+        scored.sort_by_key(|&(score, _)| Reverse(score));
+        scored.into_iter().take(max).map(|(_, post)| post).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn published(title: &str, text: &str) -> Post {
+        let mut post = Post::with_title(title);
+        post.add_text(text);
+        post.request_review();
+        post.approve();
+        post
+    }
+
+    fn titles(posts: &[&Post]) -> Vec<String> {
+        posts.iter().map(|post| post.title().to_string()).collect()
+    }
+
+    fn store() -> PostStore {
+        let mut store = PostStore::new();
+        store.add(published("ownership", "Rust ownership rules: borrowing, lifetimes and moves."));
+        store.add(published("lunch", "I ate a salad for lunch today."));
+        store.add(published("lifetimes", "Lifetimes tie borrowing to scopes; the ownership rules apply."));
+        store.add(published("borrowing", "Borrowing without ownership, in Rust."));
+        let mut draft = Post::with_title("draft");
+        draft.add_text("Ownership, borrowing, lifetimes and moves.");
+        store.add(draft);
+        store
+    }
+
+    #[test]
+    fn overlap_counts_distinct_long_words() {
+        let a = published("a", "The cat, the CAT and the hat!");
+        let b = published("b", "Cat? No: a hat-stand for the cats.");
+        // every word of a is too short to count; b only has "hatstand" and "cats"
+        assert_eq!(keyword_overlap(&a, &b), 0);
+
+        let a = published("a", "Rust's borrow checker checks borrows.");
+        let b = published("b", "rusts BORROW checker; borrow again");
+        assert_eq!(keyword_overlap(&a, &b), 3);
+        assert_eq!(keyword_overlap(&a, &a), 5);
+        // drafts have no published text to compare
+        assert_eq!(keyword_overlap(&a, &Post::new()), 0);
+    }
+
+    #[test]
+    fn related_posts_by_score() {
+        let store = store();
+        let ownership = store.get(1).unwrap();
+        // lifetimes shares "ownership", "rules", "borrowing" and "lifetimes";
+        //    borrowing shares "borrowing", "ownership" and "rust"
+        assert_eq!(keyword_overlap(ownership, store.get(3).unwrap()), 4);
+        assert_eq!(keyword_overlap(ownership, store.get(4).unwrap()), 3);
+        assert_eq!(titles(&ownership.related(&store, 5)), vec!["lifetimes", "borrowing"]);
+        assert_eq!(titles(&ownership.related(&store, 1)), vec!["lifetimes"]);
+        assert!(ownership.related(&store, 0).is_empty());
+
+        // lunch has nothing in common with anything
+        assert!(store.get(2).unwrap().related(&store, 5).is_empty());
+    }
+
+    #[test]
+    fn never_related_to_itself() {
+        let store = store();
+        for id in 1..=5 {
+            let post = store.get(id).unwrap();
+            assert!(post.related(&store, 10).iter().all(|&other| !ptr::eq(post, other)));
+        }
+        // an equal post outside the store is another post, so its twin in the store is found
+        let twin = published("ownership", "Rust ownership rules: borrowing, lifetimes and moves.");
+        assert_eq!(titles(&twin.related(&store, 1)), vec!["ownership"]);
+    }
+}
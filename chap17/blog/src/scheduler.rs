@@ -0,0 +1,125 @@
+// Posts that publish themselves once their time comes.
+//
+// Scheduling a post counts as approving it in advance: when tick() finds it due, a draft
+//    goes through review and approval in one go, just as if an editor had done both.
+
+use std::time::SystemTime;
+
+use crate::Post;
+
+pub struct ScheduledPost {
+    pub post: Post,
+    pub publish_at: SystemTime,
+}
+
+impl ScheduledPost {
+    fn is_overdue(&self, now: SystemTime) -> bool {
+        self.publish_at <= now && !self.post.is_published()
+    }
+}
+
+#[derive(Default)]
+pub struct PostScheduler {
+    scheduled: Vec<ScheduledPost>,
+}
+
+impl PostScheduler {
+    pub fn new() -> PostScheduler {
+        PostScheduler::default()
+    }
+
+    pub fn schedule(&mut self, post: Post, at: SystemTime) {
+        self.scheduled.push(ScheduledPost { post, publish_at: at });
+    }
+
+    // Publishes every post whose time has passed. Published posts stay in the list.
+    pub fn tick(&mut self) {
+        self.tick_at(SystemTime::now());
+    }
+
+    // tick() with a chosen "now", so tests don't depend on the clock; returns how many
+    //    posts it published
+    pub fn tick_at(&mut self, now: SystemTime) -> usize {
+        let mut published = 0;
+        for scheduled in self.scheduled.iter_mut().filter(|s| s.is_overdue(now)) {
+            scheduled.post.request_review();
+            scheduled.post.approve();
+            published += 1;
+        }
+        published
+    }
+
+    // Posts that are due but still waiting for the next tick
+    pub fn overdue_count(&self) -> usize {
+        self.overdue_count_at(SystemTime::now())
+    }
+
+    pub fn overdue_count_at(&self, now: SystemTime) -> usize {
+        self.scheduled.iter().filter(|s| s.is_overdue(now)).count()
+    }
+
+    pub fn scheduled(&self) -> &[ScheduledPost] {
+        &self.scheduled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn post(text: &str) -> Post {
+        let mut post = Post::new();
+        post.add_text(text);
+        post
+    }
+
+    #[test]
+    fn past_posts_publish_on_tick() {
+        let mut scheduler = PostScheduler::new();
+        scheduler.schedule(post("from a draft"), UNIX_EPOCH);
+        let mut pending = post("already in review");
+        pending.request_review();
+        scheduler.schedule(pending, UNIX_EPOCH);
+
+        assert_eq!(scheduler.overdue_count(), 2);
+        assert!(!scheduler.scheduled()[0].post.is_published());
+
+        scheduler.tick();
+        assert_eq!(scheduler.overdue_count(), 0);
+        assert!(scheduler.scheduled().iter().all(|s| s.post.is_published()));
+        assert_eq!(scheduler.scheduled()[0].post.content(), "from a draft");
+    }
+
+    #[test]
+    fn future_posts_wait() {
+        let mut scheduler = PostScheduler::new();
+        let tomorrow = SystemTime::now() + Duration::from_secs(24 * 60 * 60);
+        scheduler.schedule(post("tomorrow"), tomorrow);
+
+        scheduler.tick();
+        assert_eq!(scheduler.overdue_count(), 0);
+        assert!(!scheduler.scheduled()[0].post.is_published());
+        assert_eq!(scheduler.scheduled()[0].post.content(), "");
+
+        // once the time comes it publishes, exactly once
+        let later = tomorrow + Duration::from_secs(1);
+        assert_eq!(scheduler.overdue_count_at(later), 1);
+        assert_eq!(scheduler.tick_at(later), 1);
+        assert_eq!(scheduler.tick_at(later), 0);
+        assert_eq!(scheduler.scheduled()[0].post.content(), "tomorrow");
+    }
+
+    #[test]
+    fn only_due_posts_are_published() {
+        let mut scheduler = PostScheduler::new();
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        for secs in [500, 1_000, 1_500] {
+            scheduler.schedule(post("x"), UNIX_EPOCH + Duration::from_secs(secs));
+        }
+        // a publish time equal to now counts as due
+        assert_eq!(scheduler.tick_at(now), 2);
+        let published: Vec<bool> = scheduler.scheduled().iter().map(|s| s.post.is_published()).collect();
+        assert_eq!(published, vec![true, true, false]);
+    }
+}
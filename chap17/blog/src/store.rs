@@ -0,0 +1,146 @@
+// Keeps Posts and hands out ids for them.
+//
+// Ids start at 1 and are never reused. Nothing is ever removed, so post n sits at
+//    index n - 1 and lookups don't need a map.
+
+use crate::Post;
+
+pub struct PostStore {
+    posts: Vec<Post>,
+    next_id: usize,
+}
+
+impl PostStore {
+    pub fn new() -> PostStore {
+        PostStore { posts: Vec::new(), next_id: 1 }
+    }
+
+    pub fn add(&mut self, post: Post) -> usize {
+        let id = self.next_id;
+        self.posts.push(post);
+        self.next_id += 1;
+        id
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Post> {
+        self.posts.get(id.checked_sub(1)?)
+    }
+
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut Post> {
+        self.posts.get_mut(id.checked_sub(1)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.posts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.posts.is_empty()
+    }
+
+    pub fn published_posts(&self) -> Vec<&Post> {
+        self.posts.iter().filter(|post| post.is_published()).collect()
+    }
+
+    // Posts whose title or published text contains query, ignoring case. Drafts are
+    //    only found by title: their text isn't public yet (see Post::content).
+    pub fn search(&self, query: &str) -> Vec<&Post> {
+        let query = query.to_lowercase();
+        self.posts
+            .iter()
+            .filter(|post| {
+                post.title().to_lowercase().contains(&query)
+                    || post.content().to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    // Page 1 is the first per_page posts, in the order they were added. A page past
+    //    the end (or page 0, or per_page 0) is empty.
+    pub fn paginate(&self, page: usize, per_page: usize) -> Vec<&Post> {
+        let Some(skip) = page.checked_sub(1).and_then(|p| p.checked_mul(per_page)) else {
+            return Vec::new();
+        };
+        self.posts.iter().skip(skip).take(per_page).collect()
+    }
+}
+
+// Not derived: next_id has to start at 1, not 0
+impl Default for PostStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(posts: &[&Post]) -> Vec<String> {
+        posts.iter().map(|post| post.title().to_string()).collect()
+    }
+
+    // Ten posts: 1-4 published, 5-7 pending review, 8-10 drafts
+    fn store() -> PostStore {
+        let mut store = PostStore::new();
+        for n in 1..=10 {
+            let mut post = Post::with_title(&format!("Post {n}"));
+            post.add_text(if n % 2 == 0 { "All about Rust" } else { "Lunch: a salad" });
+            if n <= 7 {
+                post.request_review();
+            }
+            if n <= 4 {
+                post.approve();
+            }
+            assert_eq!(store.add(post), n);
+        }
+        store
+    }
+
+    #[test]
+    fn ids_find_their_posts() {
+        let store = store();
+        assert_eq!(store.len(), 10);
+        assert_eq!(store.get(1).unwrap().title(), "Post 1");
+        assert_eq!(store.get(10).unwrap().title(), "Post 10");
+        assert!(store.get(0).is_none());
+        assert!(store.get(11).is_none());
+
+        let mut default = PostStore::default();
+        assert!(default.is_empty());
+        assert_eq!(default.add(Post::new()), 1);
+    }
+
+    #[test]
+    fn only_published_posts() {
+        let store = store();
+        assert_eq!(titles(&store.published_posts()), vec!["Post 1", "Post 2", "Post 3", "Post 4"]);
+    }
+
+    #[test]
+    fn search_titles_and_published_text() {
+        let store = store();
+        // posts 2 and 4 are published; 6, 8 and 10 say the same but aren't public yet
+        assert_eq!(titles(&store.search("rust")), vec!["Post 2", "Post 4"]);
+        assert_eq!(titles(&store.search("SALAD")), vec!["Post 1", "Post 3"]);
+        // titles match in any state: "post 1" and "post 10"
+        assert_eq!(titles(&store.search("post 1")), vec!["Post 1", "Post 10"]);
+        assert_eq!(store.search("").len(), 10);
+        assert!(store.search("python").is_empty());
+    }
+
+    #[test]
+    fn pagination_boundaries() {
+        let store = store();
+        assert_eq!(titles(&store.paginate(1, 3)), vec!["Post 1", "Post 2", "Post 3"]);
+        assert_eq!(titles(&store.paginate(2, 3)), vec!["Post 4", "Post 5", "Post 6"]);
+        // the last page is short, and the one after is empty
+        assert_eq!(titles(&store.paginate(4, 3)), vec!["Post 10"]);
+        assert!(store.paginate(5, 3).is_empty());
+        assert_eq!(store.paginate(1, 10).len(), 10);
+        assert_eq!(store.paginate(1, 100).len(), 10);
+        assert!(store.paginate(0, 3).is_empty());
+        assert!(store.paginate(1, 0).is_empty());
+        assert!(store.paginate(usize::MAX, usize::MAX).is_empty());
+    }
+}
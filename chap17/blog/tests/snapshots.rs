@@ -0,0 +1,33 @@
+// The expected output lives in tests/snapshots; chap11/snapshot explains how to update it
+
+use blog::Post;
+use snapshot::assert_snapshot;
+
+#[test]
+fn empty_draft_display() {
+    assert_snapshot!("post_empty_draft", &format!("{}", Post::new()));
+}
+
+#[test]
+fn draft_display() {
+    let mut post = Post::new();
+    post.add_text("I ate a salad for lunch today");
+    assert_snapshot!("post_draft", &format!("{post}"));
+}
+
+#[test]
+fn pending_review_display() {
+    let mut post = Post::new();
+    post.add_text("I ate a salad for lunch today");
+    post.request_review();
+    assert_snapshot!("post_pending_review", &format!("{post}"));
+}
+
+#[test]
+fn published_display() {
+    let mut post = Post::new();
+    post.add_text("I ate a salad for lunch today\n\nIt was good.");
+    post.request_review();
+    post.approve();
+    assert_snapshot!("post_published", &format!("{post}"));
+}
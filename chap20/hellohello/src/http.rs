@@ -0,0 +1,838 @@
+use std::{
+    collections::HashMap,
+    io::{self, prelude::*, BufReader},
+    net::TcpStream,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::middleware;
+
+/// Requests served on one connection before the server closes it anyway.
+pub const MAX_REQUESTS_PER_CONNECTION: usize = 100;
+
+/// How long an idle keep-alive connection may wait for its next request.
+pub const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Size of the pieces a chunked response body is sent in.
+pub const CHUNK_SIZE: usize = 8 * 1024;
+
+/// Largest request body the server reads; a bigger `Content-Length` gets 413 Payload Too Large.
+pub const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Longest request, header, chunk-size or trailer line read, in bytes, line end included.
+pub const MAX_LINE: usize = 8 * 1024;
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Unique (per process) id used to correlate the log lines of one request.
+pub fn generate_request_id() -> String {
+    let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    format!("req-{id:016x}")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub request_id: String,
+    pub method: String,
+    pub path: String,
+    pub version: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Create an HTTP/1.1 request without headers or body.
+    pub fn new(method: &str, path: &str) -> HttpRequest {
+        HttpRequest {
+            request_id: generate_request_id(),
+            method: method.to_string(),
+            path: path.to_string(),
+            version: String::from("HTTP/1.1"),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> HttpRequest {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Value of the first header called `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Read the next request from `reader`.
+    ///
+    /// Returns `Ok(None)` when the peer closed the connection before sending anything,
+    /// and an `InvalidData` error when the request is malformed.
+    pub fn read_from(reader: &mut impl BufRead) -> io::Result<Option<HttpRequest>> {
+        let mut line = String::new();
+        // clients may send empty lines between pipelined requests
+        loop {
+            line.clear();
+            if read_line(reader, &mut line)? == 0 {
+                return Ok(None);
+            }
+            if !line.trim_end().is_empty() {
+                break;
+            }
+        }
+
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(path), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid_data(format!("malformed request line: {}", line.trim_end())));
+        };
+        let mut request = HttpRequest {
+            request_id: generate_request_id(),
+            method: method.to_string(),
+            path: path.to_string(),
+            version: version.to_string(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+
+        loop {
+            line.clear();
+            if read_line(reader, &mut line)? == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed inside headers"));
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let Some((name, value)) = header.split_once(':') else {
+                return Err(invalid_data(format!("malformed header: {header}")));
+            };
+            request.headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+
+        if let Some(length) = request.header("Content-Length") {
+            let length: usize = length
+                .parse()
+                .map_err(|_| invalid_data(format!("invalid Content-Length: {length}")))?;
+            // checked before allocating, so a client can't make the server reserve any size it likes
+            if length > MAX_BODY_SIZE {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, BodyTooLarge { length }));
+            }
+            request.body = vec![0; length];
+            reader.read_exact(&mut request.body)?;
+        }
+
+        Ok(Some(request))
+    }
+
+    /// Whether the client wants the connection kept open after this request.
+    ///
+    /// HTTP/1.1 connections are persistent unless the client says `Connection: close`;
+    /// HTTP/1.0 ones only when it asks for `Connection: keep-alive`.
+    pub fn wants_keep_alive(&self) -> bool {
+        let connection = self.header("Connection").map(str::to_ascii_lowercase);
+        match connection.as_deref() {
+            Some("close") => false,
+            Some("keep-alive") => true,
+            _ => self.version == "HTTP/1.1",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> HttpResponse {
+        self.set_header(name, value);
+        self
+    }
+
+    /// Value of the first header called `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Set a header, replacing any existing header with the same name.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+    }
+
+    /// Write the status line, headers and body.
+    ///
+    /// A `Content-Length` header is added unless one is already set, the body
+    /// is sent with `Transfer-Encoding`, or the status never has a body (204, 304).
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason_phrase(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        let has_body = !matches!(self.status, 204 | 304);
+        if has_body && self.header("Content-Length").is_none() && self.header("Transfer-Encoding").is_none() {
+            head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        }
+        head.push_str("\r\n");
+
+        writer.write_all(head.as_bytes())?;
+        writer.write_all(&self.body)
+    }
+}
+
+pub fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+/// Stream `body` with `Transfer-Encoding: chunked` instead of buffering it to learn its length.
+///
+/// Every chunk is written as `<hex length>\r\n<data>\r\n`, and a zero-length chunk ends the body.
+pub fn write_chunked_response(stream: &mut impl Write, status: u16, mut body: impl Read) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: text/html; charset=utf-8\r\nTransfer-Encoding: chunked\r\n\r\n",
+        reason_phrase(status)
+    );
+    stream.write_all(head.as_bytes())?;
+
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        let len = read_full(&mut body, &mut buf)?;
+        if len == 0 {
+            break;
+        }
+        stream.write_all(format!("{len:x}\r\n").as_bytes())?;
+        stream.write_all(&buf[..len])?;
+        stream.write_all(b"\r\n")?;
+    }
+    stream.write_all(b"0\r\n\r\n")?;
+    stream.flush()
+}
+
+/// `BufRead::read_line`, but a line over [`MAX_LINE`] bytes is an `InvalidData` error
+/// instead of growing `line` for as long as the peer keeps sending.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<usize> {
+    let n = reader.by_ref().take(MAX_LINE as u64).read_line(line)?;
+    if n == MAX_LINE && !line.ends_with('\n') {
+        return Err(invalid_data(format!("line is over the limit of {MAX_LINE} bytes")));
+    }
+    Ok(n)
+}
+
+/// Read as much as fits in `buf`, stopping early only at the end of `reader`.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Decode a chunked body (the part after the response headers) back into its bytes.
+///
+/// Chunk extensions (`;name=value`) and trailer headers are accepted and ignored. A body
+/// that would grow past [`MAX_BODY_SIZE`] is an `InvalidData` error, raised before the
+/// chunk is allocated or read.
+pub fn decode_chunked(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if read_line(reader, &mut line)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "missing last chunk"));
+        }
+        let size = line.trim_end().split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| invalid_data(format!("invalid chunk size: {}", line.trim_end())))?;
+        if size == 0 {
+            break;
+        }
+
+        let start = body.len();
+        let end = start.checked_add(size).filter(|&end| end <= MAX_BODY_SIZE).ok_or_else(|| {
+            invalid_data(format!("chunked body is over the limit of {MAX_BODY_SIZE} bytes"))
+        })?;
+        body.resize(end, 0);
+        reader.read_exact(&mut body[start..])?;
+
+        let mut crlf = [0; 2];
+        reader.read_exact(&mut crlf)?;
+        if &crlf != b"\r\n" {
+            return Err(invalid_data(String::from("chunk data not followed by CRLF")));
+        }
+    }
+
+    // trailers, up to the empty line closing the message
+    loop {
+        line.clear();
+        if read_line(reader, &mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    Ok(body)
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// The error inside the `InvalidData` io::Error from [`HttpRequest::read_from`] when the
+/// `Content-Length` is over [`MAX_BODY_SIZE`]; the body has not been read.
+#[derive(Debug)]
+pub struct BodyTooLarge {
+    pub length: usize,
+}
+
+impl std::fmt::Display for BodyTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "request body of {} bytes is over the limit of {MAX_BODY_SIZE}", self.length)
+    }
+}
+
+impl std::error::Error for BodyTooLarge {}
+
+fn is_body_too_large(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|inner| inner.is::<BodyTooLarge>())
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+pub type Handler = Box<dyn Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static>;
+
+/// Maps a method and path to the handler producing the response.
+pub struct Router {
+    routes: HashMap<(String, String), Handler>,
+    fallback: Handler,
+}
+
+impl Router {
+    /// Create a Router answering every request with `fallback` until routes are added.
+    pub fn new<F>(fallback: F) -> Router
+    where
+        F: Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static,
+    {
+        Router {
+            routes: HashMap::new(),
+            fallback: Box::new(fallback),
+        }
+    }
+
+    pub fn route<F>(&mut self, method: &str, path: &str, handler: F) -> &mut Router
+    where
+        F: Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static,
+    {
+        self.routes
+            .insert((method.to_string(), path.to_string()), Box::new(handler));
+        self
+    }
+
+    /// Run the handler for the request's method and path (ignoring any query string).
+    pub fn handle(&self, req: &HttpRequest) -> HttpResponse {
+        let path = req.path.split('?').next().unwrap_or_default();
+        match self.routes.get(&(req.method.clone(), path.to_string())) {
+            Some(handler) => handler(req),
+            None => (self.fallback)(req),
+        }
+    }
+}
+
+/// One logical site, picked by the request's `Host` header.
+pub struct VirtualHost {
+    pub host_header: String,
+    pub router: Arc<Router>,
+}
+
+/// Lets a single listener serve several sites, each with its own Router.
+pub struct VirtualHostRouter {
+    hosts: Vec<VirtualHost>,
+    fallback: Arc<Router>,
+}
+
+impl VirtualHostRouter {
+    /// Create a VirtualHostRouter sending requests for unknown hosts to `fallback`.
+    pub fn new(fallback: Arc<Router>) -> VirtualHostRouter {
+        VirtualHostRouter {
+            hosts: Vec::new(),
+            fallback,
+        }
+    }
+
+    pub fn add_host(&mut self, host: &str, router: Arc<Router>) -> &mut VirtualHostRouter {
+        self.hosts.push(VirtualHost {
+            host_header: host.to_string(),
+            router,
+        });
+        self
+    }
+
+    /// Handle the request with the router of the matching host.
+    ///
+    /// Host names compare case-insensitively and any `:port` suffix is ignored.
+    pub fn dispatch(&self, req: &HttpRequest) -> HttpResponse {
+        self.router_for(req).handle(req)
+    }
+
+    fn router_for(&self, req: &HttpRequest) -> &Router {
+        let Some(host) = req.header("Host") else {
+            return &self.fallback;
+        };
+        let host = strip_port(host);
+        self.hosts
+            .iter()
+            .find(|vhost| strip_port(&vhost.host_header).eq_ignore_ascii_case(host))
+            .map(|vhost| &*vhost.router)
+            .unwrap_or(&self.fallback)
+    }
+}
+
+fn strip_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        // leave bracketed IPv6 literals without a port alone
+        Some((name, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    }
+}
+
+/// Serve requests from one client until it closes the connection, asks for
+/// `Connection: close`, stays idle for [`KEEP_ALIVE_TIMEOUT`] or reaches
+/// [`MAX_REQUESTS_PER_CONNECTION`].
+pub fn handle_keepalive_connection(stream: TcpStream, router: Arc<Router>) {
+    if let Err(e) = stream.set_read_timeout(Some(KEEP_ALIVE_TIMEOUT)) {
+        eprintln!("Could not set read timeout: {e}");
+    }
+
+    let reader = BufReader::new(&stream);
+    if let Err(e) = serve_connection(reader, &stream, &router) {
+        eprintln!("Connection error: {e}");
+    }
+}
+
+/// Request/response loop behind [`handle_keepalive_connection`], over any reader and writer.
+///
+/// Returns the number of requests handled.
+pub fn serve_connection<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    router: &Router,
+) -> io::Result<usize> {
+    let mut handled = 0;
+
+    while handled < MAX_REQUESTS_PER_CONNECTION {
+        let request = match HttpRequest::read_from(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => break,
+            // an idle keep-alive connection ran into the read timeout
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => break,
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                // the rest of the stream can't be trusted either way, so the connection is closed
+                let status = if is_body_too_large(&e) { 413 } else { 400 };
+                HttpResponse::new(status, e.to_string())
+                    .with_header("Connection", "close")
+                    .write_to(&mut writer)?;
+                writer.flush()?;
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        handled += 1;
+
+        let keep_alive = request.wants_keep_alive() && handled < MAX_REQUESTS_PER_CONNECTION;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("handle_connection", path = %request.path, method = %request.method).entered();
+
+        let mut response = middleware::logging_middleware(&request, &|req| router.handle(req));
+        response.set_header("Connection", if keep_alive { "keep-alive" } else { "close" });
+        #[cfg(feature = "tracing")]
+        tracing::info!(status = response.status, bytes = response.body.len(), "response");
+        response.write_to(&mut writer)?;
+        writer.flush()?;
+
+        if !keep_alive {
+            break;
+        }
+    }
+
+    Ok(handled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Cursor, net::TcpListener, thread};
+
+    fn test_router() -> Router {
+        let mut router = Router::new(|_| HttpResponse::new(404, "not found"));
+        router
+            .route("GET", "/", |_| HttpResponse::new(200, "hello"))
+            .route("POST", "/echo", |req| HttpResponse::new(200, req.body.clone()));
+        router
+    }
+
+    fn get(path: &str, extra_headers: &str) -> String {
+        format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n{extra_headers}\r\n")
+    }
+
+    #[test]
+    fn parses_request_line_headers_and_body() {
+        let raw = "POST /echo HTTP/1.1\r\nHost: localhost\r\ncontent-length: 5\r\n\r\nhello";
+        let request = HttpRequest::read_from(&mut Cursor::new(raw)).unwrap().unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/echo");
+        assert_eq!(request.version, "HTTP/1.1");
+        assert_eq!(request.header("HOST"), Some("localhost"));
+        assert_eq!(request.body, b"hello");
+    }
+
+    #[test]
+    fn empty_input_is_no_request() {
+        assert_eq!(HttpRequest::read_from(&mut Cursor::new("")).unwrap(), None);
+    }
+
+    #[test]
+    fn malformed_request_line_is_invalid_data() {
+        let err = HttpRequest::read_from(&mut Cursor::new("GARBAGE\r\n\r\n")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn keep_alive_defaults_depend_on_version() {
+        assert!(HttpRequest::new("GET", "/").wants_keep_alive());
+        assert!(!HttpRequest::new("GET", "/").with_header("Connection", "Close").wants_keep_alive());
+
+        let mut old = HttpRequest::new("GET", "/");
+        old.version = String::from("HTTP/1.0");
+        assert!(!old.wants_keep_alive());
+        assert!(old.with_header("Connection", "keep-alive").wants_keep_alive());
+    }
+
+    #[test]
+    fn response_is_serialized_with_content_length() {
+        let mut out = Vec::new();
+        HttpResponse::new(200, "hi")
+            .with_header("Content-Type", "text/plain")
+            .write_to(&mut out)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\nhi"
+        );
+    }
+
+    #[test]
+    fn chunked_body_round_trip() {
+        let body: Vec<u8> = (0..20_000).map(|i| (i % 251) as u8).collect();
+        let mut out = Vec::new();
+        write_chunked_response(&mut out, 200, Cursor::new(body.clone())).unwrap();
+
+        let mut reader = Cursor::new(out);
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") {
+            reader.read_line(&mut head).unwrap();
+        }
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("Transfer-Encoding: chunked\r\n"));
+        assert!(!head.contains("Content-Length"));
+
+        let tail = reader.get_ref()[reader.position() as usize..].to_vec();
+        // 20000 bytes = two full 8 KiB chunks plus the rest
+        assert!(tail.starts_with(b"2000\r\n"));
+        assert!(tail.windows(7).any(|w| w == b"\r\ne20\r\n"));
+        assert!(tail.ends_with(b"\r\n0\r\n\r\n"));
+
+        let decoded = decode_chunked(&mut Cursor::new(tail)).unwrap();
+        assert_eq!(decoded, body);
+    }
+
+    #[test]
+    fn chunked_encoding_of_known_body() {
+        let mut out = Vec::new();
+        write_chunked_response(&mut out, 404, Cursor::new("Wiki")).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.ends_with("\r\n\r\n4\r\nWiki\r\n0\r\n\r\n"));
+
+        let mut empty = Vec::new();
+        write_chunked_response(&mut empty, 200, io::empty()).unwrap();
+        assert!(String::from_utf8(empty).unwrap().ends_with("\r\n\r\n0\r\n\r\n"));
+    }
+
+    #[test]
+    fn decodes_extensions_and_trailers() {
+        let raw = "4;name=value\r\nWiki\r\n5\r\npedia\r\nE\r\n in\r\n\r\nchunks.\r\n0\r\nExpires: never\r\n\r\n";
+        let decoded = decode_chunked(&mut Cursor::new(raw)).unwrap();
+        assert_eq!(decoded, b"Wikipedia in\r\n\r\nchunks.");
+    }
+
+    #[test]
+    fn rejects_bad_chunks() {
+        let bad_size = decode_chunked(&mut Cursor::new("zz\r\n")).unwrap_err();
+        assert_eq!(bad_size.kind(), io::ErrorKind::InvalidData);
+
+        let truncated = decode_chunked(&mut Cursor::new("4\r\nWi")).unwrap_err();
+        assert_eq!(truncated.kind(), io::ErrorKind::UnexpectedEof);
+
+        let missing_end = decode_chunked(&mut Cursor::new("4\r\nWiki\r\n")).unwrap_err();
+        assert_eq!(missing_end.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn overlong_lines_are_refused() {
+        let long_header = format!("GET / HTTP/1.1\r\nX-Filler: {}\r\n\r\n", "a".repeat(MAX_LINE));
+        let err = HttpRequest::read_from(&mut Cursor::new(long_header)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // no newline ever comes: still refused after MAX_LINE bytes
+        let endless = io::repeat(b'a').take(100 * MAX_LINE as u64);
+        let err = HttpRequest::read_from(&mut BufReader::new(endless)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let long_size = format!("1;{}\r\na\r\n0\r\n\r\n", "x".repeat(MAX_LINE));
+        assert_eq!(decode_chunked(&mut Cursor::new(long_size)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+        let long_trailer = format!("0\r\nX-Filler: {}\r\n\r\n", "a".repeat(MAX_LINE));
+        assert_eq!(decode_chunked(&mut Cursor::new(long_trailer)).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        // a line of exactly MAX_LINE bytes, CRLF included, is fine
+        let header = format!("X-Filler: {}\r\n", "a".repeat(MAX_LINE - 12));
+        assert_eq!(header.len(), MAX_LINE);
+        let request = HttpRequest::read_from(&mut Cursor::new(format!("GET / HTTP/1.1\r\n{header}\r\n")))
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.header("X-Filler").unwrap().len(), MAX_LINE - 12);
+    }
+
+    #[test]
+    fn rejects_oversized_chunks_before_allocating() {
+        // would overflow start + size, or ask for 16 EiB
+        let huge = decode_chunked(&mut Cursor::new("ffffffffffffffff\r\n")).unwrap_err();
+        assert_eq!(huge.kind(), io::ErrorKind::InvalidData);
+
+        // small chunks adding up to more than the limit
+        let half = format!("{:x}\r\n{}\r\n", MAX_BODY_SIZE / 2, "a".repeat(MAX_BODY_SIZE / 2));
+        let raw = format!("{half}{half}1\r\na\r\n0\r\n\r\n");
+        let over = decode_chunked(&mut Cursor::new(raw)).unwrap_err();
+        assert_eq!(over.kind(), io::ErrorKind::InvalidData);
+
+        // right at the limit is fine
+        let raw = format!("{half}{half}0\r\n\r\n");
+        assert_eq!(decode_chunked(&mut Cursor::new(raw)).unwrap().len(), MAX_BODY_SIZE);
+    }
+
+    #[test]
+    fn router_dispatches_by_method_and_path() {
+        let router = test_router();
+        assert_eq!(router.handle(&HttpRequest::new("GET", "/")).body, b"hello");
+        assert_eq!(router.handle(&HttpRequest::new("GET", "/?name=x")).status, 200);
+        assert_eq!(router.handle(&HttpRequest::new("POST", "/")).status, 404);
+        assert_eq!(router.handle(&HttpRequest::new("GET", "/missing")).status, 404);
+    }
+
+    fn site(name: &'static str) -> Arc<Router> {
+        let mut router = Router::new(move |_| HttpResponse::new(404, format!("{name}: not found")));
+        router.route("GET", "/", move |_| HttpResponse::new(200, format!("welcome to {name}")));
+        if name == "blog" {
+            router.route("GET", "/posts", |_| HttpResponse::new(200, "all posts"));
+        }
+        Arc::new(router)
+    }
+
+    fn get_with_host(path: &str, host: &str) -> HttpRequest {
+        HttpRequest::new("GET", path).with_header("Host", host)
+    }
+
+    #[test]
+    fn virtual_hosts_dispatch_by_host_header() {
+        let mut vhosts = VirtualHostRouter::new(site("default"));
+        vhosts
+            .add_host("shop.example.com", site("shop"))
+            .add_host("blog.example.com", site("blog"));
+
+        let body = |req: HttpRequest| String::from_utf8(vhosts.dispatch(&req).body).unwrap();
+        assert_eq!(body(get_with_host("/", "shop.example.com")), "welcome to shop");
+        assert_eq!(body(get_with_host("/", "blog.example.com")), "welcome to blog");
+        assert_eq!(body(get_with_host("/posts", "blog.example.com")), "all posts");
+        // only the blog has /posts
+        assert_eq!(body(get_with_host("/posts", "shop.example.com")), "shop: not found");
+    }
+
+    #[test]
+    fn host_match_ignores_case_and_port() {
+        let mut vhosts = VirtualHostRouter::new(site("default"));
+        vhosts.add_host("blog.example.com", site("blog"));
+
+        let response = vhosts.dispatch(&get_with_host("/", "Blog.Example.COM:7878"));
+        assert_eq!(response.body, b"welcome to blog");
+    }
+
+    #[test]
+    fn unknown_or_missing_host_uses_fallback() {
+        let mut vhosts = VirtualHostRouter::new(site("default"));
+        vhosts.add_host("blog.example.com", site("blog"));
+
+        assert_eq!(vhosts.dispatch(&get_with_host("/", "other.org")).body, b"welcome to default");
+        assert_eq!(vhosts.dispatch(&HttpRequest::new("GET", "/")).body, b"welcome to default");
+    }
+
+    #[test]
+    fn strip_port_keeps_ipv6_literal() {
+        assert_eq!(strip_port("localhost:7878"), "localhost");
+        assert_eq!(strip_port("localhost"), "localhost");
+        assert_eq!(strip_port("[::1]:80"), "[::1]");
+        assert_eq!(strip_port("[::1]"), "[::1]");
+    }
+
+    #[test]
+    fn serves_several_requests_until_connection_close() {
+        let input = [
+            get("/", ""),
+            get("/missing", ""),
+            String::from("POST /echo HTTP/1.1\r\nContent-Length: 4\r\n\r\nping"),
+            get("/", "Connection: close\r\n"),
+            // never read: the client asked to close before this one
+            get("/", ""),
+        ]
+        .concat();
+
+        let mut output = Vec::new();
+        let handled = serve_connection(Cursor::new(input), &mut output, &test_router()).unwrap();
+        assert_eq!(handled, 4);
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("HTTP/1.1 200 OK").count(), 3);
+        assert_eq!(output.matches("HTTP/1.1 404 Not Found").count(), 1);
+        assert_eq!(output.matches("Connection: keep-alive").count(), 3);
+        assert!(output.ends_with("Connection: close\r\nContent-Length: 5\r\n\r\nhello"));
+        assert!(output.contains("\r\n\r\nping"));
+    }
+
+    #[test]
+    fn closes_after_max_requests() {
+        let input = get("/", "").repeat(MAX_REQUESTS_PER_CONNECTION + 5);
+        let mut output = Vec::new();
+        let handled = serve_connection(Cursor::new(input), &mut output, &test_router()).unwrap();
+
+        assert_eq!(handled, MAX_REQUESTS_PER_CONNECTION);
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("Connection: close").count(), 1);
+    }
+
+    #[test]
+    fn malformed_request_gets_bad_request() {
+        let input = format!("{}NONSENSE\r\n\r\n", get("/", ""));
+        let mut output = Vec::new();
+        let handled = serve_connection(Cursor::new(input), &mut output, &test_router()).unwrap();
+
+        assert_eq!(handled, 1);
+        assert!(String::from_utf8(output).unwrap().contains("HTTP/1.1 400 Bad Request"));
+    }
+
+    #[test]
+    fn oversized_body_is_refused_before_reading_it() {
+        let raw = format!("POST /echo HTTP/1.1\r\nContent-Length: {}\r\n\r\nshort", usize::MAX);
+        let err = HttpRequest::read_from(&mut Cursor::new(raw.clone())).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(is_body_too_large(&err));
+
+        let mut output = Vec::new();
+        let handled = serve_connection(Cursor::new(raw), &mut output, &test_router()).unwrap();
+        assert_eq!(handled, 0);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("HTTP/1.1 413 Payload Too Large\r\n"), "{output}");
+        assert!(output.contains("Connection: close"));
+
+        // right at the limit is still fine
+        let body = "x".repeat(MAX_BODY_SIZE);
+        let raw = format!("POST /echo HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+        let request = HttpRequest::read_from(&mut Cursor::new(raw)).unwrap().unwrap();
+        assert_eq!(request.body.len(), MAX_BODY_SIZE);
+        let too_big = format!("POST /echo HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_SIZE + 1);
+        assert!(is_body_too_large(&HttpRequest::read_from(&mut Cursor::new(too_big)).unwrap_err()));
+    }
+
+    #[test]
+    fn keep_alive_over_a_real_tcp_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = Arc::new(test_router());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            handle_keepalive_connection(stream, router);
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        let mut reader = BufReader::new(client.try_clone().unwrap());
+        for i in 0..3 {
+            let close = if i == 2 { "Connection: close\r\n" } else { "" };
+            client.write_all(get("/", close).as_bytes()).unwrap();
+
+            let mut status = String::new();
+            reader.read_line(&mut status).unwrap();
+            assert_eq!(status, "HTTP/1.1 200 OK\r\n");
+            let mut line = String::new();
+            while line != "\r\n" {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+            }
+            let mut body = [0; 5];
+            reader.read_exact(&mut body).unwrap();
+            assert_eq!(&body, b"hello");
+        }
+
+        server.join().unwrap();
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert!(rest.is_empty());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    #[tracing_test::traced_test]
+    fn requests_are_traced() {
+        let input = "GET / HTTP/1.1\r\nConnection: close\r\n\r\n";
+        let mut output = Vec::new();
+        serve_connection(Cursor::new(input), &mut output, &test_router()).unwrap();
+
+        assert!(logs_contain("handle_connection"));
+        assert!(logs_contain("path=/"));
+        assert!(logs_contain("method=GET"));
+        assert!(logs_contain("response"));
+        assert!(logs_contain("status=200"));
+    }
+}
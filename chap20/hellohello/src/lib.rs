@@ -1,64 +1,203 @@
+pub mod http;
+pub mod middleware;
+pub mod server;
+pub mod static_files;
+
 use std::{
-    sync::{mpsc, Arc, Mutex}, 
-    thread
+    fmt, io,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
 
 pub struct ThreadPool {
     workers:  Vec<Worker>,
     sender: Option<mpsc::Sender<Job>>,
+    results: Arc<Mutex<Vec<TaskResult>>>,
+    pending: Arc<AtomicUsize>,
+}
+
+/// Outcome of a single task run by the pool.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskResult {
+    pub elapsed: Duration,
+    pub panicked: bool,
+}
+
+type Job = Box<dyn FnOnce() -> TaskResult + Send + 'static>;
+
+/// Why a [`ThreadPool`] could not be created or used.
+#[derive(Debug)]
+pub enum PoolError {
+    /// `ThreadPool::new(0)`: a pool without workers would never run anything.
+    ZeroThreads,
+    /// Not even the list of workers fits in memory.
+    TooManyThreads(usize),
+    /// The operating system refused to start another thread.
+    Spawn(io::Error),
+    /// The pool has been shut down; no more tasks are accepted.
+    PoolClosed,
 }
 
-type Job = Box<dyn FnOnce() + Send + 'static>;
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PoolError::ZeroThreads => write!(f, "a thread pool needs more than zero threads"),
+            PoolError::TooManyThreads(size) => write!(f, "not enough memory for {size} threads"),
+            PoolError::Spawn(e) => write!(f, "could not spawn a worker thread: {e}"),
+            PoolError::PoolClosed => write!(f, "the thread pool is shut down"),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PoolError::Spawn(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl ThreadPool {
     /// Create a new ThreadPool.
-    /// 
+    ///
     /// The size is the number of threads in the pool.
-    /// 
-    /// ### Panics
-    /// 
-    /// The `new` function will panic if the size is zero.
-    pub fn new(size: usize) -> ThreadPool {
-        assert!(size > 0);
+    ///
+    /// ### Errors
+    ///
+    /// [`PoolError::ZeroThreads`] if the size is zero, [`PoolError::TooManyThreads`] or
+    /// [`PoolError::Spawn`] if the workers can't all be created. Workers that did start are
+    /// shut down again before the error is returned.
+    pub fn new(size: usize) -> Result<ThreadPool, PoolError> {
+        if size == 0 {
+            return Err(PoolError::ZeroThreads);
+        }
 
         let (sender, receiver) = mpsc::channel();
 
         let receiver = Arc::new(Mutex::new(receiver));
 
-        let mut workers = Vec::with_capacity(size);
+        let mut workers = Vec::new();
+        workers.try_reserve_exact(size).map_err(|_| PoolError::TooManyThreads(size))?;
+
+        let mut pool = ThreadPool {
+            workers,
+            sender: Some(sender),
+            results: Arc::new(Mutex::new(Vec::new())),
+            pending: Arc::new(AtomicUsize::new(0)),
+        };
 
         for id in 0..size {
-            workers.push(Worker::new(id, Arc::clone(&receiver)));
+            // on error `pool` is dropped, which stops the workers started so far
+            let worker = Worker::new(
+                id,
+                Arc::clone(&receiver),
+                Arc::clone(&pool.results),
+                Arc::clone(&pool.pending),
+            )
+            .map_err(PoolError::Spawn)?;
+            pool.workers.push(worker);
         }
 
-        ThreadPool { 
-            workers, 
-            sender: Some(sender),
-        }
+        Ok(pool)
     }
 
-    pub fn execute<F>(&self, f: F)
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn execute<F>(&self, f: F) -> Result<(), PoolError>
     where
         F: FnOnce() + Send + 'static,
     {
-        let job = Box::new(f);
-        self.sender.as_ref().unwrap().send(job).unwrap();
+        let sender = self.sender.as_ref().ok_or(PoolError::PoolClosed)?;
+
+        // wrap the user closure so the worker can report how long it took
+        //    and whether it panicked, without the caller noticing
+        let job: Job = Box::new(move || {
+            let start = Instant::now();
+            let panicked = panic::catch_unwind(AssertUnwindSafe(f)).is_err();
+            TaskResult { elapsed: start.elapsed(), panicked }
+        });
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        sender.send(job).map_err(|_| {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            PoolError::PoolClosed
+        })
     }
-}
 
-impl Drop for ThreadPool {
-    fn drop(&mut self) {
+    /// Stop accepting tasks and wait for the workers to finish the queued ones.
+    /// Afterwards `execute` returns [`PoolError::PoolClosed`]; calling this again does nothing.
+    pub fn shutdown(&mut self) {
         drop(self.sender.take());
-        
-        for worker in &mut self.workers {
-            println!("Shutting down worker {}", worker.id);
 
+        for worker in &mut self.workers {
             if let Some(thread) = worker.thread.take() {
+                println!("Shutting down worker {}", worker.id);
                 thread.join().unwrap();
             }
         }
     }
+
+    /// Number of worker threads.
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Number of submitted tasks that no worker has picked up yet.
+    pub fn pending_count(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Spin until every submitted task has been picked up by a worker.
+    ///
+    /// Returns `false` if the queue did not drain within `timeout`.
+    pub fn wait_until_empty(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.pending_count() != 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        true
+    }
+
+    /// Results of every task finished so far, in completion order.
+    pub fn task_results(&self) -> Vec<TaskResult> {
+        self.results.lock().unwrap().clone()
+    }
+
+    /// Average elapsed time of the finished tasks, `None` if nothing has finished yet.
+    pub fn average_task_duration(&self) -> Option<Duration> {
+        let results = self.results.lock().unwrap();
+        if results.is_empty() {
+            return None;
+        }
+        let total: Duration = results.iter().map(|r| r.elapsed).sum();
+        Some(average(total, results.len()))
+    }
+
+    /// Longest elapsed time of the finished tasks, `None` if nothing has finished yet.
+    pub fn max_task_duration(&self) -> Option<Duration> {
+        self.results.lock().unwrap().iter().map(|r| r.elapsed).max()
+    }
+}
+
+// In nanoseconds as u128, since `Duration / u32` would cut a count past u32::MAX short
+fn average(total: Duration, count: usize) -> Duration {
+    let nanos = total.as_nanos() / count as u128;
+    // an average task of over 584 years is capped there
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
 }
 
 struct Worker {
@@ -67,24 +206,162 @@ struct Worker {
 }
 
 impl Worker {
-    fn new(id: usize, receiver: Arc<Mutex<mpsc::Receiver<Job>>>) -> Worker {
-        let thread = thread::spawn(move || loop {
+    fn new(
+        id: usize,
+        receiver: Arc<Mutex<mpsc::Receiver<Job>>>,
+        results: Arc<Mutex<Vec<TaskResult>>>,
+        pending: Arc<AtomicUsize>,
+    ) -> io::Result<Worker> {
+        let thread = thread::Builder::new()
+            .name(format!("worker-{id}"))
+            .spawn(move || Worker::run(id, receiver, results, pending))?;
+
+        Ok(Worker {
+            id,
+            thread: Some(thread),
+        })
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(receiver, results, pending)))]
+    fn run(
+        id: usize,
+        receiver: Arc<Mutex<mpsc::Receiver<Job>>>,
+        results: Arc<Mutex<Vec<TaskResult>>>,
+        pending: Arc<AtomicUsize>,
+    ) {
+        loop {
             let message = receiver.lock().unwrap().recv();
             match message {
                 Ok(job) => {
+                    pending.fetch_sub(1, Ordering::SeqCst);
                     println!("Worker {id} got a job; executing.");
-                    job();
+                    let result = job();
+                    results.lock().unwrap().push(result);
                 },
                 Err(_) => {
                     println!("Worker {id} disconnected; shutting down.");
                     break;
                 }
             }
-        });
+        }
+    }
+}
 
-        Worker { 
-            id, 
-            thread: Some(thread),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for_results(pool: &ThreadPool, count: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while pool.task_results().len() < count {
+            assert!(Instant::now() < deadline, "tasks did not finish in time");
+            thread::sleep(Duration::from_millis(5));
         }
     }
+
+    #[test]
+    fn reports_elapsed_time_of_tasks() {
+        let pool = ThreadPool::new(2).unwrap();
+        pool.execute(|| thread::sleep(Duration::from_millis(50))).unwrap();
+        pool.execute(|| thread::sleep(Duration::from_millis(100))).unwrap();
+        wait_for_results(&pool, 2);
+
+        let tolerance = Duration::from_millis(200);
+        for result in pool.task_results() {
+            assert!(result.elapsed >= Duration::from_millis(50));
+            assert!(result.elapsed < Duration::from_millis(100) + tolerance);
+            assert!(!result.panicked);
+        }
+
+        let max = pool.max_task_duration().unwrap();
+        assert!(max >= Duration::from_millis(100) && max < Duration::from_millis(100) + tolerance);
+
+        let average = pool.average_task_duration().unwrap();
+        assert!(average >= Duration::from_millis(75) && average < Duration::from_millis(75) + tolerance);
+    }
+
+    #[test]
+    fn no_durations_before_any_task_finishes() {
+        let pool = ThreadPool::new(1).unwrap();
+        assert!(pool.task_results().is_empty());
+        assert_eq!(pool.average_task_duration(), None);
+        assert_eq!(pool.max_task_duration(), None);
+    }
+
+    #[test]
+    fn average_divides_by_the_whole_count() {
+        assert_eq!(average(Duration::from_millis(300), 4), Duration::from_millis(75));
+        // 2^32 + 2 tasks: as a u32 the count would have been 2
+        let count = (1usize << 32) + 2;
+        assert_eq!(average(Duration::from_nanos(count as u64 * 3), count), Duration::from_nanos(3));
+        assert_eq!(average(Duration::MAX, 1), Duration::from_nanos(u64::MAX));
+    }
+
+    #[test]
+    fn panicking_task_is_recorded_and_worker_survives() {
+        let pool = ThreadPool::new(1).unwrap();
+        pool.execute(|| panic!("boom")).unwrap();
+        pool.execute(|| {}).unwrap();
+        wait_for_results(&pool, 2);
+
+        let results = pool.task_results();
+        assert!(results[0].panicked);
+        assert!(!results[1].panicked);
+    }
+
+    #[test]
+    fn pending_count_drains_to_zero() {
+        let pool = ThreadPool::new(4).unwrap();
+        for _ in 0..20 {
+            pool.execute(|| thread::sleep(Duration::from_millis(20))).unwrap();
+        }
+        assert!(pool.pending_count() > 0);
+
+        assert!(pool.wait_until_empty(Duration::from_secs(5)));
+        assert_eq!(pool.pending_count(), 0);
+    }
+
+    #[test]
+    fn wait_until_empty_times_out_on_busy_pool() {
+        let pool = ThreadPool::new(1).unwrap();
+        pool.execute(|| thread::sleep(Duration::from_millis(300))).unwrap();
+        pool.execute(|| {}).unwrap();
+
+        assert!(!pool.wait_until_empty(Duration::from_millis(50)));
+        assert!(pool.wait_until_empty(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn zero_threads_is_an_error() {
+        assert!(matches!(ThreadPool::new(0), Err(PoolError::ZeroThreads)));
+        assert_eq!(
+            ThreadPool::new(0).err().unwrap().to_string(),
+            "a thread pool needs more than zero threads"
+        );
+    }
+
+    #[test]
+    fn absurd_size_is_a_memory_error() {
+        let err = ThreadPool::new(usize::MAX).err().unwrap();
+        assert!(matches!(err, PoolError::TooManyThreads(usize::MAX)));
+        assert!(err.to_string().contains("memory"));
+    }
+
+    #[test]
+    fn execute_after_shutdown_is_pool_closed() {
+        let mut pool = ThreadPool::new(2).unwrap();
+        pool.execute(|| thread::sleep(Duration::from_millis(20))).unwrap();
+        pool.shutdown();
+
+        // the queued task still ran
+        assert_eq!(pool.task_results().len(), 1);
+        assert!(matches!(pool.execute(|| {}), Err(PoolError::PoolClosed)));
+        assert_eq!(pool.pending_count(), 0);
+        pool.shutdown();
+    }
+
+    #[test]
+    fn size_matches_request() {
+        assert_eq!(ThreadPool::new(3).unwrap().size(), 3);
+    }
 }
@@ -1,82 +1,99 @@
 use std::{
-    fs,
-    io::{prelude::*, BufReader},
-    net::{TcpListener, TcpStream}, 
-    thread, 
+    net::{TcpListener, TcpStream},
+    process,
+    sync::Arc,
+    thread,
     time::Duration,
 };
 
-use hellohello::ThreadPool;
+use hellohello::{
+    http::{self, Router},
+    server::{self, Server, RELOAD},
+    static_files::StaticFiles,
+};
 
 #[allow(dead_code)]
 enum Mode {
     SingleThreaded,
     ThreadPerRequest,
-    ThreadPool(usize),
+    ThreadPool,
 }
 
 fn main() {
+    #[cfg(feature = "tracing")]
+    tracing_subscriber::fmt::init();
+
     let listener = TcpListener::bind("127.0.0.1:7878").unwrap();
-    let mode = Mode::ThreadPool(4);
+    let mode = Mode::ThreadPool;
+    let router = Arc::new(build_router());
 
     match mode {
         Mode::SingleThreaded | Mode::ThreadPerRequest => {
             for stream in listener.incoming() {
                 let stream = stream.unwrap();
+                let router = Arc::clone(&router);
                 match mode {
-                    Mode::SingleThreaded => run_single_threaded(stream),
-                    Mode::ThreadPerRequest => run_req_per_thread(stream),
+                    Mode::SingleThreaded => run_single_threaded(stream, router),
+                    Mode::ThreadPerRequest => run_req_per_thread(stream, router),
                     _ => (),
                 }
             }
         },
-        Mode::ThreadPool(num) => {
-            let pool = ThreadPool::new(num);
+        // the pool size comes from server.conf; `kill -USR1 <pid>` re-reads it without
+        //    closing the listener, so new connections wait in its backlog while the
+        //    old pool finishes its requests, instead of being refused
+        Mode::ThreadPool => {
+            server::install_reload_handler();
+            let mut server = Server::new("server.conf").unwrap_or_else(|e| {
+                eprintln!("Could not start the server: {e}");
+                process::exit(1);
+            });
             for stream in listener.incoming() {
                 let stream = stream.unwrap();
+                let router = Arc::clone(&router);
 
-                pool.execute(|| {
-                    handle_connection(stream);
-                });
+                if let Err(e) = server.execute(|| {
+                    handle_connection(stream, router);
+                }) {
+                    eprintln!("Dropped a connection: {e}");
+                }
+                // a failed reload keeps serving with the old pool
+                if let Err(e) = server.reload_if_requested(&RELOAD) {
+                    eprintln!("Reload failed: {e}");
+                }
             }
             println!("Shutting down.");
         }
     }
-
-    
 }
 
+fn build_router() -> Router {
+    let files = StaticFiles::new(".");
+
+    let not_found = files.clone();
+    let mut router = Router::new(move |req| not_found.serve(req, 404, "404.html"));
+
+    let hello = files.clone();
+    let sleepy = files;
+    router
+        .route("GET", "/", move |req| hello.serve(req, 200, "hello.html"))
+        .route("GET", "/sleep", move |req| {
+            thread::sleep(Duration::from_secs(5));
+            sleepy.serve(req, 200, "hello.html")
+        });
+    router
+}
 
-fn run_single_threaded(stream: TcpStream) {
-    handle_connection(stream);
+fn run_single_threaded(stream: TcpStream, router: Arc<Router>) {
+    handle_connection(stream, router);
 }
 
-fn run_req_per_thread(stream: TcpStream) {
+fn run_req_per_thread(stream: TcpStream, router: Arc<Router>) {
     thread::spawn(|| {
-        handle_connection(stream);
+        handle_connection(stream, router);
     });
 }
 
-fn handle_connection(mut stream: TcpStream) {
-    let buf_reader = BufReader::new(&stream);
-
-    let request_line = buf_reader.lines().next().unwrap().unwrap();
-
-    let (status_line, filename) = match &request_line[..] {
-        "GET / HTTP/1.1" => ("HTTP/1.1 200 OK", "hello.html"),
-        "GET /sleep HTTP/1.1" => {
-            thread::sleep(Duration::from_secs(5));
-            ("HTTP/1.1 200 OK", "hello.html")
-        },
-        _ => ("HTTP/1.1 404 NOT FOUND", "404.html"),
-    };
-
-    let contents = fs::read_to_string(filename).unwrap();
-    let length = contents.len();
-
-    let response = format!(
-        "{status_line}\r\nContent-Length: {length}\r\n\r\n{contents}"
-    );
-
-    stream.write_all(response.as_bytes()).unwrap();
+fn handle_connection(stream: TcpStream, router: Arc<Router>) {
+    http::handle_keepalive_connection(stream, router);
 }
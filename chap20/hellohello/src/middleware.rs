@@ -0,0 +1,338 @@
+use crate::http::{Handler, HttpRequest, HttpResponse};
+
+/// Prefix a log message with the id of the request it belongs to.
+pub fn log_line(req: &HttpRequest, message: &str) -> String {
+    format!("[{}] {message}", req.request_id)
+}
+
+/// Log the request and its outcome, and echo the request id back to the client.
+pub fn logging_middleware(
+    req: &HttpRequest,
+    next: &dyn Fn(&HttpRequest) -> HttpResponse,
+) -> HttpResponse {
+    println!("{}", log_line(req, &format!("{} {}", req.method, req.path)));
+
+    let mut response = next(req);
+    inject_request_id_header(&mut response, &req.request_id);
+
+    println!(
+        "{}",
+        log_line(req, &format!("-> {} ({} bytes)", response.status, response.body.len()))
+    );
+    response
+}
+
+pub fn inject_request_id_header(response: &mut HttpResponse, id: &str) {
+    response.set_header("X-Request-Id", id);
+}
+
+const ADLER_MOD: u32 = 65521;
+
+/// Running Adler-32 checksum, fed a piece of data at a time.
+#[derive(Debug, Clone, Copy)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    pub fn new() -> Adler32 {
+        Adler32 { a: 1, b: 0 }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        // 5552 is the most bytes that can be summed before b could overflow a u32,
+        //    so the (slow) modulo is only needed once per block
+        for block in data.chunks(5552) {
+            for &byte in block {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= ADLER_MOD;
+            self.b %= ADLER_MOD;
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
+
+impl Default for Adler32 {
+    fn default() -> Adler32 {
+        Adler32::new()
+    }
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let mut checksum = Adler32::new();
+    checksum.update(data);
+    checksum.finish()
+}
+
+/// Adds an `X-Checksum: adler32=<hex>` header to responses for `GET` requests,
+/// so clients can check the body arrived intact.
+pub struct ChecksumMiddleware {
+    pub inner: Handler,
+}
+
+impl ChecksumMiddleware {
+    pub fn handle(&self, req: HttpRequest) -> HttpResponse {
+        let mut response = (self.inner)(&req);
+        if req.method == "GET" {
+            let value = format!("adler32={:08x}", adler32(&response.body));
+            response.set_header("X-Checksum", &value);
+        }
+        response
+    }
+}
+
+/// Which cross-origin requests browsers are allowed to make.
+/// An origin of `"*"` allows every origin.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    /// How long, in seconds, a browser may cache a preflight answer.
+    pub max_age: u64,
+}
+
+impl CorsConfig {
+    /// The `Access-Control-Allow-Origin` value for `origin`, or `None` if it isn't allowed.
+    pub fn allow_origin<'a>(&self, origin: &'a str) -> Option<&'a str> {
+        if self.allowed_origins.iter().any(|o| o == "*") {
+            Some("*")
+        } else if self.allowed_origins.iter().any(|o| o == origin) {
+            Some(origin)
+        } else {
+            None
+        }
+    }
+}
+
+/// Answer `OPTIONS` preflight requests from allowed origins and add
+/// `Access-Control-Allow-Origin` to every other response sent to them.
+///
+/// Requests without an `Origin` header (same-origin or not from a browser) pass through untouched.
+pub fn cors_middleware(config: &CorsConfig, req: HttpRequest, handler: &Handler) -> HttpResponse {
+    let origin = match req.header("Origin") {
+        Some(origin) => origin,
+        None => return handler(&req),
+    };
+    let allowed = config.allow_origin(origin);
+    let preflight = req.method == "OPTIONS" && req.header("Access-Control-Request-Method").is_some();
+
+    if preflight {
+        return match allowed {
+            Some(allowed) => {
+                let mut response = HttpResponse::new(200, Vec::new())
+                    .with_header("Access-Control-Allow-Origin", allowed)
+                    .with_header("Access-Control-Allow-Methods", &config.allowed_methods.join(", "))
+                    .with_header("Access-Control-Allow-Headers", &config.allowed_headers.join(", "))
+                    .with_header("Access-Control-Max-Age", &config.max_age.to_string());
+                add_vary_origin(&mut response, allowed);
+                response
+            }
+            None => HttpResponse::new(403, "Forbidden"),
+        };
+    }
+
+    let mut response = handler(&req);
+    if let Some(allowed) = allowed {
+        response.set_header("Access-Control-Allow-Origin", allowed);
+        add_vary_origin(&mut response, allowed);
+    }
+    response
+}
+
+// An echoed origin makes the response depend on the request, so caches must keep them apart
+fn add_vary_origin(response: &mut HttpResponse, allowed: &str) {
+    if allowed != "*" {
+        response.set_header("Vary", "Origin");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::generate_request_id;
+    use std::{collections::HashSet, thread};
+
+    fn id_number(id: &str) -> u64 {
+        u64::from_str_radix(id.strip_prefix("req-").unwrap(), 16).unwrap()
+    }
+
+    #[test]
+    fn adler32_known_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let data: Vec<u8> = (0..100_000).map(|i| (i * 31 % 256) as u8).collect();
+        let mut streaming = Adler32::new();
+        for piece in data.chunks(777) {
+            streaming.update(piece);
+        }
+        assert_eq!(streaming.finish(), adler32(&data));
+
+        // all 0xff bytes is the worst case for overflow inside a block
+        let ones = vec![0xff; 20_000];
+        let mut naive_a: u64 = 1;
+        let mut naive_b: u64 = 0;
+        for &byte in &ones {
+            naive_a = (naive_a + u64::from(byte)) % 65521;
+            naive_b = (naive_b + naive_a) % 65521;
+        }
+        assert_eq!(adler32(&ones), ((naive_b << 16) | naive_a) as u32);
+    }
+
+    #[test]
+    fn checksum_header_matches_body() {
+        let middleware = ChecksumMiddleware {
+            inner: Box::new(|_| HttpResponse::new(200, "<h1>Hello!</h1>")),
+        };
+        let response = middleware.handle(HttpRequest::new("GET", "/"));
+
+        let header = response.header("X-Checksum").unwrap();
+        let value = header.strip_prefix("adler32=").unwrap();
+        let value = u32::from_str_radix(value, 16).unwrap();
+        assert_eq!(value, adler32(b"<h1>Hello!</h1>"));
+        assert_eq!(response.body, b"<h1>Hello!</h1>");
+    }
+
+    #[test]
+    fn checksum_only_for_get() {
+        let middleware = ChecksumMiddleware {
+            inner: Box::new(|_| HttpResponse::new(200, "created")),
+        };
+        let response = middleware.handle(HttpRequest::new("POST", "/"));
+        assert_eq!(response.header("X-Checksum"), None);
+    }
+
+    #[test]
+    fn request_id_format() {
+        let id = generate_request_id();
+        assert!(id.starts_with("req-"));
+        assert_eq!(id.len(), 4 + 16);
+        assert!(id[4..].chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn ids_are_unique_and_increasing_across_threads() {
+        let handles: Vec<_> = (0..100)
+            .map(|_| {
+                thread::spawn(|| {
+                    let ids: Vec<String> = (0..10).map(|_| generate_request_id()).collect();
+                    // each thread sees its own ids strictly increasing
+                    assert!(ids.windows(2).all(|w| id_number(&w[0]) < id_number(&w[1])));
+                    ids
+                })
+            })
+            .collect();
+
+        let mut all = HashSet::new();
+        for handle in handles {
+            for id in handle.join().unwrap() {
+                assert!(all.insert(id), "duplicate request id");
+            }
+        }
+        assert_eq!(all.len(), 1000);
+    }
+
+    #[test]
+    fn requests_get_distinct_ids() {
+        let a = HttpRequest::new("GET", "/");
+        let b = HttpRequest::new("GET", "/");
+        assert_ne!(a.request_id, b.request_id);
+    }
+
+    #[test]
+    fn log_lines_carry_the_request_id() {
+        let req = HttpRequest::new("GET", "/");
+        let line = log_line(&req, "GET /");
+        assert_eq!(line, format!("[{}] GET /", req.request_id));
+    }
+
+    #[test]
+    fn middleware_adds_request_id_header() {
+        let req = HttpRequest::new("GET", "/");
+        let response = logging_middleware(&req, &|_| HttpResponse::new(200, "hello"));
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("X-Request-Id"), Some(req.request_id.as_str()));
+    }
+
+    fn cors(origins: &[&str]) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            allowed_methods: vec![String::from("GET"), String::from("POST")],
+            allowed_headers: vec![String::from("Content-Type"), String::from("X-Request-Id")],
+            max_age: 600,
+        }
+    }
+
+    fn hello_handler() -> Handler {
+        Box::new(|_| HttpResponse::new(200, "hello"))
+    }
+
+    #[test]
+    fn preflight_returns_all_cors_headers() {
+        let config = cors(&["https://example.com"]);
+        let req = HttpRequest::new("OPTIONS", "/api")
+            .with_header("Origin", "https://example.com")
+            .with_header("Access-Control-Request-Method", "POST");
+        let response = cors_middleware(&config, req, &hello_handler());
+
+        assert_eq!(response.status, 200);
+        assert!(response.body.is_empty());
+        assert_eq!(response.header("Access-Control-Allow-Origin"), Some("https://example.com"));
+        assert_eq!(response.header("Access-Control-Allow-Methods"), Some("GET, POST"));
+        assert_eq!(response.header("Access-Control-Allow-Headers"), Some("Content-Type, X-Request-Id"));
+        assert_eq!(response.header("Access-Control-Max-Age"), Some("600"));
+        assert_eq!(response.header("Vary"), Some("Origin"));
+    }
+
+    #[test]
+    fn get_gets_origin_header_added() {
+        let config = cors(&["https://example.com"]);
+        let req = HttpRequest::new("GET", "/").with_header("Origin", "https://example.com");
+        let response = cors_middleware(&config, req, &hello_handler());
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"hello");
+        assert_eq!(response.header("Access-Control-Allow-Origin"), Some("https://example.com"));
+        assert_eq!(response.header("Access-Control-Allow-Methods"), None);
+    }
+
+    #[test]
+    fn wildcard_allows_any_origin() {
+        let config = cors(&["*"]);
+        let req = HttpRequest::new("GET", "/").with_header("Origin", "http://localhost:3000");
+        let response = cors_middleware(&config, req, &hello_handler());
+
+        assert_eq!(response.header("Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(response.header("Vary"), None);
+    }
+
+    #[test]
+    fn other_origins_get_no_cors_headers() {
+        let config = cors(&["https://example.com"]);
+
+        let get = HttpRequest::new("GET", "/").with_header("Origin", "https://evil.example");
+        let response = cors_middleware(&config, get, &hello_handler());
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("Access-Control-Allow-Origin"), None);
+
+        let preflight = HttpRequest::new("OPTIONS", "/")
+            .with_header("Origin", "https://evil.example")
+            .with_header("Access-Control-Request-Method", "GET");
+        assert_eq!(cors_middleware(&config, preflight, &hello_handler()).status, 403);
+
+        let same_origin = HttpRequest::new("GET", "/");
+        let response = cors_middleware(&config, same_origin, &hello_handler());
+        assert_eq!(response.header("Access-Control-Allow-Origin"), None);
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{PoolError, ThreadPool};
+
+/// Set (from a signal handler) to ask the server to reload its configuration.
+pub static RELOAD: AtomicBool = AtomicBool::new(false);
+
+/// Make `SIGUSR1` set [`RELOAD`], so `kill -USR1 <pid>` reloads the server.
+/// Does nothing on platforms without Unix signals.
+pub fn install_reload_handler() {
+    #[cfg(unix)]
+    {
+        // only an atomic store: anything that could allocate or lock isn't safe in a signal handler
+        extern "C" fn on_sigusr1(_signal: libc::c_int) {
+            RELOAD.store(true, Ordering::SeqCst);
+        }
+        let handler: extern "C" fn(libc::c_int) = on_sigusr1;
+        unsafe {
+            libc::signal(libc::SIGUSR1, handler as libc::sighandler_t);
+        }
+    }
+}
+
+/// Settings read from a `key = value` file; `#` starts a comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub threads: usize,
+}
+
+impl Default for ServerConfig {
+    fn default() -> ServerConfig {
+        ServerConfig { threads: 4 }
+    }
+}
+
+impl ServerConfig {
+    pub fn parse(text: &str) -> Result<ServerConfig, String> {
+        let mut config = ServerConfig::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `key = value`", i + 1))?;
+            match key.trim() {
+                "threads" => {
+                    config.threads = value
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or_else(|| format!("line {}: threads must be a positive number", i + 1))?;
+                }
+                other => return Err(format!("line {}: unknown setting `{other}`", i + 1)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Read and parse `path`; the error says which file and what was wrong with it.
+    pub fn load(path: &Path) -> Result<ServerConfig, String> {
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| ServerConfig::parse(&text))
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Read `path`, keeping the defaults if it can't be read or parsed.
+    pub fn load_or_default(path: &Path) -> ServerConfig {
+        ServerConfig::load(path).unwrap_or_else(|e| {
+            eprintln!("Using default config ({e})");
+            ServerConfig::default()
+        })
+    }
+}
+
+/// Why [`Server::reload`] left the running pool and config as they were.
+#[derive(Debug)]
+pub enum ReloadError {
+    /// The config file couldn't be read or parsed.
+    Config(String),
+    /// The new pool couldn't be started.
+    Pool(PoolError),
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReloadError::Config(e) => write!(f, "bad config: {e}"),
+            ReloadError::Pool(e) => write!(f, "could not start the new pool: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ReloadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReloadError::Config(_) => None,
+            ReloadError::Pool(e) => Some(e),
+        }
+    }
+}
+
+impl From<PoolError> for ReloadError {
+    fn from(e: PoolError) -> ReloadError {
+        ReloadError::Pool(e)
+    }
+}
+
+/// A thread pool that can be swapped for a freshly configured one without stopping the
+/// accept loop; the connection counter lives outside the pool so it survives the swap.
+pub struct Server {
+    config_path: PathBuf,
+    config: ServerConfig,
+    pool: ThreadPool,
+    connections: Arc<AtomicUsize>,
+    reloads: usize,
+}
+
+/// Counts a job as finished when it is dropped, which happens on the way out of a
+/// panicking handler too.
+struct CountOnDrop(Arc<AtomicUsize>);
+
+impl Drop for CountOnDrop {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl Server {
+    pub fn new(config_path: impl Into<PathBuf>) -> Result<Server, PoolError> {
+        let config_path = config_path.into();
+        let config = ServerConfig::load_or_default(&config_path);
+        Ok(Server {
+            pool: ThreadPool::new(config.threads)?,
+            config,
+            config_path,
+            connections: Arc::new(AtomicUsize::new(0)),
+            reloads: 0,
+        })
+    }
+
+    pub fn execute<F>(&self, f: F) -> Result<(), PoolError>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let counted = CountOnDrop(Arc::clone(&self.connections));
+        self.pool.execute(move || {
+            let _counted = counted;
+            f();
+        })
+    }
+
+    /// Jobs (one per accepted connection) finished so far, across every pool this server
+    /// has had, including those whose handler panicked. A keep-alive connection counts
+    /// once, however many requests it carried.
+    pub fn connections_handled(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
+    pub fn pool_size(&self) -> usize {
+        self.pool.size()
+    }
+
+    pub fn reloads(&self) -> usize {
+        self.reloads
+    }
+
+    /// Reload if `flag` is set, clearing it; called after every accepted connection.
+    pub fn reload_if_requested(&mut self, flag: &AtomicBool) -> Result<bool, ReloadError> {
+        if flag.swap(false, Ordering::SeqCst) {
+            self.reload()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Re-read the config file and replace the pool. Requests already queued on the old
+    /// pool are finished first (dropping a `ThreadPool` waits for its workers).
+    /// If the file can't be read or parsed, or the new pool can't be started, the old
+    /// pool and its config stay in place.
+    pub fn reload(&mut self) -> Result<(), ReloadError> {
+        let config = ServerConfig::load(&self.config_path).map_err(ReloadError::Config)?;
+        let old = std::mem::replace(&mut self.pool, ThreadPool::new(config.threads)?);
+        drop(old);
+        self.config = config;
+        self.reloads += 1;
+        println!("Reloaded config: {:?}", self.config);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{process, thread, time::Duration};
+
+    fn config_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("hellohello-{name}-{}.conf", process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn parse_config() {
+        assert_eq!(ServerConfig::parse("# pool\nthreads = 8\n\n"), Ok(ServerConfig { threads: 8 }));
+        assert_eq!(ServerConfig::parse(""), Ok(ServerConfig::default()));
+        assert!(ServerConfig::parse("threads = 0").is_err());
+        assert!(ServerConfig::parse("threads").is_err());
+        assert!(ServerConfig::parse("colour = blue").is_err());
+    }
+
+    #[test]
+    fn missing_config_uses_defaults() {
+        let path = std::env::temp_dir().join("hellohello-does-not-exist.conf");
+        assert_eq!(ServerConfig::load_or_default(&path), ServerConfig::default());
+    }
+
+    #[test]
+    fn flag_recreates_pool_and_keeps_counter() {
+        let path = config_file("reload", "threads = 2");
+        let mut server = Server::new(&path).unwrap();
+        assert_eq!(server.pool_size(), 2);
+
+        for _ in 0..5 {
+            server.execute(|| thread::sleep(Duration::from_millis(5))).unwrap();
+        }
+
+        let flag = AtomicBool::new(false);
+        assert!(!server.reload_if_requested(&flag).unwrap());
+        assert_eq!(server.reloads(), 0);
+
+        fs::write(&path, "threads = 3").unwrap();
+        flag.store(true, Ordering::SeqCst);
+        assert!(server.reload_if_requested(&flag).unwrap());
+        assert!(!flag.load(Ordering::SeqCst), "flag is reset");
+        assert_eq!(server.reloads(), 1);
+        assert_eq!(server.pool_size(), 3);
+        assert_eq!(server.config(), &ServerConfig { threads: 3 });
+        // the old pool finished its queue before it was replaced
+        assert_eq!(server.connections_handled(), 5);
+
+        server.execute(|| ()).unwrap();
+        server.execute(|| ()).unwrap();
+        let connections = Arc::clone(&server.connections);
+        drop(server);
+        assert_eq!(connections.load(Ordering::SeqCst), 7);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn panicking_handlers_are_counted_too() {
+        let path = config_file("panicking", "threads = 2");
+        let server = Server::new(&path).unwrap();
+        server.execute(|| ()).unwrap();
+        server.execute(|| panic!("handler failed")).unwrap();
+        server.execute(|| ()).unwrap();
+
+        let connections = Arc::clone(&server.connections);
+        drop(server);
+        assert_eq!(connections.load(Ordering::SeqCst), 3);
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn broken_config_keeps_the_running_one() {
+        let path = config_file("reload-broken", "threads = 3");
+        let mut server = Server::new(&path).unwrap();
+
+        fs::write(&path, "threads = lots").unwrap();
+        let err = server.reload().unwrap_err();
+        assert!(matches!(&err, ReloadError::Config(e) if e.contains("threads must be a positive number")), "{err}");
+        assert_eq!(server.config(), &ServerConfig { threads: 3 });
+        assert_eq!(server.pool_size(), 3);
+        assert_eq!(server.reloads(), 0);
+
+        // a file that has gone away is an error too, not a reason to fall back to the defaults
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(server.reload(), Err(ReloadError::Config(_))));
+        assert_eq!(server.pool_size(), 3);
+        server.execute(|| ()).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sigusr1_sets_reload_flag() {
+        install_reload_handler();
+        RELOAD.store(false, Ordering::SeqCst);
+        unsafe {
+            libc::raise(libc::SIGUSR1);
+        }
+        assert!(RELOAD.swap(false, Ordering::SeqCst));
+    }
+}
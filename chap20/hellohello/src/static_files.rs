@@ -0,0 +1,215 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::http::{HttpRequest, HttpResponse};
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+// CRC-32 (IEEE 802.3, reflected polynomial 0xEDB88320), one lookup per byte
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc = CRC32_TABLE[((crc ^ u32::from(byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Serves files from a directory with `ETag`s, answering `304 Not Modified`
+/// when the client already has the current version.
+///
+/// ETags are cached per file, so a conditional request doesn't re-read the file; every
+/// full response re-reads it and refreshes the cached tag. Until one does, an edited file
+/// can still be answered with `304`; call [`StaticFiles::clear_cache`] after editing them.
+#[derive(Clone)]
+pub struct StaticFiles {
+    root: PathBuf,
+    etags: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl StaticFiles {
+    pub fn new(root: impl Into<PathBuf>) -> StaticFiles {
+        StaticFiles {
+            root: root.into(),
+            etags: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The quoted ETag of `file`, e.g. `"cbf43926"`.
+    pub fn etag(&self, file: &str) -> io::Result<String> {
+        if let Some(etag) = self.etags.lock().unwrap().get(file) {
+            return Ok(etag.clone());
+        }
+        let contents = fs::read(self.root.join(file))?;
+        Ok(self.store_etag(file, &contents))
+    }
+
+    // the tag of contents, which replaces whatever was cached for file: a body must never
+    //    go out under an older version's tag
+    fn store_etag(&self, file: &str, contents: &[u8]) -> String {
+        let etag = format!("\"{:08x}\"", crc32(contents));
+        self.etags.lock().unwrap().insert(file.to_string(), etag.clone());
+        etag
+    }
+
+    pub fn clear_cache(&self) {
+        self.etags.lock().unwrap().clear();
+    }
+
+    /// Respond with `file` and `status`; a successful response becomes `304 Not Modified`
+    /// with no body when the request's `If-None-Match` names the current ETag.
+    pub fn serve(&self, req: &HttpRequest, status: u16, file: &str) -> HttpResponse {
+        if status == 200 {
+            if let (Some(tags), Ok(etag)) = (req.header("If-None-Match"), self.etag(file)) {
+                if etag_matches(tags, &etag) {
+                    return HttpResponse::new(304, Vec::new()).with_header("ETag", &etag);
+                }
+            }
+        }
+
+        match fs::read(self.root.join(file)) {
+            Ok(contents) => {
+                let etag = self.store_etag(file, &contents);
+                HttpResponse::new(status, contents).with_header("ETag", &etag)
+            }
+            Err(e) => {
+                eprintln!("Could not read {file}: {e}");
+                HttpResponse::new(500, "Internal Server Error")
+            }
+        }
+    }
+}
+
+/// `If-None-Match` holds `*` or a comma-separated list of (possibly weak, `W/"..."`) tags.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{path::Path, process};
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hellohello-{name}-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(root: &Path, file: &str, contents: &str) {
+        fs::write(root.join(file), contents).unwrap();
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn conditional_get_returns_not_modified() {
+        let root = temp_root("etag");
+        write(&root, "hello.html", "<h1>Hello!</h1>");
+        let files = StaticFiles::new(&root);
+
+        let first = files.serve(&HttpRequest::new("GET", "/"), 200, "hello.html");
+        assert_eq!(first.status, 200);
+        assert_eq!(first.body, b"<h1>Hello!</h1>");
+        let etag = first.header("ETag").unwrap().to_string();
+        assert_eq!(etag, format!("\"{:08x}\"", crc32(b"<h1>Hello!</h1>")));
+
+        let conditional = HttpRequest::new("GET", "/").with_header("If-None-Match", &etag);
+        let second = files.serve(&conditional, 200, "hello.html");
+        assert_eq!(second.status, 304);
+        assert!(second.body.is_empty());
+        assert_eq!(second.header("ETag"), Some(etag.as_str()));
+
+        let mut out = Vec::new();
+        second.write_to(&mut out).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("Content-Length"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn stale_or_other_tags_get_full_response() {
+        let root = temp_root("stale");
+        write(&root, "hello.html", "v1");
+        let files = StaticFiles::new(&root);
+
+        let stale = HttpRequest::new("GET", "/").with_header("If-None-Match", "\"00000000\"");
+        assert_eq!(files.serve(&stale, 200, "hello.html").status, 200);
+
+        let etag = files.etag("hello.html").unwrap();
+        let listed = HttpRequest::new("GET", "/")
+            .with_header("If-None-Match", &format!("\"abc\", W/{etag}"));
+        assert_eq!(files.serve(&listed, 200, "hello.html").status, 304);
+
+        let any = HttpRequest::new("GET", "/").with_header("If-None-Match", "*");
+        assert_eq!(files.serve(&any, 200, "hello.html").status, 304);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn etags_are_cached_until_cleared() {
+        let root = temp_root("cache");
+        write(&root, "page.html", "old");
+        let files = StaticFiles::new(&root);
+        let old = files.etag("page.html").unwrap();
+
+        write(&root, "page.html", "new");
+        assert_eq!(files.etag("page.html").unwrap(), old);
+        // a full response sends the tag of the body it sends, and caches that one
+        let response = files.serve(&HttpRequest::new("GET", "/"), 200, "page.html");
+        assert_eq!(response.body, b"new");
+        let new = format!("\"{:08x}\"", crc32(b"new"));
+        assert_eq!(response.header("ETag"), Some(new.as_str()));
+        assert_eq!(files.etag("page.html").unwrap(), new);
+
+        write(&root, "page.html", "newer");
+        files.clear_cache();
+        assert_eq!(files.etag("page.html").unwrap(), format!("\"{:08x}\"", crc32(b"newer")));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn error_pages_are_never_not_modified() {
+        let root = temp_root("error");
+        write(&root, "404.html", "Oops!");
+        let files = StaticFiles::new(&root);
+        let etag = files.etag("404.html").unwrap();
+
+        let req = HttpRequest::new("GET", "/missing").with_header("If-None-Match", &etag);
+        let response = files.serve(&req, 404, "404.html");
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, b"Oops!");
+
+        assert_eq!(files.serve(&req, 200, "nope.html").status, 500);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
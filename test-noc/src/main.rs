@@ -1,6 +1,13 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
 use nannou::prelude::*;
 
+// how much one key press changes the stiffness (q / w) or the damping (a / d)
+const STIFFNESS_STEP: f32 = 0.005;
+const DAMPING_STEP: f32 = 0.01;
+
 fn main() {
     nannou::app(model).update(update).run();
 }
@@ -10,6 +17,12 @@ struct Model {
     y: f32,
     x_speed: f32,
     y_speed: f32,
+    // the spring pulling the ball to the window center
+    spring_stiffness: f32,
+    spring_damping: f32,
+    // `s` saves the frame in here
+    screenshot_dir: PathBuf,
+    screenshot_count: usize,
 }
 
 fn model(app: &App) -> Model {
@@ -18,28 +31,85 @@ fn model(app: &App) -> Model {
     let x_speed = 2.5;
     let y_speed = 2.0;
 
-    let _window = app.new_window().size(800, 200).view(view).build().unwrap();
+    let screenshot_dir = PathBuf::from("screenshots");
+    create_screenshot_dir(&screenshot_dir).expect("could not create the screenshot directory");
+
+    let _window = app
+        .new_window()
+        .size(800, 200)
+        .view(view)
+        .key_pressed(key_pressed)
+        .build()
+        .unwrap();
     Model {
         x,
         y,
         x_speed,
         y_speed,
+        spring_stiffness: 0.01,
+        spring_damping: 0.05,
+        screenshot_dir,
+        screenshot_count: 0,
     }
 }
 
-fn update(app: &App, model: &mut Model, _update: Update) {
+// at startup, so the first capture doesn't fail for want of a directory; fine if it's already there
+fn create_screenshot_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+fn screenshot_path(dir: &Path, frame_num: u64) -> PathBuf {
+    dir.join(format!("screenshot_{}.png", frame_num))
+}
+
+// F = -k * (pos - center) - d * velocity: pulled back towards the center, harder the
+// further away the ball is, and slowed down the faster it moves
+fn spring_force(pos: Vec2, center: Vec2, velocity: Vec2, stiffness: f32, damping: f32) -> Vec2 {
+    -stiffness * (pos - center) - damping * velocity
+}
+
+// one frame: the force changes the speed, then the new speed moves the ball
+fn step(model: &mut Model, center: Vec2) {
+    let velocity = vec2(model.x_speed, model.y_speed);
+    let force = spring_force(
+        vec2(model.x, model.y),
+        center,
+        velocity,
+        model.spring_stiffness,
+        model.spring_damping,
+    );
+    model.x_speed += force.x;
+    model.y_speed += force.y;
+
     // add the current speed to the position
-    model.x = model.x + model.x_speed;
-    model.y = model.y + model.y_speed;
+    model.x += model.x_speed;
+    model.y += model.y_speed;
+}
 
-    let win_rect = app.window_rect();
+fn update(app: &App, model: &mut Model, _update: Update) {
+    // no bouncing off the edges: the spring keeps the ball in the window
+    step(model, app.window_rect().xy());
+}
 
-    if (model.x > win_rect.right()) || (model.x < win_rect.left()) {
-        model.x_speed = model.x_speed * (-1.0);
-    }
+// never below zero: a negative stiffness pushes the ball away, negative damping speeds it up
+fn adjusted(value: f32, delta: f32) -> f32 {
+    (value + delta).max(0.0)
+}
 
-    if (model.y > win_rect.top()) || (model.y < win_rect.bottom()) {
-        model.y_speed = model.y_speed * (-1.0);
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::Q => model.spring_stiffness = adjusted(model.spring_stiffness, -STIFFNESS_STEP),
+        Key::W => model.spring_stiffness = adjusted(model.spring_stiffness, STIFFNESS_STEP),
+        Key::A => model.spring_damping = adjusted(model.spring_damping, -DAMPING_STEP),
+        Key::D => model.spring_damping = adjusted(model.spring_damping, DAMPING_STEP),
+        Key::S => {
+            // written in the background, once this frame has been drawn
+            let path = screenshot_path(&model.screenshot_dir, app.elapsed_frames());
+            app.main_window().capture_frame(&path);
+            model.screenshot_count += 1;
+            println!("screenshot {} saved to {}", model.screenshot_count, path.display());
+        }
+        _ => {}
     }
 }
 
@@ -48,11 +118,132 @@ fn view(app: &App, model: &Model, frame: Frame) {
     let draw = app.draw();
     draw.background().color(WHITE);
 
+    let win_rect = app.window_rect();
+    draw.line()
+        .start(win_rect.xy())
+        .end(pt2(model.x, model.y))
+        .weight(2.0)
+        .color(GRAY);
+
     draw.ellipse()
         .x_y(model.x, model.y)
         .w_h(50.0, 50.0)
         .rgba(0.5, 0.5, 0.5, 1.0)
         .stroke(BLACK);
 
+    let label = format!(
+        "stiffness {:.3} (q/w)   damping {:.2} (a/d)   screenshot (s)",
+        model.spring_stiffness, model.spring_damping
+    );
+    draw.text(&label)
+        .xy(win_rect.top_left() + vec2(210.0, -15.0))
+        .w(400.0)
+        .left_justify()
+        .color(BLACK);
+
     draw.to_frame(app, &frame).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ball(stiffness: f32, damping: f32) -> Model {
+        Model {
+            x: 100.0,
+            y: 100.0,
+            x_speed: 2.5,
+            y_speed: 2.0,
+            spring_stiffness: stiffness,
+            spring_damping: damping,
+            screenshot_dir: PathBuf::from("screenshots"),
+            screenshot_count: 0,
+        }
+    }
+
+    #[test]
+    fn force_from_position_and_velocity() {
+        // 10 right of and 20 above the center, moving right at 2
+        let force = spring_force(vec2(10.0, 20.0), Vec2::ZERO, vec2(2.0, 0.0), 0.5, 0.25);
+        assert_eq!(force, vec2(-5.0 - 0.5, -10.0));
+
+        // only the offset from the center counts
+        let force = spring_force(vec2(110.0, 70.0), vec2(100.0, 50.0), vec2(2.0, 0.0), 0.5, 0.25);
+        assert_eq!(force, vec2(-5.5, -10.0));
+
+        // at rest in the center there's nothing to do
+        assert_eq!(spring_force(Vec2::ZERO, Vec2::ZERO, Vec2::ZERO, 0.5, 0.25), Vec2::ZERO);
+        // no damping: the velocity doesn't matter
+        assert_eq!(spring_force(vec2(4.0, 0.0), Vec2::ZERO, vec2(9.0, 9.0), 0.5, 0.0), vec2(-2.0, 0.0));
+    }
+
+    #[test]
+    fn damped_ball_settles_in_the_center() {
+        let center = vec2(0.0, 0.0);
+        let mut model = ball(0.01, 0.05);
+        for _ in 0..2000 {
+            step(&mut model, center);
+        }
+        assert!(vec2(model.x, model.y).distance(center) < 0.01, "{} {}", model.x, model.y);
+        assert!(vec2(model.x_speed, model.y_speed).length() < 0.01);
+
+        // around any center, not just the origin
+        let center = vec2(-40.0, 30.0);
+        let mut model = ball(0.05, 0.2);
+        for _ in 0..2000 {
+            step(&mut model, center);
+        }
+        assert!(vec2(model.x, model.y).distance(center) < 0.01, "{} {}", model.x, model.y);
+    }
+
+    #[test]
+    fn undamped_ball_keeps_swinging() {
+        let mut model = ball(0.01, 0.0);
+        let start = vec2(model.x, model.y).length();
+        let mut furthest: f32 = 0.0;
+        for _ in 0..2000 {
+            step(&mut model, Vec2::ZERO);
+            furthest = furthest.max(vec2(model.x, model.y).length());
+        }
+        assert!(furthest >= start);
+    }
+
+    #[test]
+    fn adjustments_stop_at_zero() {
+        assert!((adjusted(0.01, STIFFNESS_STEP) - 0.015).abs() < 1e-6);
+        assert_eq!(adjusted(0.01, -0.05), 0.0);
+        assert_eq!(adjusted(0.0, -DAMPING_STEP), 0.0);
+    }
+
+    #[test]
+    fn screenshot_names_use_the_frame_number() {
+        let dir = Path::new("screenshots");
+        assert_eq!(screenshot_path(dir, 0), Path::new("screenshots/screenshot_0.png"));
+        assert_eq!(screenshot_path(dir, 7), Path::new("screenshots/screenshot_7.png"));
+        assert_eq!(screenshot_path(dir, 1234), Path::new("screenshots/screenshot_1234.png"));
+        assert_eq!(
+            screenshot_path(Path::new("/tmp/shots"), u64::MAX),
+            Path::new("/tmp/shots/screenshot_18446744073709551615.png")
+        );
+    }
+
+    #[test]
+    fn screenshot_dir_is_created_once_and_kept() {
+        let root = std::env::temp_dir().join(format!("test-noc-screenshots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dir = root.join("nested").join("screenshots");
+        create_screenshot_dir(&dir).unwrap();
+        assert!(dir.is_dir());
+
+        // already there, with a capture in it: nothing is lost
+        fs::write(screenshot_path(&dir, 3), b"png").unwrap();
+        create_screenshot_dir(&dir).unwrap();
+        assert_eq!(fs::read(screenshot_path(&dir, 3)).unwrap(), b"png");
+
+        // a file in the way is an error, not a silent success
+        let blocked = root.join("blocked");
+        fs::write(&blocked, b"").unwrap();
+        assert!(create_screenshot_dir(&blocked).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}